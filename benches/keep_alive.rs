@@ -0,0 +1,94 @@
+//! Serves 10k small Requests over a single keep-alive connection and counts the allocations
+//! of [serve_connection] against the naive path parsing every Request with a fresh reader.
+//!
+//! Run with `cargo bench --bench keep_alive`
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use whdp::{resp_presets, serve_connection, ParseOptions, Request, Response};
+
+const REQUESTS: usize = 10_000;
+const REQUEST: &[u8] = b"GET /ping HTTP/1.1\r\nHost: localhost\r\n\r\n";
+const LAST_REQUEST: &[u8] = b"GET /ping HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn handler(_: Request) -> Response {
+    resp_presets::ok("pong")
+}
+
+/// sends the Requests one after another and waits for every Response before sending the next one,
+/// so the naive path never loses bytes read ahead by its reader
+fn client(mut stream: TcpStream) {
+    let mut answer = [0; 256];
+    for idx in 0..REQUESTS {
+        let request = if idx + 1 == REQUESTS { LAST_REQUEST } else { REQUEST };
+        stream.write_all(request).unwrap();
+        let mut read = 0;
+        while !answer[..read].ends_with(b"pong") {
+            read += stream.read(&mut answer[read..]).unwrap();
+        }
+    }
+}
+
+fn naive(mut stream: TcpStream) {
+    for _ in 0..REQUESTS {
+        let req = Request::from_reader(&mut stream).unwrap();
+        handler(req).write_to(&mut stream).unwrap();
+    }
+}
+
+fn pooled(stream: TcpStream) {
+    serve_connection(stream, &ParseOptions::new(), handler).unwrap();
+}
+
+fn run(name: &str, server: fn(TcpStream)) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let client = thread::spawn(move || client(TcpStream::connect(addr).unwrap()));
+    let (stream, _) = listener.accept().unwrap();
+    stream.set_nodelay(true).unwrap();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    server(stream);
+    let elapsed: Duration = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    client.join().unwrap();
+    println!(
+        "{:<8} {} requests in {:>8.2?}, {:>7} allocations ({:.1} per request)",
+        name,
+        REQUESTS,
+        elapsed,
+        allocations,
+        allocations as f64 / REQUESTS as f64
+    );
+}
+
+fn main() {
+    run("naive", naive);
+    run("pooled", pooled);
+}
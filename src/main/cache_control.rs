@@ -0,0 +1,375 @@
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Display, Formatter};
+
+use crate::util::{quote_if_needed, split_quoted, unquote};
+
+pub(crate) const CACHE_CONTROL: &str = "Cache-Control";
+const MAX_AGE: &str = "max-age";
+const S_MAXAGE: &str = "s-maxage";
+const MAX_STALE: &str = "max-stale";
+const MIN_FRESH: &str = "min-fresh";
+const NO_CACHE: &str = "no-cache";
+const NO_STORE: &str = "no-store";
+const NO_TRANSFORM: &str = "no-transform";
+const ONLY_IF_CACHED: &str = "only-if-cached";
+const MUST_REVALIDATE: &str = "must-revalidate";
+const PROXY_REVALIDATE: &str = "proxy-revalidate";
+const PRIVATE: &str = "private";
+const PUBLIC: &str = "public";
+const IMMUTABLE: &str = "immutable";
+const DIRECTIVE_DELIMITER: char = ',';
+const VALUE_DELIMITER: char = '=';
+
+/// Struct for the directives of the Cache-Control header
+/// ([RFC 9111](https://www.rfc-editor.org/rfc/rfc9111#section-5.2)) of a Request or Response. <br>
+/// Directive names are matched case-insensitive and the first occurrence of a directive wins.
+/// A `no-cache` or `private` with a list of field names still counts as set.
+/// Unknown directives (and the ones with a malformed number) are kept in [get_other](CacheControl::get_other)
+///
+/// Example:
+/// ```
+/// use whdp::CacheControl;
+///
+/// let cache_control = CacheControl::parse("max-age=3600, no-cache, private");
+/// assert_eq!(cache_control.get_max_age(), Some(3600));
+/// assert!(cache_control.is_no_cache());
+/// assert!(cache_control.is_private());
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct CacheControl {
+    max_age: Option<u64>,
+    s_maxage: Option<u64>,
+    max_stale: Option<Option<u64>>,
+    min_fresh: Option<u64>,
+    no_cache: bool,
+    no_store: bool,
+    no_transform: bool,
+    only_if_cached: bool,
+    must_revalidate: bool,
+    proxy_revalidate: bool,
+    private: bool,
+    public: bool,
+    immutable: bool,
+    other: BTreeMap<String, Option<String>>,
+}
+
+impl CacheControl {
+    /// creates a new CacheControl without any directive
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// replaces the seconds of `max-age`
+    pub fn with_max_age(mut self, seconds: u64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+    /// replaces the seconds of `s-maxage`
+    pub fn with_s_maxage(mut self, seconds: u64) -> Self {
+        self.s_maxage = Some(seconds);
+        self
+    }
+    /// replaces `max-stale` ([None] accepts any stale Response)
+    pub fn with_max_stale(mut self, seconds: Option<u64>) -> Self {
+        self.max_stale = Some(seconds);
+        self
+    }
+    /// replaces the seconds of `min-fresh`
+    pub fn with_min_fresh(mut self, seconds: u64) -> Self {
+        self.min_fresh = Some(seconds);
+        self
+    }
+    /// replaces the current value with the no_cache parameter
+    pub fn with_no_cache(mut self, no_cache: bool) -> Self {
+        self.no_cache = no_cache;
+        self
+    }
+    /// replaces the current value with the no_store parameter
+    pub fn with_no_store(mut self, no_store: bool) -> Self {
+        self.no_store = no_store;
+        self
+    }
+    /// replaces the current value with the no_transform parameter
+    pub fn with_no_transform(mut self, no_transform: bool) -> Self {
+        self.no_transform = no_transform;
+        self
+    }
+    /// replaces the current value with the only_if_cached parameter
+    pub fn with_only_if_cached(mut self, only_if_cached: bool) -> Self {
+        self.only_if_cached = only_if_cached;
+        self
+    }
+    /// replaces the current value with the must_revalidate parameter
+    pub fn with_must_revalidate(mut self, must_revalidate: bool) -> Self {
+        self.must_revalidate = must_revalidate;
+        self
+    }
+    /// replaces the current value with the proxy_revalidate parameter
+    pub fn with_proxy_revalidate(mut self, proxy_revalidate: bool) -> Self {
+        self.proxy_revalidate = proxy_revalidate;
+        self
+    }
+    /// replaces the current value with the private parameter
+    pub fn with_private(mut self, private: bool) -> Self {
+        self.private = private;
+        self
+    }
+    /// replaces the current value with the public parameter
+    pub fn with_public(mut self, public: bool) -> Self {
+        self.public = public;
+        self
+    }
+    /// replaces the current value with the immutable parameter
+    pub fn with_immutable(mut self, immutable: bool) -> Self {
+        self.immutable = immutable;
+        self
+    }
+    /// adds a directive that has no field of its own (like `stale-while-revalidate`),
+    /// the name is stored lowercase
+    pub fn with_directive(mut self, name: &str, value: Option<&str>) -> Self {
+        self.other.insert(name.to_ascii_lowercase(), value.map(String::from));
+        self
+    }
+    /// parses the value of the Cache-Control header (empty or malformed directives are skipped)
+    pub fn parse(value: &str) -> Self {
+        let mut cache_control = Self::default();
+        let mut seen: Vec<String> = Vec::new();
+        for directive in split_quoted(value, DIRECTIVE_DELIMITER) {
+            let (name, value) = match directive.split_once(VALUE_DELIMITER) {
+                Some((name, value)) => (name.trim().to_ascii_lowercase(), Some(unquote(value.trim()))),
+                None => (directive.trim().to_ascii_lowercase(), None),
+            };
+            if name.is_empty() || seen.contains(&name) {
+                continue;
+            }
+            seen.push(name.clone());
+            cache_control.apply(name, value);
+        }
+        cache_control
+    }
+    fn apply(&mut self, name: String, value: Option<String>) {
+        let seconds = value.as_deref().and_then(|value| value.parse::<u64>().ok());
+        match name.as_str() {
+            MAX_AGE if seconds.is_some() => self.max_age = seconds,
+            S_MAXAGE if seconds.is_some() => self.s_maxage = seconds,
+            MIN_FRESH if seconds.is_some() => self.min_fresh = seconds,
+            MAX_STALE if value.is_none() || seconds.is_some() => self.max_stale = Some(seconds),
+            NO_CACHE => self.no_cache = true,
+            NO_STORE => self.no_store = true,
+            NO_TRANSFORM => self.no_transform = true,
+            ONLY_IF_CACHED => self.only_if_cached = true,
+            MUST_REVALIDATE => self.must_revalidate = true,
+            PROXY_REVALIDATE => self.proxy_revalidate = true,
+            PRIVATE => self.private = true,
+            PUBLIC => self.public = true,
+            IMMUTABLE => self.immutable = true,
+            _ => {
+                self.other.insert(name, value);
+            }
+        }
+    }
+    /// returns the seconds of `max-age`
+    pub fn get_max_age(&self) -> Option<u64> {
+        self.max_age
+    }
+    /// returns the seconds of `s-maxage` (the max-age for shared caches)
+    pub fn get_s_maxage(&self) -> Option<u64> {
+        self.s_maxage
+    }
+    /// returns `max-stale` of a Request: [None] if it is absent,
+    /// `Some(None)` if any stale Response is accepted and otherwise the accepted seconds
+    pub fn get_max_stale(&self) -> Option<Option<u64>> {
+        self.max_stale
+    }
+    /// returns the seconds of `min-fresh` of a Request
+    pub fn get_min_fresh(&self) -> Option<u64> {
+        self.min_fresh
+    }
+    /// looks if `no-cache` is set (a stored Response has to be revalidated before it is used)
+    pub fn is_no_cache(&self) -> bool {
+        self.no_cache
+    }
+    /// looks if `no-store` is set (nothing may be stored)
+    pub fn is_no_store(&self) -> bool {
+        self.no_store
+    }
+    /// looks if `no-transform` is set
+    pub fn is_no_transform(&self) -> bool {
+        self.no_transform
+    }
+    /// looks if `only-if-cached` is set on a Request
+    pub fn is_only_if_cached(&self) -> bool {
+        self.only_if_cached
+    }
+    /// looks if `must-revalidate` is set
+    pub fn is_must_revalidate(&self) -> bool {
+        self.must_revalidate
+    }
+    /// looks if `proxy-revalidate` is set
+    pub fn is_proxy_revalidate(&self) -> bool {
+        self.proxy_revalidate
+    }
+    /// looks if `private` is set (only a private cache may store the Response)
+    pub fn is_private(&self) -> bool {
+        self.private
+    }
+    /// looks if `public` is set
+    pub fn is_public(&self) -> bool {
+        self.public
+    }
+    /// looks if `immutable` is set
+    pub fn is_immutable(&self) -> bool {
+        self.immutable
+    }
+    /// returns the unknown directives with their unquoted values
+    pub fn get_other(&self) -> &BTreeMap<String, Option<String>> {
+        &self.other
+    }
+    /// looks if there isn't any directive
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Display for CacheControl {
+    /// writes the directives in a fixed order (the field names of `no-cache` and `private` are lost)
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut directives: Vec<String> = Vec::new();
+        let flags = [
+            (self.public, PUBLIC),
+            (self.private, PRIVATE),
+            (self.no_cache, NO_CACHE),
+            (self.no_store, NO_STORE),
+            (self.no_transform, NO_TRANSFORM),
+            (self.only_if_cached, ONLY_IF_CACHED),
+            (self.must_revalidate, MUST_REVALIDATE),
+            (self.proxy_revalidate, PROXY_REVALIDATE),
+            (self.immutable, IMMUTABLE),
+        ];
+        directives.extend(flags.iter().filter(|(set, _)| *set).map(|(_, name)| String::from(*name)));
+        let seconds = [(self.max_age, MAX_AGE), (self.s_maxage, S_MAXAGE), (self.min_fresh, MIN_FRESH)];
+        for (value, name) in seconds {
+            if let Some(value) = value {
+                directives.push(format!("{}{}{}", name, VALUE_DELIMITER, value));
+            }
+        }
+        match self.max_stale {
+            Some(Some(value)) => directives.push(format!("{}{}{}", MAX_STALE, VALUE_DELIMITER, value)),
+            Some(None) => directives.push(String::from(MAX_STALE)),
+            None => {}
+        }
+        for (name, value) in &self.other {
+            match value {
+                Some(value) => directives.push(format!("{}{}{}", name, VALUE_DELIMITER, quote_if_needed(value))),
+                None => directives.push(name.clone()),
+            }
+        }
+        write!(f, "{}", directives.join(", "))
+    }
+}
+
+impl Debug for CacheControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::{CacheControl, Destruct, HttpVersion, Request, resp_presets, Response, status_presets};
+
+    #[test]
+    fn directives() {
+        let cache_control = CacheControl::parse("public, MAX-AGE=\"60\", s-maxage=120, must-revalidate, immutable");
+        assert!(cache_control.is_public());
+        assert_eq!(cache_control.get_max_age(), Some(60));
+        assert_eq!(cache_control.get_s_maxage(), Some(120));
+        assert!(cache_control.is_must_revalidate());
+        assert!(cache_control.is_immutable());
+        assert!(!cache_control.is_no_store());
+        assert!(cache_control.get_other().is_empty());
+    }
+
+    #[test]
+    fn edge_cases() {
+        let cache_control = CacheControl::parse("no-cache=\"Set-Cookie, Authorization\", max-age=10, max-age=20, max-age=x, ,community=\"UCI\", stale-while-revalidate=30");
+        assert!(cache_control.is_no_cache());
+        assert_eq!(cache_control.get_max_age(), Some(10));
+        assert_eq!(cache_control.get_other().get("community"), Some(&Some(String::from("UCI"))));
+        assert_eq!(cache_control.get_other().get("stale-while-revalidate"), Some(&Some(String::from("30"))));
+        assert_eq!(cache_control.get_other().len(), 2);
+
+        let cache_control = CacheControl::parse("s-maxage=abc");
+        assert_eq!(cache_control.get_s_maxage(), None);
+        assert_eq!(cache_control.get_other().get("s-maxage"), Some(&Some(String::from("abc"))));
+        assert!(CacheControl::parse("").is_empty());
+        assert_eq!(CacheControl::parse("max-stale").get_max_stale(), Some(None));
+        assert_eq!(CacheControl::parse("max-stale=5").get_max_stale(), Some(Some(5)));
+    }
+
+    #[test]
+    fn round_trip() {
+        let cache_control = CacheControl::parse("private, no-store, max-age=0, x-ext=\"a b\"");
+        assert_eq!(cache_control.to_string(), "private, no-store, max-age=0, x-ext=\"a b\"");
+        assert_eq!(CacheControl::parse(&cache_control.to_string()), cache_control);
+    }
+
+    #[test]
+    fn set() {
+        let cc = CacheControl::new()
+            .with_public(true)
+            .with_max_age(3600)
+            .with_must_revalidate(true)
+            .with_directive("stale-while-revalidate", Some("60"));
+        let resp = Response::builder()
+            .with_status(status_presets::ok())
+            .with_version(HttpVersion::OnePointOne)
+            .with_cache_control(cc.clone())
+            .with_empty_body()
+            .build()
+            .unwrap();
+        assert_eq!(
+            resp.get_header("Cache-Control").unwrap(),
+            "public, must-revalidate, max-age=3600, stale-while-revalidate=60"
+        );
+        assert_eq!(resp.cache_control(), cc);
+
+        let mut resp = resp_presets::ok("private");
+        resp.set_cache_control(CacheControl::new().with_private(true).with_no_store(true));
+        assert_eq!(resp.get_header("Cache-Control").unwrap(), "private, no-store");
+        resp.set_cache_control(CacheControl::new());
+        assert!(resp.get_header("Cache-Control").is_none());
+    }
+
+    #[test]
+    fn set_replaces_any_casing() {
+        let mut resp = resp_presets::ok("stored");
+        resp.add_header((String::from("cache-control"), String::from("no-store")));
+        resp.set_cache_control(CacheControl::new().with_max_age(60));
+        assert_eq!(resp.get_headers().get("Cache-Control").unwrap(), "max-age=60");
+        assert!(!resp.get_headers().contains_key("cache-control"));
+        assert_eq!(resp.to_raw_string(true).to_ascii_lowercase().matches("cache-control").count(), 1);
+        resp.set_cache_control(CacheControl::new());
+        assert!(resp.get_headers().keys().all(|key| !key.eq_ignore_ascii_case("Cache-Control")));
+
+        let mut headers = BTreeMap::new();
+        headers.insert(String::from("CACHE-CONTROL"), String::from("no-store"));
+        let builder = Response::builder().with_headers(headers).with_cache_control(CacheControl::new().with_public(true));
+        let (_, _, headers, _) = builder.destruct();
+        assert_eq!(headers.unwrap().into_iter().collect::<Vec<_>>(), vec![(String::from("Cache-Control"), String::from("public"))]);
+    }
+
+    #[test]
+    fn messages() {
+        let mut resp = resp_presets::ok("cached");
+        assert!(resp.cache_control().is_empty());
+        resp.add_header((String::from("cache-control"), String::from("max-age=3600, no-cache, private")));
+        assert_eq!(resp.cache_control().get_max_age(), Some(3600));
+        assert!(resp.cache_control().is_private());
+
+        let req = Request::try_from("GET / HTTP/1.1\nCache-Control: no-cache, max-stale\n\n").unwrap();
+        assert!(req.cache_control().is_no_cache());
+        assert_eq!(req.cache_control().get_max_stale(), Some(None));
+    }
+}
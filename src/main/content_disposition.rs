@@ -0,0 +1,224 @@
+use std::fmt::{Debug, Display, Formatter};
+
+use crate::form::percent_decode;
+use crate::util::{quote_if_needed, split_quoted, unquote};
+
+pub(crate) const CONTENT_DISPOSITION: &str = "Content-Disposition";
+const NAME: &str = "name";
+const FILENAME: &str = "filename";
+const FILENAME_EXT: &str = "filename*";
+const UTF8: &str = "UTF-8";
+const ISO_8859_1: &str = "ISO-8859-1";
+const PARAMETER_DELIMITER: char = ';';
+const EXT_DELIMITER: char = '\'';
+const ATTR_CHARS: &str = "!#$&+-.^_`|~";
+
+/// Struct for the value of the Content-Disposition header
+/// ([RFC 6266](https://www.rfc-editor.org/rfc/rfc6266)) with its type
+/// (like `attachment` or `form-data`), the field `name` and the `filename`. <br>
+/// An encoded `filename*` ([RFC 5987](https://www.rfc-editor.org/rfc/rfc5987)) is preferred over a plain `filename`
+///
+/// Example:
+/// ```
+/// use whdp::parse_content_disposition;
+///
+/// let disposition = parse_content_disposition("attachment; filename=\"EURO rates.txt\"; filename*=UTF-8''%e2%82%ac%20rates.txt").unwrap();
+/// assert_eq!(disposition.get_disposition_type(), "attachment");
+/// assert_eq!(disposition.get_filename(), Some("€ rates.txt"));
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ContentDisposition {
+    disposition_type: String,
+    name: Option<String>,
+    filename: Option<String>,
+}
+
+impl ContentDisposition {
+    /// creates a new ContentDisposition of the type without a name and filename
+    pub fn new(disposition_type: &str) -> Self {
+        Self {
+            disposition_type: String::from(disposition_type),
+            name: None,
+            filename: None,
+        }
+    }
+    /// replaces the name of the form field
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(String::from(name));
+        self
+    }
+    /// replaces the filename
+    pub fn with_filename(mut self, filename: &str) -> Self {
+        self.filename = Some(String::from(filename));
+        self
+    }
+    /// returns the lowercase disposition type (like `inline`, `attachment` or `form-data`)
+    pub fn get_disposition_type(&self) -> &str {
+        &self.disposition_type
+    }
+    /// returns the name of the form field
+    pub fn get_name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    /// returns the decoded filename
+    pub fn get_filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
+    /// looks if the disposition type is `attachment` (the content should be downloaded)
+    pub fn is_attachment(&self) -> bool {
+        self.disposition_type == "attachment"
+    }
+}
+
+impl Display for ContentDisposition {
+    /// writes the header value, a filename that isn't ASCII is additionally written as `filename*`
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.disposition_type)?;
+        if let Some(name) = &self.name {
+            write!(f, "{} {}={}", PARAMETER_DELIMITER, NAME, quote_param(name))?;
+        }
+        if let Some(filename) = &self.filename {
+            let fallback: String = filename.chars().map(|char| if char.is_ascii() { char } else { '_' }).collect();
+            write!(f, "{} {}={}", PARAMETER_DELIMITER, FILENAME, quote_param(&fallback))?;
+            if !filename.is_ascii() {
+                write!(f, "{} {}={}''{}", PARAMETER_DELIMITER, FILENAME_EXT, UTF8, encode_ext_value(filename))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Debug for ContentDisposition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// writes a parameter value (like a name or filename) as a token or as a quoted-string
+/// with escaped quotes and backslashes. <br>
+/// CR and LF can't be part of a header value, so they are percent-encoded like browsers do
+pub(crate) fn quote_param(value: &str) -> String {
+    quote_if_needed(&value.replace('\r', "%0D").replace('\n', "%0A"))
+}
+
+/// Parses the value of the Content-Disposition header. <br>
+/// Parameter names are matched case-insensitive, quoted values are unescaped
+/// and `filename*` is decoded from UTF-8 or ISO-8859-1 (it wins over `filename`).
+/// Returns [None] if the disposition type is missing
+///
+/// Example:
+/// ```
+/// use whdp::parse_content_disposition;
+///
+/// let disposition = parse_content_disposition("form-data; name=\"avatar\"; filename=\"me.png\"").unwrap();
+/// assert_eq!(disposition.get_name(), Some("avatar"));
+/// assert_eq!(disposition.get_filename(), Some("me.png"));
+/// ```
+pub fn parse_content_disposition(value: &str) -> Option<ContentDisposition> {
+    let mut split = split_quoted(value, PARAMETER_DELIMITER).into_iter();
+    let disposition_type = split.next()?.trim();
+    if disposition_type.is_empty() || disposition_type.contains('=') {
+        return None;
+    }
+    let mut disposition = ContentDisposition::new(&disposition_type.to_ascii_lowercase());
+    let mut ext_filename = None;
+    for param in split {
+        let Some((key, value)) = param.split_once('=') else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        if key.eq_ignore_ascii_case(NAME) {
+            disposition.name.get_or_insert_with(|| unquote(value));
+        } else if key.eq_ignore_ascii_case(FILENAME) {
+            disposition.filename.get_or_insert_with(|| unquote(value));
+        } else if key.eq_ignore_ascii_case(FILENAME_EXT) && ext_filename.is_none() {
+            ext_filename = decode_ext_value(value);
+        }
+    }
+    if ext_filename.is_some() {
+        disposition.filename = ext_filename;
+    }
+    Some(disposition)
+}
+
+/// decodes an ext-value `charset'language'value` with a percent-encoded value
+/// (only the charsets UTF-8 and ISO-8859-1 are supported)
+fn decode_ext_value(value: &str) -> Option<String> {
+    let mut split = value.splitn(3, EXT_DELIMITER);
+    let (charset, _language, encoded) = (split.next()?, split.next()?, split.next()?);
+    let decoded = percent_decode(encoded, false)?;
+    if charset.eq_ignore_ascii_case(UTF8) {
+        String::from_utf8(decoded).ok()
+    } else if charset.eq_ignore_ascii_case(ISO_8859_1) {
+        Some(decoded.into_iter().map(char::from).collect())
+    } else {
+        None
+    }
+}
+
+/// percent-encodes everything of the UTF-8 bytes that isn't an attr-char
+fn encode_ext_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || ATTR_CHARS.contains(char::from(byte)) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ContentDisposition, parse_content_disposition, resp_presets};
+
+    #[test]
+    fn plain_and_quoted() {
+        let disposition = parse_content_disposition("Attachment; FILENAME=\"x.pdf\"").unwrap();
+        assert_eq!(disposition.get_disposition_type(), "attachment");
+        assert!(disposition.is_attachment());
+        assert_eq!(disposition.get_filename(), Some("x.pdf"));
+        assert_eq!(disposition.get_name(), None);
+
+        let disposition = parse_content_disposition("form-data; name=field; filename=\"a \\\"quoted\\\"; name.txt\"").unwrap();
+        assert_eq!(disposition.get_name(), Some("field"));
+        assert_eq!(disposition.get_filename(), Some("a \"quoted\"; name.txt"));
+
+        assert_eq!(parse_content_disposition("inline").unwrap().get_disposition_type(), "inline");
+        assert!(parse_content_disposition("").is_none());
+        assert!(parse_content_disposition("filename=x.pdf").is_none());
+    }
+
+    #[test]
+    fn encoded_filename() {
+        let disposition = parse_content_disposition("attachment; filename*=UTF-8''na%C3%AFve%20file.txt; filename=naive.txt").unwrap();
+        assert_eq!(disposition.get_filename(), Some("naïve file.txt"));
+        let disposition = parse_content_disposition("attachment; filename*=iso-8859-1'de'%E4pfel.txt").unwrap();
+        assert_eq!(disposition.get_filename(), Some("äpfel.txt"));
+        let disposition = parse_content_disposition("attachment; filename=fallback.txt; filename*=UTF-8''%FF%zz").unwrap();
+        assert_eq!(disposition.get_filename(), Some("fallback.txt"));
+        let disposition = parse_content_disposition("attachment; filename=fallback.txt; filename*=KOI8-R''%C1").unwrap();
+        assert_eq!(disposition.get_filename(), Some("fallback.txt"));
+        let disposition = parse_content_disposition("attachment; filename*=UTF-8''c++%20notes.txt").unwrap();
+        assert_eq!(disposition.get_filename(), Some("c++ notes.txt"));
+    }
+
+    #[test]
+    fn line_breaks() {
+        let disposition = ContentDisposition::new("attachment").with_filename("a\r\nSet-Cookie: x=1");
+        assert_eq!(disposition.to_string(), "attachment; filename=\"a%0D%0ASet-Cookie: x=1\"");
+    }
+
+    #[test]
+    fn round_trip() {
+        let disposition = ContentDisposition::new("attachment").with_filename("€ rates.txt");
+        assert_eq!(disposition.to_string(), "attachment; filename=\"_ rates.txt\"; filename*=UTF-8''%E2%82%AC%20rates.txt");
+        assert_eq!(parse_content_disposition(&disposition.to_string()).unwrap(), disposition);
+
+        let mut resp = resp_presets::ok("%PDF");
+        resp.set_content_disposition(&ContentDisposition::new("attachment").with_filename("x.pdf"));
+        assert_eq!(resp.get_header("Content-Disposition").unwrap(), "attachment; filename=x.pdf");
+        assert_eq!(resp.get_content_disposition().unwrap().get_filename(), Some("x.pdf"));
+    }
+}
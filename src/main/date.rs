@@ -0,0 +1,191 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const SECONDS_PER_DAY: u64 = 86_400;
+const GMT: &str = "GMT";
+
+/// Formats the [SystemTime] as an HTTP date in the IMF-fixdate format
+/// (for example `Sun, 06 Nov 1994 08:49:37 GMT`). <br>
+/// Times before the unix epoch are formatted as the epoch
+pub fn format_http_date(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let days = secs / SECONDS_PER_DAY;
+    let secs_of_day = secs % SECONDS_PER_DAY;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} {}",
+        DAYS[(days % 7) as usize],
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
+        GMT
+    )
+}
+
+/// Parses an HTTP date into a [SystemTime]. <br>
+/// Besides the IMF-fixdate format the obsolete RFC 850
+/// (`Sunday, 06-Nov-94 08:49:37 GMT`) and asctime (`Sun Nov  6 08:49:37 1994`)
+/// formats are accepted as well ([RFC 7231](https://www.rfc-editor.org/rfc/rfc7231#section-7.1.1.1))
+pub fn parse_http_date(str: &str) -> Option<SystemTime> {
+    let parts: Vec<&str> = str.split_whitespace().collect();
+    let (year, month, day, time) = match parts.as_slice() {
+        [_, day, month, year, time, GMT] => (year.parse().ok()?, parse_month(month)?, day.parse().ok()?, *time),
+        [_, date, time, GMT] => {
+            let mut date = date.split('-');
+            let day = date.next()?.parse().ok()?;
+            let month = parse_month(date.next()?)?;
+            let year: u64 = date.next()?.parse().ok()?;
+            // two digit years are interpreted like in RFC 6265
+            let year = match year {
+                0..=69 => year + 2000,
+                70..=99 => year + 1900,
+                _ => year,
+            };
+            (year, month, day, *time)
+        }
+        [_, month, day, time, year] => (year.parse().ok()?, parse_month(month)?, day.parse().ok()?, *time),
+        _ => return None,
+    };
+    if year < 1970 || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+    let mut time = time.split(':').map(|part| part.parse::<u64>().ok());
+    let (hours, minutes, seconds) = (time.next()??, time.next()??, time.next()??);
+    if time.next().is_some() || hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    let secs = days * SECONDS_PER_DAY + hours * 3600 + minutes * 60 + seconds;
+    UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+}
+
+/// Enum for the value of the Expires header. <br>
+/// An invalid date (like `0` or `-1`) means the Response is already expired
+/// ([RFC 7234](https://www.rfc-editor.org/rfc/rfc7234#section-5.3))
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Expires {
+    /// the Response is stale after this time
+    At(SystemTime),
+    /// the header isn't a valid HTTP date, so the Response is already expired
+    Expired,
+}
+
+impl Expires {
+    /// parses the value of the Expires header (never fails, see [Expired](Expires::Expired))
+    pub fn parse(str: &str) -> Self {
+        parse_http_date(str.trim()).map_or(Expires::Expired, Expires::At)
+    }
+    /// looks if the Response is expired at the given point in time
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        match self {
+            Expires::At(time) => *time <= now,
+            Expires::Expired => true,
+        }
+    }
+}
+
+fn parse_month(str: &str) -> Option<u64> {
+    MONTHS
+        .iter()
+        .position(|month| month.eq_ignore_ascii_case(str))
+        .map(|idx| idx as u64 + 1)
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// algorithms from http://howardhinnant.github.io/date_algorithms.html
+// restricted to dates after the unix epoch
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::{format_http_date, parse_http_date, Expires};
+
+    #[test]
+    fn formats() {
+        let time = UNIX_EPOCH + Duration::from_secs(784_111_777);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"), Some(time));
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), Some(time));
+        assert_eq!(parse_http_date("Sun Nov  6 08:49:37 1994"), Some(time));
+        assert_eq!(format_http_date(time), "Sun, 06 Nov 1994 08:49:37 GMT");
+    }
+
+    #[test]
+    fn round_trip() {
+        let time = UNIX_EPOCH + Duration::from_secs(1_700_052_171);
+        assert_eq!(format_http_date(time), "Wed, 15 Nov 2023 12:42:51 GMT");
+        assert_eq!(parse_http_date(&format_http_date(time)), Some(time));
+        assert_eq!(format_http_date(UNIX_EPOCH), "Thu, 01 Jan 1970 00:00:00 GMT");
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(parse_http_date("-1"), None);
+        assert_eq!(parse_http_date("0"), None);
+        assert_eq!(parse_http_date("Sun, 06 Foo 1994 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 25:49:37 GMT"), None);
+    }
+
+    #[test]
+    fn day_of_month() {
+        assert_eq!(parse_http_date("Tue, 31 Feb 2023 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Thu, 31-Apr-24 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Wed Feb 29 08:49:37 2023"), None);
+        assert_eq!(parse_http_date("Mon, 29 Feb 2100 08:49:37 GMT"), None);
+        assert_eq!(format_http_date(parse_http_date("Thu, 29 Feb 2024 08:49:37 GMT").unwrap()), "Thu, 29 Feb 2024 08:49:37 GMT");
+        assert_eq!(format_http_date(parse_http_date("Tue, 29 Feb 2000 08:49:37 GMT").unwrap()), "Tue, 29 Feb 2000 08:49:37 GMT");
+        assert!(parse_http_date("Sat, 31 Dec 2022 08:49:37 GMT").is_some());
+    }
+
+    #[test]
+    fn expires() {
+        let time = UNIX_EPOCH + Duration::from_secs(784_111_777);
+        assert_eq!(Expires::parse("Sun, 06 Nov 1994 08:49:37 GMT"), Expires::At(time));
+        assert_eq!(Expires::parse("Sunday, 06-Nov-94 08:49:37 GMT"), Expires::At(time));
+        assert_eq!(Expires::parse(" Sun Nov  6 08:49:37 1994 "), Expires::At(time));
+        assert_eq!(Expires::parse("0"), Expires::Expired);
+        assert_eq!(Expires::parse("-1"), Expires::Expired);
+        assert!(Expires::At(time).is_expired_at(time));
+        assert!(!Expires::At(time).is_expired_at(UNIX_EPOCH));
+        assert!(Expires::Expired.is_expired_at(UNIX_EPOCH));
+    }
+}
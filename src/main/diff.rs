@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Display, Formatter};
+
+const UNSET: &str = "(unset)";
+
+/// A single difference between two builders with the values before and after as Strings. <br>
+/// Header differences are reported per header (the name is compared case-insensitive)
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct FieldDiff {
+    field: &'static str,
+    header: Option<String>,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+impl FieldDiff {
+    /// returns the name of the field that differs (`method`, `uri`, `version`, `status`, `headers` or `body`)
+    pub fn get_field(&self) -> &str {
+        self.field
+    }
+    /// returns the name of the header that differs (only for the `headers` field)
+    pub fn get_header(&self) -> Option<&str> {
+        self.header.as_deref()
+    }
+    /// returns the value before or [None] if it was unset
+    pub fn get_before(&self) -> Option<&str> {
+        self.before.as_deref()
+    }
+    /// returns the value after or [None] if it is unset
+    pub fn get_after(&self) -> Option<&str> {
+        self.after.as_deref()
+    }
+}
+
+impl Display for FieldDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.header {
+            Some(header) => write!(f, "{} {}", self.field, header)?,
+            None => write!(f, "{}", self.field)?,
+        }
+        write!(
+            f,
+            ": {} -> {}",
+            self.before.as_deref().unwrap_or(UNSET),
+            self.after.as_deref().unwrap_or(UNSET)
+        )
+    }
+}
+
+impl Debug for FieldDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// adds a [FieldDiff] if the values differ
+pub(crate) fn diff_field<T: PartialEq + Display>(
+    diffs: &mut Vec<FieldDiff>,
+    field: &'static str,
+    before: &Option<T>,
+    after: &Option<T>,
+) {
+    if before != after {
+        diffs.push(FieldDiff {
+            field,
+            header: None,
+            before: before.as_ref().map(T::to_string),
+            after: after.as_ref().map(T::to_string),
+        });
+    }
+}
+
+/// adds a [FieldDiff] for every header that was added, removed or changed (unset headers count as empty)
+pub(crate) fn diff_headers(
+    diffs: &mut Vec<FieldDiff>,
+    field: &'static str,
+    before: &Option<BTreeMap<String, String>>,
+    after: &Option<BTreeMap<String, String>>,
+) {
+    let before = lowercase_keys(before);
+    let after = lowercase_keys(after);
+    let mut names: Vec<&String> = before.keys().chain(after.keys()).collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        let (old, new) = (before.get(name), after.get(name));
+        if old.map(|(_, value)| value) != new.map(|(_, value)| value) {
+            let (key, _) = old.or(new).copied().unwrap_or_default();
+            diffs.push(FieldDiff {
+                field,
+                header: Some(String::from(key)),
+                before: old.map(|(_, value)| String::from(*value)),
+                after: new.map(|(_, value)| String::from(*value)),
+            });
+        }
+    }
+}
+
+fn lowercase_keys(headers: &Option<BTreeMap<String, String>>) -> BTreeMap<String, (&str, &str)> {
+    headers
+        .iter()
+        .flatten()
+        .map(|(key, value)| (key.to_ascii_lowercase(), (key.as_str(), value.as_str())))
+        .collect()
+}
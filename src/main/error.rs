@@ -1,20 +1,68 @@
+use std::cmp::Ordering;
+use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 use crate::util::Destruct;
 
 const MESSAGE: &str = "Failure:";
+const LINE: &str = "Line:";
+const OFFSET: &str = "Offset:";
 
 /// ### Error struct for HTTP Parsing
 ///
 /// contains a [kind] for automatically handling the error <br>
-/// and an optional [message] for further information
+/// and an optional [message], [line] and [offset] for further information
 ///
 /// [kind]: crate::HttpParseError::get_kind
 /// [message]: crate::HttpParseError::get_msg
+/// [line]: crate::HttpParseError::get_line
+/// [offset]: crate::HttpParseError::get_offset
+///
+/// The constructors ([new](HttpParseError::new) and [builder](HttpParseError::builder)) are `const`
+/// so errors can be prepared in constants. <br>
+/// Every [ParseErrorKind] has a shorthand constructor with a message (like [request](HttpParseError::request)).
+/// Code outside of this crate returning a HttpParseError (for example for a body it couldn't decode)
+/// should use the one matching the failed part, [body](HttpParseError::body) for a body
+/// and [util](HttpParseError::util) if nothing else fits,
+/// and keep the underlying error with [with_source](HttpParseError::with_source)
 #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Hash,Default)]
 pub struct HttpParseError {
     kind: ParseErrorKind,
     msg: Option<String>,
+    line: Option<usize>,
+    offset: Option<usize>,
+    source: Option<ErrorSource>,
+}
+
+/// the underlying error of a [HttpParseError]
+/// (two sources are always equal, so only the presence of a source is compared or hashed)
+#[derive(Clone)]
+struct ErrorSource(Arc<dyn Error + Send + Sync>);
+
+impl PartialEq for ErrorSource {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ErrorSource {}
+
+impl PartialOrd for ErrorSource {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ErrorSource {
+    fn cmp(&self, _other: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+impl Hash for ErrorSource {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
 }
 
 impl HttpParseError {
@@ -24,8 +72,83 @@ impl HttpParseError {
         Self {
             kind: ParseErrorKind::Unkown,
             msg: None,
+            line: None,
+            offset: None,
+            source: None,
         }
     }
+    /// creates a [Method](ParseErrorKind::Method) error with the message
+    pub fn method(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::Method).message(msg).build()
+    }
+    /// creates a [Version](ParseErrorKind::Version) error with the message
+    pub fn version(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::Version).message(msg).build()
+    }
+    /// creates a [Req](ParseErrorKind::Req) error with the message
+    pub fn request(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::Req).message(msg).build()
+    }
+    /// creates a [Status](ParseErrorKind::Status) error with the message
+    pub fn status(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::Status).message(msg).build()
+    }
+    /// creates a [Resp](ParseErrorKind::Resp) error with the message
+    pub fn response(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::Resp).message(msg).build()
+    }
+    /// creates a [Body](ParseErrorKind::Body) error with the message
+    pub fn body(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::Body).message(msg).build()
+    }
+    /// creates a [Length](ParseErrorKind::Length) error with the message
+    pub fn length(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::Length).message(msg).build()
+    }
+    /// creates a [Timeout](ParseErrorKind::Timeout) error with the message
+    pub fn timeout(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::Timeout).message(msg).build()
+    }
+    /// creates an [Unsupported](ParseErrorKind::Unsupported) error with the message
+    pub fn unsupported(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::Unsupported).message(msg).build()
+    }
+    /// creates a [HeaderFieldsTooLarge](ParseErrorKind::HeaderFieldsTooLarge) error with the message
+    pub fn header_fields_too_large(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::HeaderFieldsTooLarge).message(msg).build()
+    }
+    /// creates a [VersionNotSupported](ParseErrorKind::VersionNotSupported) error with the message
+    pub fn version_not_supported(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::VersionNotSupported).message(msg).build()
+    }
+    /// creates a [HostNotAllowed](ParseErrorKind::HostNotAllowed) error with the message
+    pub fn host_not_allowed(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::HostNotAllowed).message(msg).build()
+    }
+    /// creates a [NotHttp](ParseErrorKind::NotHttp) error with the message
+    pub fn not_http(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::NotHttp).message(msg).build()
+    }
+    /// creates a [Util](ParseErrorKind::Util) error with the message
+    pub fn util(msg: impl Into<String>) -> Self {
+        Self::builder(ParseErrorKind::Util).message(msg).build()
+    }
+    /// creates a [Util](ParseErrorKind::Util) error for a malformed header with the message
+    /// (there is no separate kind for headers)
+    pub fn header(msg: impl Into<String>) -> Self {
+        Self::util(msg)
+    }
+    /// keeps the underlying error, which is returned by [source](Error::source)
+    /// (only whether there is a source is compared or hashed, not the source itself)
+    pub fn with_source(mut self, err: impl Error + Send + Sync + 'static) -> Self {
+        self.source = Some(ErrorSource(Arc::new(err)));
+        self
+    }
+    /// Creates a new Instance of a [HttpParseErrorBuilder]
+    /// to "construct" a HttpParseError of the given [ParseErrorKind]
+    pub const fn builder(kind: ParseErrorKind) -> HttpParseErrorBuilder {
+        HttpParseErrorBuilder::new(kind)
+    }
     /// get the [ParseErrorKind] of this Error
     pub const fn get_kind(&self) -> &ParseErrorKind {
         &self.kind
@@ -37,36 +160,46 @@ impl HttpParseError {
     }
     /// get the Message of this Error
     pub fn get_msg(&self) -> Option<&str> {
-        self.msg.as_ref().map(|s|s.as_str())
+        self.msg.as_deref()
+    }
+    /// get the line (starting at 1) where the Error occurred
+    pub const fn get_line(&self) -> Option<usize> {
+        self.line
+    }
+    /// get the byte offset where the Error occurred
+    pub const fn get_offset(&self) -> Option<usize> {
+        self.offset
     }
 }
 
 impl From<ParseErrorKind> for HttpParseError {
     fn from(value: ParseErrorKind) -> Self {
-        Self {
-            kind: value,
-            msg: None,
-        }
+        Self::builder(value).build()
     }
 }
 
 impl From<(ParseErrorKind, &str)> for HttpParseError {
     fn from(value: (ParseErrorKind, &str)) -> Self {
-        let mut err = Self::from(value.0);
-        err.with_msg(value.1);
-        err
+        Self::builder(value.0).message(value.1).build()
     }
 }
 
 impl From<(ParseErrorKind, String)> for HttpParseError {
     fn from(value: (ParseErrorKind, String)) -> Self {
-        Self::from((value.0,value.1.as_str()))
+        Self::builder(value.0).message(value.1).build()
     }
 }
 
 impl Debug for HttpParseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {}{}", self.kind, MESSAGE, self.get_msg().unwrap_or(""))
+        write!(f, "{} {}{}", self.kind, MESSAGE, self.get_msg().unwrap_or(""))?;
+        if let Some(line) = self.line {
+            write!(f, " {}{}", LINE, line)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, " {}{}", OFFSET, offset)?;
+        }
+        Ok(())
     }
 }
 
@@ -76,6 +209,12 @@ impl Display for HttpParseError {
     }
 }
 
+impl Error for HttpParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_ref().map(|source| source.0.as_ref() as &(dyn Error + 'static))
+    }
+}
+
 impl Destruct for HttpParseError {
     type Item = (ParseErrorKind, String);
     fn destruct(self) -> Self::Item {
@@ -83,6 +222,58 @@ impl Destruct for HttpParseError {
     }
 }
 
+/// Builder impl for [HttpParseError]
+///
+/// Example:
+/// ```
+/// use whdp::{HttpParseError, ParseErrorKind};
+///
+/// let err = HttpParseError::builder(ParseErrorKind::Req)
+///     .message("missing uri")
+///     .line(1)
+///     .offset(4)
+///     .build();
+/// assert_eq!(err.get_line(), Some(1));
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Hash, Debug)]
+pub struct HttpParseErrorBuilder {
+    error: HttpParseError,
+}
+
+impl HttpParseErrorBuilder {
+    /// creates a new instance of HttpParseErrorBuilder for the given [ParseErrorKind]
+    pub const fn new(kind: ParseErrorKind) -> Self {
+        Self {
+            error: HttpParseError {
+                kind,
+                msg: None,
+                line: None,
+                offset: None,
+                source: None,
+            },
+        }
+    }
+    /// replaces the current value with the message parameter
+    pub fn message(mut self, msg: impl Into<String>) -> Self {
+        self.error.msg = Some(msg.into());
+        self
+    }
+    /// replaces the current value with the line parameter
+    pub fn line(mut self, line: usize) -> Self {
+        self.error.line = Some(line);
+        self
+    }
+    /// replaces the current value with the offset parameter
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.error.offset = Some(offset);
+        self
+    }
+    /// makes it to a [HttpParseError]
+    pub fn build(self) -> HttpParseError {
+        self.error
+    }
+}
+
 /// #### Enum for the different places where the parsing could went wrong
 /// This is more for error handling in match cases. It's used in [HttpParseError] <br>
 /// For genuine Information where it went wrong read the message
@@ -111,6 +302,30 @@ pub enum ParseErrorKind {
     ///
     /// [HttpResponse]: crate::Response
     Resp,
+    /// Error type for everything that has to do with parsing the body
+    /// (for example an empty or malformed body)
+    Body,
+    /// Error type for a declared length that doesn't match the actual length
+    /// (for example the Content-Length header and the body)
+    Length,
+    /// Error type for a stream that didn't deliver the message in time
+    Timeout,
+    /// Error type for a start line and headers that exceed the maximum head size
+    /// of the [ParseOptions](crate::ParseOptions)
+    HeaderFieldsTooLarge,
+    /// Error type for a feature that is understood but not supported
+    /// (for example an unknown transfer coding). <br>
+    /// The message contains the unsupported token
+    Unsupported,
+    /// Error type for an HTTP version that is recognized but not supported
+    /// (for example the HTTP/2 connection preface sent to an HTTP/1.x server)
+    VersionNotSupported,
+    /// Error type for a well-formed host that isn't one of the allowed hosts
+    /// of the [HostGuard](crate::HostGuard)
+    HostNotAllowed,
+    /// Error type for bytes that don't look like HTTP/1.x at all
+    /// (for example a TLS handshake sent to a plaintext port)
+    NotHttp,
     /// Error type for some util functions for example
     /// 1. Parsing the body
     /// 2. Parsing the headers
@@ -123,3 +338,64 @@ impl Display for ParseErrorKind {
         Debug::fmt(self, f)
     }
 }
+
+/// Enum for the fields of the [RequestBuilder] and [ResponseBuilder] that weren't filled with a value
+///
+/// [RequestBuilder]: crate::RequestBuilder
+/// [ResponseBuilder]: crate::ResponseBuilder
+#[derive(Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub enum MissingField {
+    /// the [HttpMethod](crate::HttpMethod) of a Request
+    Method,
+    /// the uri of a Request
+    Uri,
+    /// the [HttpStatus](crate::HttpStatus) of a Response
+    Status,
+    /// the [HttpVersion](crate::HttpVersion)
+    Version,
+    /// the headers (an empty map counts as filled)
+    Headers,
+    /// the body (an empty body counts as filled)
+    Body,
+}
+
+impl Display for MissingField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use crate::{HttpParseError, HttpParseErrorBuilder, ParseErrorKind};
+
+    #[test]
+    fn const_constructors() {
+        const ERROR: HttpParseError = HttpParseError::new();
+        const ERROR_BUILDER: HttpParseErrorBuilder = HttpParseError::builder(ParseErrorKind::Body);
+        assert_eq!(ERROR.get_kind(), &ParseErrorKind::Unkown);
+        assert_eq!(ERROR_BUILDER.build().get_kind(), &ParseErrorKind::Body);
+    }
+
+    #[test]
+    fn constructors() {
+        assert_eq!(HttpParseError::request("no uri"), HttpParseError::from((ParseErrorKind::Req, "no uri")));
+        assert_eq!(HttpParseError::status(String::from("999")).get_kind(), &ParseErrorKind::Status);
+        assert_eq!(HttpParseError::header("bad header").get_kind(), &ParseErrorKind::Util);
+        assert_eq!(HttpParseError::not_http("TLS").get_msg(), Some("TLS"));
+        assert_eq!(HttpParseError::version_not_supported("HTTP/2.0").get_kind(), &ParseErrorKind::VersionNotSupported);
+        assert_eq!(HttpParseError::host_not_allowed("evil.com").get_kind(), &ParseErrorKind::HostNotAllowed);
+    }
+
+    #[test]
+    fn source() {
+        let io = std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "eof");
+        let err = HttpParseError::body("truncated").with_source(io);
+        assert_eq!(err.source().unwrap().to_string(), "eof");
+        let other = std::io::Error::new(std::io::ErrorKind::InvalidData, "other");
+        assert_eq!(err, HttpParseError::body("truncated").with_source(other));
+        assert!(HttpParseError::body("truncated").source().is_none());
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+use crate::error::HttpParseError;
+
+const WEAK_PREFIX: &str = "W/";
+const QUOTE: char = '"';
+const INVALID_ETAG: &str = "The entity tag has to be a quoted string optionally prefixed by W/";
+
+/// Struct for a strong (`"abc"`) or weak (`W/"abc"`) entity tag
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ETag {
+    tag: String,
+    weak: bool,
+}
+
+impl ETag {
+    /// creates a new strong ETag
+    pub fn strong(tag: &str) -> Self {
+        Self {
+            tag: String::from(tag),
+            weak: false,
+        }
+    }
+    /// creates a new weak ETag
+    pub fn weak(tag: &str) -> Self {
+        Self {
+            tag: String::from(tag),
+            weak: true,
+        }
+    }
+    /// returns the opaque tag without quotes
+    pub fn get_tag(&self) -> &str {
+        &self.tag
+    }
+    /// looks if the ETag is weak
+    pub const fn is_weak(&self) -> bool {
+        self.weak
+    }
+    /// compares the ETags with the strong or weak comparison of
+    /// [RFC 7232](https://www.rfc-editor.org/rfc/rfc7232#section-2.3.2). <br>
+    /// The strong comparison requires both ETags to be strong,
+    /// the weak comparison only compares the tags
+    pub fn matches(&self, other: &ETag, strong: bool) -> bool {
+        if strong && (self.weak || other.weak) {
+            return false;
+        }
+        self.tag == other.tag
+    }
+}
+
+impl FromStr for ETag {
+    type Err = HttpParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (weak, quoted) = match s.strip_prefix(WEAK_PREFIX) {
+            Some(quoted) => (true, quoted),
+            None => (false, s),
+        };
+        let tag = quoted
+            .strip_prefix(QUOTE)
+            .and_then(|tag| tag.strip_suffix(QUOTE))
+            .filter(|tag| !tag.contains(QUOTE))
+            .ok_or(HttpParseError::util(INVALID_ETAG))?;
+        Ok(Self {
+            tag: String::from(tag),
+            weak,
+        })
+    }
+}
+
+impl Display for ETag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.weak {
+            write!(f, "{}", WEAK_PREFIX)?;
+        }
+        write!(f, "{}{}{}", QUOTE, self.tag, QUOTE)
+    }
+}
+
+impl Debug for ETag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::ETag;
+
+    #[test]
+    fn parse() {
+        assert_eq!(ETag::from_str("\"abc\""), Ok(ETag::strong("abc")));
+        assert_eq!(ETag::from_str(" W/\"abc\""), Ok(ETag::weak("abc")));
+        assert!(ETag::from_str("abc").is_err());
+        assert_eq!(ETag::weak("abc").to_string(), "W/\"abc\"");
+    }
+
+    #[test]
+    fn comparison() {
+        // the table from RFC 7232 section 2.3.2
+        let cases = [
+            (ETag::weak("1"), ETag::weak("1"), false, true),
+            (ETag::weak("1"), ETag::weak("2"), false, false),
+            (ETag::weak("1"), ETag::strong("1"), false, true),
+            (ETag::strong("1"), ETag::strong("1"), true, true),
+        ];
+        for (first, second, strong, weak) in cases {
+            assert_eq!(first.matches(&second, true), strong);
+            assert_eq!(first.matches(&second, false), weak);
+        }
+    }
+}
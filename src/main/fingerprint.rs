@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+
+use crate::sha256::Sha256;
+use crate::uri::Target;
+use crate::util::get_header_ignore_case;
+
+const VERSION: &[u8] = b"whdp-fingerprint-v1";
+const METHOD_TAG: u8 = b'M';
+const PATH_TAG: u8 = b'P';
+const QUERY_TAG: u8 = b'Q';
+const HEADER_NAME_TAG: u8 = b'H';
+const HEADER_VALUE_TAG: u8 = b'V';
+const HEADER_ABSENT_TAG: u8 = b'N';
+const BODY_TAG: u8 = b'B';
+const PAIR_DELIMITER: char = '&';
+const ROOT: &str = "/";
+const LIST_DELIMITER: char = ',';
+const LINE_DELIMITER: char = '\n';
+const LIST_SEPARATOR: &str = ", ";
+
+/// Struct for the components of a Request that participate in its
+/// [fingerprint](crate::Request::fingerprint) and how they are canonicalized. <br>
+/// The fingerprint is the SHA-256 of the selected components, which makes it useful to detect
+/// duplicate deliveries (like retried webhooks) or replays:
+///
+/// | Component | Default | Canonicalization |
+/// |---|---|---|
+/// | method | included | as it is (`GET`) |
+/// | path | always included | scheme and authority are dropped, an empty path is `/` |
+/// | query | included | the pairs are sorted unless [with_sorted_query](FingerprintConfig::with_sorted_query) is false |
+/// | headers | none | names are case-insensitive, their order in the config doesn't matter, values are canonical lists (see below) |
+/// | body | included | as it is |
+///
+/// **Stability:** the fingerprint of a Request and a config never changes between versions of this crate,
+/// because fingerprints get persisted. A new encoding would get a new version tag and a new opt-in setting.
+/// The encoding of version 1 is the SHA-256 of the bytes `whdp-fingerprint-v1` followed by the components in the order
+/// of the table, each as a tag byte, the length as big-endian u64 and the bytes:
+/// `M` method, `P` path, `Q` query (empty if absent) and per header `H` with the lowercase name
+/// followed by `V` with the value or an empty `N` if it is absent, and `B` body. <br>
+/// A header value is split into its elements at every `,` and line break, each element is trimmed,
+/// empty elements are dropped and the rest is joined by `, `. So `Accept: a` + `Accept: b`, `Accept: a,b`
+/// and `Accept: a, b` have the same fingerprint no matter how the repeated lines were combined while parsing
+/// ([DuplicatePolicy](crate::DuplicatePolicy)). Only a header whose repetitions are dropped
+/// ([FirstWins](crate::DuplicatePolicy::FirstWins)) contributes just its first value
+///
+/// Example:
+/// ```
+/// use whdp::{FingerprintConfig, Request};
+///
+/// let config = FingerprintConfig::new().with_headers(&["X-Webhook-Id"]);
+/// let first = Request::try_from("POST /hook?b=2&a=1 HTTP/1.1\nX-Webhook-Id: 42\nDate: today\n\n{}").unwrap();
+/// let retry = Request::try_from("POST /hook?a=1&b=2 HTTP/1.1\nx-webhook-id: 42\nDate: tomorrow\n\n{}").unwrap();
+/// assert_eq!(first.fingerprint(&config), retry.fingerprint(&config));
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct FingerprintConfig {
+    method: bool,
+    query: bool,
+    sorted_query: bool,
+    headers: Vec<String>,
+    body: bool,
+}
+
+impl FingerprintConfig {
+    /// creates a new FingerprintConfig with the defaults (see the table)
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// replaces whether the method participates
+    pub fn with_method(mut self, method: bool) -> Self {
+        self.method = method;
+        self
+    }
+    /// replaces whether the query participates (the path always does)
+    pub fn with_query(mut self, query: bool) -> Self {
+        self.query = query;
+        self
+    }
+    /// replaces whether the pairs of the query are sorted, so `?a=1&b=2` and `?b=2&a=1` are the same
+    pub fn with_sorted_query(mut self, sorted_query: bool) -> Self {
+        self.sorted_query = sorted_query;
+        self
+    }
+    /// replaces the names of the headers that participate (case-insensitive)
+    pub fn with_headers(mut self, names: &[&str]) -> Self {
+        let mut headers: Vec<String> = names.iter().map(|name| name.to_ascii_lowercase()).collect();
+        headers.sort();
+        headers.dedup();
+        self.headers = headers;
+        self
+    }
+    /// replaces whether the body participates
+    pub fn with_body(mut self, body: bool) -> Self {
+        self.body = body;
+        self
+    }
+    /// returns whether the method participates
+    pub fn get_method(&self) -> bool {
+        self.method
+    }
+    /// returns whether the query participates
+    pub fn get_query(&self) -> bool {
+        self.query
+    }
+    /// returns whether the pairs of the query are sorted
+    pub fn get_sorted_query(&self) -> bool {
+        self.sorted_query
+    }
+    /// returns the lowercase names of the headers that participate (sorted)
+    pub fn get_headers(&self) -> &[String] {
+        &self.headers
+    }
+    /// returns whether the body participates
+    pub fn get_body(&self) -> bool {
+        self.body
+    }
+}
+
+impl Default for FingerprintConfig {
+    fn default() -> Self {
+        Self {
+            method: true,
+            query: true,
+            sorted_query: true,
+            headers: Vec::new(),
+            body: true,
+        }
+    }
+}
+
+/// computes the fingerprint of version 1 (see [FingerprintConfig])
+pub(crate) fn fingerprint(
+    config: &FingerprintConfig,
+    method: &str,
+    uri: &str,
+    headers: &BTreeMap<String, String>,
+    body: &str,
+) -> [u8; 32] {
+    let mut sha = Sha256::new();
+    sha.update(VERSION);
+    if config.method {
+        component(&mut sha, METHOD_TAG, method.as_bytes());
+    }
+    let target = Target::split(uri);
+    let path = if target.get_path().is_empty() { ROOT } else { target.get_path() };
+    component(&mut sha, PATH_TAG, path.as_bytes());
+    if config.query {
+        let query = target.get_query().unwrap_or_default();
+        if config.sorted_query {
+            let mut pairs: Vec<&str> = query.split(PAIR_DELIMITER).filter(|pair| !pair.is_empty()).collect();
+            pairs.sort_unstable();
+            component(&mut sha, QUERY_TAG, pairs.join("&").as_bytes());
+        } else {
+            component(&mut sha, QUERY_TAG, query.as_bytes());
+        }
+    }
+    for name in &config.headers {
+        component(&mut sha, HEADER_NAME_TAG, name.as_bytes());
+        match get_header_ignore_case(headers, name) {
+            Some(value) => component(&mut sha, HEADER_VALUE_TAG, canonical_value(value).as_bytes()),
+            None => component(&mut sha, HEADER_ABSENT_TAG, &[]),
+        }
+    }
+    if config.body {
+        component(&mut sha, BODY_TAG, body.as_bytes());
+    }
+    sha.finish()
+}
+
+/// splits the value into its list elements (repeated lines are stored joined by `, ` or by a line break)
+fn canonical_value(value: &str) -> String {
+    value
+        .split([LIST_DELIMITER, LINE_DELIMITER])
+        .map(str::trim)
+        .filter(|element| !element.is_empty())
+        .collect::<Vec<&str>>()
+        .join(LIST_SEPARATOR)
+}
+
+fn component(sha: &mut Sha256, tag: u8, bytes: &[u8]) {
+    sha.update(&[tag]);
+    sha.update(&(bytes.len() as u64).to_be_bytes());
+    sha.update(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FingerprintConfig, Request};
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    fn fingerprint(req: &str, config: &FingerprintConfig) -> String {
+        hex(&Request::try_from(req).unwrap().fingerprint(config))
+    }
+
+    // golden values: they must never change, because fingerprints get persisted
+    #[test]
+    fn golden() {
+        let req = "POST /hook?b=2&a=1 HTTP/1.1\nX-Webhook-Id: 42\nContent-Type: application/json\n\n{\"event\":\"paid\"}";
+        assert_eq!(fingerprint(req, &FingerprintConfig::new()), "5316a0f25213b7f426160319138d6572642328ce9d82198e09b4bbd8f36369e6");
+        let config = FingerprintConfig::new().with_headers(&["X-Webhook-Id", "X-Missing"]).with_sorted_query(false);
+        assert_eq!(fingerprint(req, &config), "b2bf4fded0f8d0b197f1f1d0f384f47b93e4f4edf39fe2d5a5a936d84856750e");
+        let config = FingerprintConfig::new().with_method(false).with_query(false).with_body(false);
+        assert_eq!(fingerprint("GET http://example.com HTTP/1.1\n\n", &config), "a43b3509c22de7bd53aaef2c2d6f71c707f5d0e8889c1cbe5f3dd09fc24f96f8");
+    }
+
+    #[test]
+    fn golden_repeated_header() {
+        let config = FingerprintConfig::new().with_headers(&["Accept", "Set-Cookie"]);
+        let golden = fingerprint("GET / HTTP/1.1\nAccept: a\nAccept: b\nSet-Cookie: x=1\nSet-Cookie: y=2\n\n", &config);
+        assert_eq!(golden, "8518a38942ae6a0c562b5f2292339d576ffb8d764dd2625b15686521cb712ade");
+        assert_eq!(fingerprint("GET / HTTP/1.1\nAccept: a,b\nSet-Cookie: x=1, y=2\n\n", &config), golden);
+        assert_eq!(fingerprint("GET / HTTP/1.1\nAccept: a ,, b\nSet-Cookie: x=1,y=2\n\n", &config), golden);
+        assert_ne!(fingerprint("GET / HTTP/1.1\nAccept: b, a\nSet-Cookie: x=1, y=2\n\n", &config), golden);
+    }
+
+    #[test]
+    fn canonicalization() {
+        let config = FingerprintConfig::new();
+        let base = fingerprint("GET /a?x=1&y=2 HTTP/1.1\n\n", &config);
+        assert_eq!(fingerprint("GET /a?y=2&&x=1 HTTP/1.1\nDate: now\n\n", &config), base);
+        assert_eq!(fingerprint("GET http://example.com/a?x=1&y=2 HTTP/1.1\n\n", &config), base);
+        assert_ne!(fingerprint("GET /a?y=2&x=1 HTTP/1.1\n\n", &config.clone().with_sorted_query(false)), base);
+        assert_ne!(fingerprint("GET /a?x=1&y=3 HTTP/1.1\n\n", &config), base);
+        assert_ne!(fingerprint("HEAD /a?x=1&y=2 HTTP/1.1\n\n", &config), base);
+        assert_eq!(
+            fingerprint("HEAD /a HTTP/1.1\n\n", &config.clone().with_method(false).with_query(false)),
+            fingerprint("GET /a?z HTTP/1.1\n\n", &config.clone().with_method(false).with_query(false))
+        );
+        assert_ne!(fingerprint("POST /a?x=1&y=2 HTTP/1.1\n\nbody", &config), fingerprint("POST /a?x=1&y=2 HTTP/1.1\n\n", &config));
+    }
+
+    #[test]
+    fn headers() {
+        let config = FingerprintConfig::new().with_headers(&["X-Id", "x-id", "Authorization"]);
+        assert_eq!(config.get_headers(), ["authorization", "x-id"]);
+        assert_eq!(config, FingerprintConfig::new().with_headers(&["authorization", "X-ID"]));
+        let with = fingerprint("GET / HTTP/1.1\nx-id: 1\n\n", &config);
+        assert_eq!(fingerprint("GET / HTTP/1.1\nX-Id: 1\nAccept: */*\n\n", &config), with);
+        assert_ne!(fingerprint("GET / HTTP/1.1\nX-Id: 2\n\n", &config), with);
+        // an empty header isn't the same as an absent one
+        assert_ne!(fingerprint("GET / HTTP/1.1\n\n", &config), fingerprint("GET / HTTP/1.1\nAuthorization: \n\n", &config));
+        // the components can't be shifted into each other
+        assert_ne!(fingerprint("GET / HTTP/1.1\nX-Id: 1\n\n", &config), fingerprint("GET / HTTP/1.1\nAuthorization: 1\n\n", &config));
+    }
+}
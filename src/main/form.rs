@@ -0,0 +1,123 @@
+use std::borrow::Cow;
+
+use crate::error::HttpParseError;
+
+pub(crate) const FORM_URLENCODED: &str = "application/x-www-form-urlencoded";
+const INVALID_PERCENT_ENCODING: &str = "The form field isn't validly percent-encoded:";
+const INVALID_UTF8: &str = "The form field isn't valid UTF-8 after decoding:";
+const PAIR_DELIMITER: char = '&';
+const KEY_VALUE_DELIMITER: char = '=';
+const LEGACY_PAIR_DELIMITER: char = ';';
+
+/// lazily splits an `application/x-www-form-urlencoded` body into its decoded pairs. <br>
+/// Empty pairs are skipped and a pair without `=` has an empty value.
+/// A pair that can't be decoded yields an error without ending the iteration
+pub(crate) fn form_fields(body: &str) -> impl Iterator<Item = Result<(String, String), HttpParseError>> + '_ {
+    body.split(PAIR_DELIMITER)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once(KEY_VALUE_DELIMITER).unwrap_or((pair, ""));
+            Ok((decode_form_component(key)?, decode_form_component(value)?))
+        })
+}
+
+/// decodes a percent-encoded form component where `+` stands for a space
+pub(crate) fn decode_form_component(component: &str) -> Result<String, HttpParseError> {
+    let decoded = percent_decode(component, true)
+        .ok_or_else(|| HttpParseError::body(format!("{} {}", INVALID_PERCENT_ENCODING, component)))?;
+    String::from_utf8(decoded).map_err(|_err| HttpParseError::body(format!("{} {}", INVALID_UTF8, component)))
+}
+
+/// decodes the `%XX` escapes of a component into its raw bytes (`+` becomes a space if `plus_as_space` is set) <br>
+/// Returns None if an escape isn't followed by two hex digits
+pub(crate) fn percent_decode(component: &str, plus_as_space: bool) -> Option<Vec<u8>> {
+    let bytes = component.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' if plus_as_space => decoded.push(b' '),
+            b'%' => {
+                let byte = component
+                    .get(index + 1..index + 3)
+                    .filter(|hex| hex.bytes().all(|byte| byte.is_ascii_hexdigit()))
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())?;
+                decoded.push(byte);
+                index += 2;
+            }
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    Some(decoded)
+}
+
+/// percent-encodes a form component: unreserved characters are kept and a space becomes `+`
+pub(crate) fn encode_form_component(component: &str) -> String {
+    let mut encoded = String::with_capacity(component.len());
+    for byte in component.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(char::from(byte)),
+            b' ' => encoded.push('+'),
+            byte => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// splits a query string at `&` and `;` (legacy) into its decoded pairs in order. <br>
+/// Empty pairs are skipped, a pair without `=` has an empty value
+/// and a component that can't be decoded is kept as it is
+pub(crate) fn query_pairs(query: &str) -> Vec<(String, String)> {
+    query_pairs_iter(query)
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect()
+}
+
+/// lazily splits a query string like [query_pairs],
+/// the components without a `%` escape or a `+` are borrowed from the query
+pub(crate) fn query_pairs_iter(query: &str) -> impl Iterator<Item = (Cow<'_, str>, Cow<'_, str>)> {
+    query
+        .split([PAIR_DELIMITER, LEGACY_PAIR_DELIMITER])
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once(KEY_VALUE_DELIMITER).unwrap_or((pair, ""));
+            (decode_query_component(key), decode_query_component(value))
+        })
+}
+
+fn decode_query_component(component: &str) -> Cow<'_, str> {
+    if !component.contains(['%', '+']) {
+        return Cow::Borrowed(component);
+    }
+    decode_form_component(component).map_or(Cow::Borrowed(component), Cow::Owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Request;
+
+    #[test]
+    fn lazy_pairs() {
+        let req = Request::try_from("POST / HTTP/1.1\nContent-Type: application/x-www-form-urlencoded\n\nname=J%C3%B6rg+M&empty=&flag&&bad=%zz&last=a%3Db").unwrap();
+        let fields: Vec<_> = req.form_fields_iter().collect();
+        assert_eq!(fields.len(), 5);
+        assert_eq!(fields[0].as_ref().unwrap(), &(String::from("name"), String::from("Jörg M")));
+        assert_eq!(fields[1].as_ref().unwrap(), &(String::from("empty"), String::new()));
+        assert_eq!(fields[2].as_ref().unwrap(), &(String::from("flag"), String::new()));
+        assert!(fields[3].as_ref().unwrap_err().get_msg().unwrap().ends_with("%zz"));
+        assert_eq!(fields[4].as_ref().unwrap(), &(String::from("last"), String::from("a=b")));
+        let mut iter = req.form_fields_iter();
+        assert_eq!(iter.next().unwrap().unwrap().0, "name");
+    }
+
+    #[test]
+    fn other_content_types() {
+        let req = Request::try_from("POST / HTTP/1.1\nContent-Type: application/json\n\na=b").unwrap();
+        assert_eq!(req.form_fields_iter().count(), 0);
+        let req = Request::try_from("POST / HTTP/1.1\n\na=b").unwrap();
+        assert_eq!(req.form_fields_iter().count(), 0);
+        let req = Request::try_from("POST / HTTP/1.1\nContent-Type: Application/X-WWW-Form-Urlencoded; charset=utf-8\n\na=%FF").unwrap();
+        assert!(req.form_fields_iter().next().unwrap().is_err());
+    }
+}
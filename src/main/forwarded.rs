@@ -0,0 +1,212 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::net::{IpAddr, Ipv6Addr};
+use std::str::FromStr;
+
+use crate::util::{quote_if_needed, split_quoted, unquote};
+
+const FOR: &str = "for";
+const BY: &str = "by";
+const HOST: &str = "host";
+const PROTO: &str = "proto";
+const IPV6_START: char = '[';
+const IPV6_END: char = ']';
+const PORT_DELIMITER: char = ':';
+
+/// Struct for a single element of the Forwarded header
+/// ([RFC 7239](https://www.rfc-editor.org/rfc/rfc7239)), which is added by one proxy. <br>
+/// The nodes (`for` and `by`) may be an address, an address with port,
+/// `unknown` or an obfuscated identifier like `_hidden`. <br>
+/// [Display] quotes the values where needed and brackets bare IPv6 addresses,
+/// `unknown` and obfuscated identifiers are written as they are
+///
+/// Example:
+/// ```
+/// use whdp::ForwardedElement;
+///
+/// let element = ForwardedElement::new()
+///     .with_for("2001:db8::17")
+///     .with_proto("https");
+/// assert_eq!(element.to_string(), "for=\"[2001:db8::17]\";proto=https");
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct ForwardedElement {
+    for_node: Option<String>,
+    by: Option<String>,
+    host: Option<String>,
+    proto: Option<String>,
+}
+
+impl ForwardedElement {
+    /// creates a new ForwardedElement without any parameter
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// replaces the node the Request came from (the `for` parameter)
+    pub fn with_for(mut self, node: &str) -> Self {
+        self.for_node = Some(String::from(node));
+        self
+    }
+    /// replaces the node of the proxy that received the Request (the `by` parameter)
+    pub fn with_by(mut self, node: &str) -> Self {
+        self.by = Some(String::from(node));
+        self
+    }
+    /// replaces the Host header the proxy received (the `host` parameter)
+    pub fn with_host(mut self, host: &str) -> Self {
+        self.host = Some(String::from(host));
+        self
+    }
+    /// replaces the protocol the proxy received the Request with (the `proto` parameter)
+    pub fn with_proto(mut self, proto: &str) -> Self {
+        self.proto = Some(String::from(proto));
+        self
+    }
+    /// returns the node the Request came from (the `for` parameter)
+    pub fn get_for(&self) -> Option<&str> {
+        self.for_node.as_deref()
+    }
+    /// returns the node of the proxy that received the Request (the `by` parameter)
+    pub fn get_by(&self) -> Option<&str> {
+        self.by.as_deref()
+    }
+    /// returns the Host header the proxy received (the `host` parameter)
+    pub fn get_host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+    /// returns the protocol the proxy received the Request with (the `proto` parameter)
+    pub fn get_proto(&self) -> Option<&str> {
+        self.proto.as_deref()
+    }
+    /// returns the IP address of the `for` node without the port
+    /// ([None] for `unknown` and obfuscated identifiers)
+    pub fn for_ip(&self) -> Option<IpAddr> {
+        self.for_node.as_deref().and_then(node_ip)
+    }
+}
+
+impl Display for ForwardedElement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let for_node = self.for_node.as_deref().map(bracket_ipv6);
+        let by = self.by.as_deref().map(bracket_ipv6);
+        write_params(f, [&for_node, &by, &self.host, &self.proto])
+    }
+}
+
+impl Debug for ForwardedElement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+fn bracket_ipv6(node: &str) -> String {
+    match Ipv6Addr::from_str(node) {
+        Ok(_) => format!("{}{}{}", IPV6_START, node, IPV6_END),
+        Err(_) => String::from(node),
+    }
+}
+
+/// writes the for, by, host and proto parameters, quoting the values that aren't tokens
+fn write_params(f: &mut Formatter<'_>, values: [&Option<String>; 4]) -> std::fmt::Result {
+    let mut first = true;
+    for (key, value) in [FOR, BY, HOST, PROTO].into_iter().zip(values) {
+        let Some(value) = value else {
+            continue;
+        };
+        if !first {
+            write!(f, ";")?;
+        }
+        first = false;
+        write!(f, "{}={}", key, quote_if_needed(value))?;
+    }
+    Ok(())
+}
+
+/// parses the value of the Forwarded header into its elements.
+/// Unknown parameters and pairs without `=` are ignored
+pub(crate) fn parse_forwarded(header: &str) -> Vec<ForwardedElement> {
+    split_quoted(header, ',')
+        .into_iter()
+        .filter(|element| !element.trim().is_empty())
+        .map(parse_element)
+        .collect()
+}
+
+fn parse_element(element: &str) -> ForwardedElement {
+    let mut forwarded = ForwardedElement::default();
+    for pair in split_quoted(element, ';') {
+        let Some((key, value)) = pair.split_once('=') else {
+            continue;
+        };
+        let value = Some(unquote(value.trim()));
+        match key.trim().to_ascii_lowercase().as_str() {
+            FOR => forwarded.for_node = value,
+            BY => forwarded.by = value,
+            HOST => forwarded.host = value,
+            PROTO => forwarded.proto = value,
+            _ => {}
+        }
+    }
+    forwarded
+}
+
+/// parses the address of a node like `192.0.2.43`, `192.0.2.43:47011` or `[2001:db8::17]:4711`
+fn node_ip(node: &str) -> Option<IpAddr> {
+    if let Some(rest) = node.strip_prefix(IPV6_START) {
+        return rest.split(IPV6_END).next().and_then(|ip| IpAddr::from_str(ip).ok());
+    }
+    node.split(PORT_DELIMITER)
+        .next()
+        .and_then(|ip| IpAddr::from_str(ip).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+    use std::str::FromStr;
+
+    use crate::forwarded::{ForwardedElement, parse_forwarded};
+
+    #[test]
+    fn elements() {
+        let elements = parse_forwarded("for=192.0.2.60;proto=http;by=203.0.113.43, For=\"[2001:db8:cafe::17]:4711\";Host=\"example.com\"");
+        assert_eq!(elements.len(), 2);
+        assert_eq!(elements[0].get_for(), Some("192.0.2.60"));
+        assert_eq!(elements[0].get_proto(), Some("http"));
+        assert_eq!(elements[0].get_by(), Some("203.0.113.43"));
+        assert_eq!(elements[0].get_host(), None);
+        assert_eq!(elements[1].get_for(), Some("[2001:db8:cafe::17]:4711"));
+        assert_eq!(elements[1].get_host(), Some("example.com"));
+        assert_eq!(elements[1].for_ip(), Some(IpAddr::from_str("2001:db8:cafe::17").unwrap()));
+    }
+
+    #[test]
+    fn edge_cases() {
+        let elements = parse_forwarded("for=unknown, for=_hidden;by=_SEVKISEK, for=\"192.0.2.43:47011\", for=\"a\\\"b;c,d\";secret=1, ,");
+        assert_eq!(elements.len(), 4);
+        assert_eq!(elements[0].get_for(), Some("unknown"));
+        assert_eq!(elements[0].for_ip(), None);
+        assert_eq!(elements[1].get_for(), Some("_hidden"));
+        assert_eq!(elements[1].get_by(), Some("_SEVKISEK"));
+        assert_eq!(elements[2].for_ip(), Some(IpAddr::from_str("192.0.2.43").unwrap()));
+        assert_eq!(elements[3].get_for(), Some("a\"b;c,d"));
+        assert_eq!(elements[3].to_string(), "for=\"a\\\"b;c,d\"");
+        assert_eq!(elements[1].to_string(), "for=_hidden;by=_SEVKISEK");
+    }
+
+    #[test]
+    fn quoting() {
+        let element = ForwardedElement::new()
+            .with_for("[2001:db8:cafe::17]:4711")
+            .with_by("2001:db8::1");
+        assert_eq!(element.to_string(), "for=\"[2001:db8:cafe::17]:4711\";by=\"[2001:db8::1]\"");
+        let element = ForwardedElement::new()
+            .with_for("unknown")
+            .with_by("_hidden")
+            .with_host("example.com:8080")
+            .with_proto("https");
+        assert_eq!(element.to_string(), "for=unknown;by=_hidden;host=\"example.com:8080\";proto=https");
+        let element = ForwardedElement::new().with_for("192.0.2.43");
+        assert_eq!(element.to_string(), "for=192.0.2.43");
+        assert_eq!(parse_forwarded(&element.to_string()), vec![element]);
+    }
+}
@@ -0,0 +1,150 @@
+//! Golden-file tests locking down the exact bytes of the wire writer. <br>
+//! Run the tests with `UPDATE_GOLDEN=1` to regenerate the files under `src/resources/golden`
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+
+use crate::{HttpMethod, HttpVersion, Request, Response, status_presets};
+
+const GOLDEN_DIR: &str = "src/resources/golden";
+const UPDATE_GOLDEN: &str = "UPDATE_GOLDEN";
+
+/// compares the bytes with the golden file of the given name byte-for-byte
+/// or overwrites the file if `UPDATE_GOLDEN=1` is set
+fn assert_golden(name: &str, bytes: &[u8]) {
+    let path = format!("{}/{}.bin", GOLDEN_DIR, name);
+    if env::var(UPDATE_GOLDEN).is_ok_and(|value| value == "1") {
+        fs::create_dir_all(GOLDEN_DIR).unwrap();
+        fs::write(&path, bytes).unwrap();
+        return;
+    }
+    let expected = fs::read(&path).unwrap_or_else(|err| panic!("{}: {} (run with {}=1 to create it)", path, err, UPDATE_GOLDEN));
+    assert!(
+        expected == bytes,
+        "{} differs\nexpected: {:?}\nactual:   {:?}",
+        path,
+        String::from_utf8_lossy(&expected),
+        String::from_utf8_lossy(bytes)
+    );
+}
+
+fn headers(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|(key, value)| (String::from(*key), String::from(*value)))
+        .collect()
+}
+
+#[test]
+fn response_empty_body() {
+    let resp = Response::builder()
+        .with_status(status_presets::no_content())
+        .with_version(HttpVersion::OnePointOne)
+        .with_headers(headers(&[("Server", "whdp")]))
+        .with_empty_body()
+        .build()
+        .unwrap();
+    assert_golden("response_empty_body", &resp.to_bytes().unwrap());
+}
+
+#[test]
+fn response_empty_headers() {
+    let resp = Response::builder()
+        .with_status(status_presets::ok())
+        .with_version(HttpVersion::One)
+        .with_empty_headers()
+        .with_body("plain")
+        .build()
+        .unwrap();
+    assert_golden("response_empty_headers", &resp.to_bytes().unwrap());
+}
+
+#[test]
+fn response_multi_value_headers() {
+    let resp = Response::builder()
+        .with_status(status_presets::ok())
+        .with_version(HttpVersion::OnePointOne)
+        .with_headers(headers(&[
+            ("Content-Length", "2"),
+            ("Set-Cookie", "a=1\nb=2; Path=/"),
+            ("Vary", "Accept, Accept-Encoding"),
+        ]))
+        .with_body("ok")
+        .build()
+        .unwrap();
+    assert_golden("response_multi_value_headers", &resp.to_bytes().unwrap());
+}
+
+#[test]
+fn response_head() {
+    let resp = Response::builder()
+        .with_status(status_presets::ok())
+        .with_version(HttpVersion::OnePointOne)
+        .with_headers(headers(&[("Content-Length", "12"), ("Content-Type", "text/plain")]))
+        .with_empty_body()
+        .build()
+        .unwrap();
+    assert_golden("response_head", &resp.to_bytes().unwrap());
+}
+
+#[test]
+fn response_chunked() {
+    let resp = Response::builder()
+        .with_status(status_presets::ok())
+        .with_version(HttpVersion::OnePointOne)
+        .with_headers(headers(&[("Transfer-Encoding", "chunked")]))
+        .with_body("5\r\nHello\r\n7\r\n, World\r\n0\r\n\r\n")
+        .build()
+        .unwrap();
+    assert_golden("response_chunked", &resp.to_bytes().unwrap());
+}
+
+#[test]
+fn response_utf8_body() {
+    let body = "Grüße, 世界 🌍";
+    let resp = Response::builder()
+        .with_status(status_presets::ok())
+        .with_version(HttpVersion::OnePointOne)
+        .with_headers(headers(&[
+            ("Content-Length", &body.len().to_string()),
+            ("Content-Type", "text/plain; charset=utf-8"),
+        ]))
+        .with_body(body)
+        .build()
+        .unwrap();
+    assert_golden("response_utf8_body", &resp.to_bytes().unwrap());
+}
+
+#[test]
+fn request_empty_body() {
+    let req = Request::builder()
+        .with_method(HttpMethod::Get)
+        .with_uri("/index.html?q=1")
+        .with_version(HttpVersion::OnePointOne)
+        .with_headers(headers(&[("Accept", "*/*"), ("Host", "example.com")]))
+        .with_empty_body()
+        .build()
+        .unwrap();
+    assert_golden("request_empty_body", &req.to_bytes().unwrap());
+}
+
+#[test]
+fn request_utf8_body() {
+    let body = "{\"name\":\"Zoë\"}";
+    let req = Request::builder()
+        .with_method(HttpMethod::Post)
+        .with_uri("/users")
+        .with_version(HttpVersion::OnePointOne)
+        .with_headers(headers(&[
+            ("Content-Length", &body.len().to_string()),
+            ("Content-Type", "application/json"),
+            ("Host", "example.com"),
+        ]))
+        .with_body(body)
+        .build()
+        .unwrap();
+    let mut written = Vec::new();
+    req.write_to(&mut written).unwrap();
+    assert_eq!(written, req.to_bytes().unwrap());
+    assert_golden("request_utf8_body", &written);
+}
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use crate::error::HttpParseError;
+use crate::util::NEW_LINE;
+
+const DUPLICATE_HEADER: &str = "The header is not allowed to be repeated:";
+const CONFLICTING_HEADER: &str = "The repeated header has a conflicting value:";
+const LIST_DELIMITER: &str = ", ";
+const ERROR_HEADERS: [&str; 1] = ["Host"];
+const IDENTICAL_HEADERS: [&str; 1] = ["Content-Length"];
+const KEEP_ALL_HEADERS: [&str; 3] = ["Set-Cookie", "WWW-Authenticate", "Proxy-Authenticate"];
+const FIRST_WINS_HEADERS: [&str; 14] = [
+    "Age",
+    "Authorization",
+    "Content-Location",
+    "Content-Type",
+    "Date",
+    "ETag",
+    "Expires",
+    "From",
+    "If-Modified-Since",
+    "If-Unmodified-Since",
+    "Last-Modified",
+    "Location",
+    "Referer",
+    "User-Agent",
+];
+
+/// Enum for the different ways a repeated header is handled
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum DuplicatePolicy {
+    /// A repeated header is always an error in strict mode (for example Host)
+    Error,
+    /// A repeated header has to have the same value in strict mode (for example Content-Length)
+    Identical,
+    /// All values are kept (for example Set-Cookie). <br>
+    /// They are stored separated by a new line and written as separate header lines
+    KeepAll,
+    /// All values are merged into a comma separated list (for example Accept)
+    Merge,
+    /// Only the first value is kept (for example Content-Type)
+    FirstWins,
+}
+
+impl DuplicatePolicy {
+    /// looks up the policy for a header name (case-insensitive). <br>
+    /// Headers that aren't known are merged like list headers
+    pub fn of(key: &str) -> Self {
+        let contains = |list: &[&str]| list.iter().any(|name| name.eq_ignore_ascii_case(key));
+        if contains(&ERROR_HEADERS) {
+            DuplicatePolicy::Error
+        } else if contains(&IDENTICAL_HEADERS) {
+            DuplicatePolicy::Identical
+        } else if contains(&KEEP_ALL_HEADERS) {
+            DuplicatePolicy::KeepAll
+        } else if contains(&FIRST_WINS_HEADERS) {
+            DuplicatePolicy::FirstWins
+        } else {
+            DuplicatePolicy::Merge
+        }
+    }
+}
+
+/// Trait for inserting headers into a header map
+/// while respecting the [DuplicatePolicy] of each header
+pub trait HeaderMap {
+    /// inserts the header according to the given [DuplicatePolicy]
+    /// and returns if the header was already present (case-insensitive). <br>
+    /// In strict mode the [Error](DuplicatePolicy::Error) and [Identical](DuplicatePolicy::Identical)
+    /// policies return a [HttpParseError], otherwise they behave like [FirstWins](DuplicatePolicy::FirstWins)
+    fn insert_with(
+        &mut self,
+        key: String,
+        value: String,
+        policy: DuplicatePolicy,
+        strict: bool,
+    ) -> Result<bool, HttpParseError>;
+    /// inserts the header according to its own [DuplicatePolicy] (see [insert_with](HeaderMap::insert_with))
+    fn insert_with_policy(&mut self, key: String, value: String, strict: bool) -> Result<bool, HttpParseError> {
+        let policy = DuplicatePolicy::of(&key);
+        self.insert_with(key, value, policy, strict)
+    }
+}
+
+impl HeaderMap for BTreeMap<String, String> {
+    fn insert_with(
+        &mut self,
+        key: String,
+        value: String,
+        policy: DuplicatePolicy,
+        strict: bool,
+    ) -> Result<bool, HttpParseError> {
+        let existing = self
+            .iter_mut()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&key))
+            .map(|(_, value)| value);
+        let existing = match existing {
+            Some(existing) => existing,
+            None => {
+                self.insert(key, value);
+                return Ok(false);
+            }
+        };
+        match policy {
+            DuplicatePolicy::Error if strict => {
+                return Err(HttpParseError::util(format!("{} {}", DUPLICATE_HEADER, key)));
+            }
+            DuplicatePolicy::Identical if strict && existing.as_str() != value => {
+                return Err(HttpParseError::util(format!("{} {}", CONFLICTING_HEADER, key)));
+            }
+            DuplicatePolicy::KeepAll => {
+                existing.push(NEW_LINE);
+                existing.push_str(&value);
+            }
+            DuplicatePolicy::Merge => {
+                existing.push_str(LIST_DELIMITER);
+                existing.push_str(&value);
+            }
+            _ => {}
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crate::{DuplicatePolicy, HeaderMap};
+
+    fn insert_twice(key: &str, first: &str, second: &str, strict: bool) -> Result<String, ()> {
+        let mut map = BTreeMap::new();
+        assert_eq!(map.insert_with_policy(key.into(), first.into(), strict), Ok(false));
+        map.insert_with_policy(key.to_lowercase(), second.into(), strict)
+            .map_err(|_err| ())?;
+        Ok(map.get(key).unwrap().clone())
+    }
+
+    #[test]
+    fn policies() {
+        assert_eq!(DuplicatePolicy::of("host"), DuplicatePolicy::Error);
+        assert_eq!(DuplicatePolicy::of("Content-Length"), DuplicatePolicy::Identical);
+        assert_eq!(DuplicatePolicy::of("Set-Cookie"), DuplicatePolicy::KeepAll);
+        assert_eq!(DuplicatePolicy::of("Accept"), DuplicatePolicy::Merge);
+        assert_eq!(DuplicatePolicy::of("Content-Type"), DuplicatePolicy::FirstWins);
+    }
+
+    #[test]
+    fn error() {
+        assert!(insert_twice("Host", "a", "a", true).is_err());
+        assert_eq!(insert_twice("Host", "a", "b", false), Ok(String::from("a")));
+    }
+
+    #[test]
+    fn identical() {
+        assert_eq!(insert_twice("Content-Length", "1", "1", true), Ok(String::from("1")));
+        assert!(insert_twice("Content-Length", "1", "2", true).is_err());
+        assert_eq!(insert_twice("Content-Length", "1", "2", false), Ok(String::from("1")));
+    }
+
+    #[test]
+    fn keep_all_merge_first_wins() {
+        assert_eq!(insert_twice("Set-Cookie", "a=1", "b=2", true), Ok(String::from("a=1\nb=2")));
+        assert_eq!(insert_twice("Accept", "text/html", "*/*", true), Ok(String::from("text/html, */*")));
+        assert_eq!(insert_twice("Content-Type", "a", "b", true), Ok(String::from("a")));
+    }
+}
@@ -0,0 +1,173 @@
+use std::net::Ipv6Addr;
+use std::str::FromStr;
+
+use crate::error::HttpParseError;
+use crate::request::Request;
+use crate::util::split_host_port;
+
+const HOST: &str = "Host";
+const HOST_MISSING: &str = "The Host header is missing";
+const HOST_MALFORMED: &str = "The Host header is malformed:";
+const HOST_DUPLICATED: &str = "The Host header was sent more than once";
+const HOST_NOT_ALLOWED: &str = "The host isn't allowed:";
+const WILDCARD_PREFIX: &str = "*.";
+const IPV6_START: char = '[';
+
+/// Guard against Host header attacks, which only lets Requests for the allowed hosts pass. <br>
+/// A pattern is either an exact host (`example.com`, `[::1]`) or a wildcard suffix (`*.example.com`),
+/// which matches every subdomain but not the domain itself. A port in the pattern (`example.com:8080`)
+/// requires exactly that port, otherwise every port is allowed. Patterns that aren't valid hosts never match
+///
+/// Example:
+/// ```
+/// use whdp::{HostGuard, Request};
+///
+/// let guard = HostGuard::new()
+///     .with_allowed("example.com")
+///     .with_allowed("*.example.com:8443");
+/// let req = Request::try_from("GET / HTTP/1.1\nHost: api.example.com:8443\n\n").unwrap();
+/// assert!(guard.check(&req).is_ok());
+/// let req = Request::try_from("GET / HTTP/1.1\nHost: evil.com\n\n").unwrap();
+/// assert!(guard.check(&req).is_err());
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct HostGuard {
+    allowed: Vec<String>,
+}
+
+impl HostGuard {
+    /// creates a new HostGuard without any allowed hosts
+    pub const fn new() -> Self {
+        Self { allowed: Vec::new() }
+    }
+    /// adds a pattern of allowed hosts
+    pub fn with_allowed(mut self, pattern: &str) -> Self {
+        self.allowed.push(String::from(pattern));
+        self
+    }
+    /// returns the patterns of the allowed hosts
+    pub fn get_allowed(&self) -> &[String] {
+        &self.allowed
+    }
+    /// looks if the host (with an optional port) matches one of the allowed patterns
+    pub fn is_allowed(&self, host: &str) -> bool {
+        split_authority(host).is_some_and(|(host, port)| {
+            self.allowed
+                .iter()
+                .any(|pattern| matches_pattern(pattern, &host, port))
+        })
+    }
+    /// checks the effective host of the Request (the authority of an absolute uri or the Host header). <br>
+    /// Returns a [Req](crate::ParseErrorKind::Req) error if the host is absent, malformed or duplicated
+    /// and a [HostNotAllowed](crate::ParseErrorKind::HostNotAllowed) error if it isn't allowed
+    pub fn check(&self, req: &Request) -> Result<(), HttpParseError> {
+        if req.had_duplicate(HOST) {
+            return Err(HttpParseError::request(HOST_DUPLICATED));
+        }
+        let host = req
+            .effective_host()
+            .ok_or(HttpParseError::request(HOST_MISSING))?;
+        if split_authority(&host).is_none() {
+            return Err(HttpParseError::request(format!("{} {}", HOST_MALFORMED, host)));
+        }
+        if !self.is_allowed(&host) {
+            return Err(HttpParseError::host_not_allowed(format!("{} {}", HOST_NOT_ALLOWED, host)));
+        }
+        Ok(())
+    }
+}
+
+fn matches_pattern(pattern: &str, host: &str, port: Option<u16>) -> bool {
+    let (wildcard, pattern) = match pattern.strip_prefix(WILDCARD_PREFIX) {
+        Some(rest) => (true, rest),
+        None => (false, pattern),
+    };
+    let Some((pattern_host, pattern_port)) = split_authority(pattern) else {
+        return false;
+    };
+    let host_matches = if wildcard {
+        host.strip_suffix(&pattern_host)
+            .and_then(|sub| sub.strip_suffix('.'))
+            .is_some_and(|sub| !sub.is_empty())
+    } else {
+        host == pattern_host
+    };
+    host_matches && pattern_port.is_none_or(|pattern_port| port == Some(pattern_port))
+}
+
+/// splits the authority into the lowercase host (IPv6 addresses normalized and without brackets)
+/// and the optional port or returns [None] if it is malformed
+fn split_authority(authority: &str) -> Option<(String, Option<u16>)> {
+    let (host, port) = split_host_port(authority)?;
+    if authority.starts_with(IPV6_START) {
+        return Some((Ipv6Addr::from_str(host).ok()?.to_string(), port));
+    }
+    let valid = !host.is_empty()
+        && host
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '-' || char == '.');
+    valid.then(|| (host.to_ascii_lowercase(), port))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HostGuard, ParseErrorKind, Request, resp_presets};
+
+    fn check(guard: &HostGuard, host: &str) -> Result<(), String> {
+        let req = Request::try_from(format!("GET / HTTP/1.1\nHost: {}\n\n", host)).unwrap();
+        guard
+            .check(&req)
+            .map_err(|err| String::from(err.get_msg().unwrap_or("")))
+    }
+
+    #[test]
+    fn ports() {
+        let guard = HostGuard::new().with_allowed("example.com").with_allowed("api.example.com:8080");
+        assert!(check(&guard, "example.com").is_ok());
+        assert!(check(&guard, "EXAMPLE.com:1234").is_ok());
+        assert!(check(&guard, "api.example.com:8080").is_ok());
+        assert!(check(&guard, "api.example.com:8081").unwrap_err().starts_with("The host isn't allowed"));
+        assert!(check(&guard, "api.example.com").is_err());
+        assert!(check(&guard, "example.com:99999").unwrap_err().starts_with("The Host header is malformed"));
+        assert!(check(&guard, "example.com:").unwrap_err().starts_with("The Host header is malformed"));
+    }
+
+    #[test]
+    fn ipv6() {
+        let guard = HostGuard::new().with_allowed("[::1]:8080").with_allowed("[2001:db8::1]");
+        assert!(check(&guard, "[::1]:8080").is_ok());
+        assert!(check(&guard, "[0:0:0:0:0:0:0:1]:8080").is_ok());
+        assert!(check(&guard, "[::1]").is_err());
+        assert!(check(&guard, "[2001:DB8::1]:443").is_ok());
+        assert!(check(&guard, "::1").unwrap_err().starts_with("The Host header is malformed"));
+        assert!(check(&guard, "[::1]8080").unwrap_err().starts_with("The Host header is malformed"));
+    }
+
+    #[test]
+    fn wildcard() {
+        let guard = HostGuard::new().with_allowed("*.example.com");
+        assert!(check(&guard, "api.example.com").is_ok());
+        assert!(check(&guard, "a.b.example.com:443").is_ok());
+        assert!(check(&guard, "example.com").is_err());
+        assert!(check(&guard, "evilexample.com").is_err());
+        assert!(check(&guard, ".example.com").unwrap_err().starts_with("The host isn't allowed"));
+    }
+
+    #[test]
+    fn missing_and_duplicated() {
+        let guard = HostGuard::new().with_allowed("example.com");
+        let req = Request::try_from("GET / HTTP/1.1\n\n").unwrap();
+        assert_eq!(guard.check(&req).unwrap_err().get_msg(), Some("The Host header is missing"));
+        let req = Request::try_from("GET / HTTP/1.1\nHost: example.com\nhost: evil.com\n\n").unwrap();
+        assert_eq!(guard.check(&req).unwrap_err().get_msg(), Some("The Host header was sent more than once"));
+        let req = Request::try_from("GET http://example.com/path HTTP/1.1\nHost: example.com\n\n").unwrap();
+        assert!(guard.check(&req).is_ok());
+        let req = Request::try_from("GET / HTTP/1.1\nHost: exa mple.com\n\n").unwrap();
+        let err = guard.check(&req).unwrap_err();
+        assert_eq!(resp_presets::from_error(&err).get_status().get_code(), &400);
+        let req = Request::try_from("GET / HTTP/1.1\nHost: evil.com\n\n").unwrap();
+        let err = guard.check(&req).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::HostNotAllowed);
+        assert_eq!(resp_presets::from_error(&err).get_status().get_code(), &421);
+    }
+}
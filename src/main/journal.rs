@@ -0,0 +1,204 @@
+use std::fmt::{Debug, Display, Formatter};
+
+const UNSET: &str = "(unset)";
+
+/// The kind of a recorded header mutation
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum HeaderAction {
+    /// the header didn't exist before
+    Add,
+    /// the value of an existing header was replaced
+    Set,
+    /// the header was removed
+    Remove,
+}
+
+/// A single header mutation recorded by the header journal of a [Response](crate::Response)
+/// with the value before and after and the tag of the caller (if any)
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct HeaderChange {
+    action: HeaderAction,
+    name: String,
+    old: Option<String>,
+    new: Option<String>,
+    tag: Option<String>,
+}
+
+impl HeaderChange {
+    /// returns the kind of the mutation
+    pub fn get_action(&self) -> HeaderAction {
+        self.action
+    }
+    /// returns the name of the header
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    /// returns the value before or [None] if the header didn't exist
+    pub fn get_old(&self) -> Option<&str> {
+        self.old.as_deref()
+    }
+    /// returns the value after or [None] if the header was removed
+    pub fn get_new(&self) -> Option<&str> {
+        self.new.as_deref()
+    }
+    /// returns the tag the caller supplied (like `cors-layer`)
+    pub fn get_tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+}
+
+impl Display for HeaderChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(tag) = &self.tag {
+            write!(f, "[{}] ", tag)?;
+        }
+        write!(
+            f,
+            "{:?} {}: {} -> {}",
+            self.action,
+            self.name,
+            self.old.as_deref().unwrap_or(UNSET),
+            self.new.as_deref().unwrap_or(UNSET)
+        )
+    }
+}
+
+impl Debug for HeaderChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// The opt-in journal of header mutations, which is disabled by default. <br>
+/// Every journal is equal to every other, so it never affects the equality of a Response
+#[derive(Clone, Default)]
+pub(crate) struct HeaderJournal(Option<Vec<HeaderChange>>);
+
+impl HeaderJournal {
+    /// starts recording (keeps the already recorded changes)
+    pub(crate) fn enable(&mut self) {
+        self.0.get_or_insert_with(Vec::new);
+    }
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.0.is_some()
+    }
+    /// returns the recorded changes (empty if the journal is disabled)
+    pub(crate) fn entries(&self) -> &[HeaderChange] {
+        self.0.as_deref().unwrap_or_default()
+    }
+    /// records the change if the journal is enabled and the value changed
+    pub(crate) fn record(&mut self, name: &str, old: Option<String>, new: Option<String>, tag: Option<&str>) {
+        let Some(entries) = &mut self.0 else {
+            return;
+        };
+        let action = match (&old, &new) {
+            (None, Some(_)) => HeaderAction::Add,
+            (Some(_), Some(_)) => HeaderAction::Set,
+            (Some(_), None) => HeaderAction::Remove,
+            (None, None) => return,
+        };
+        entries.push(HeaderChange {
+            action,
+            name: String::from(name),
+            old,
+            new,
+            tag: tag.map(String::from),
+        });
+    }
+}
+
+impl PartialEq for HeaderJournal {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for HeaderJournal {}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use crate::{HeaderAction, Response, resp_presets};
+
+    fn cors_layer(resp: &mut Response) {
+        resp.add_header_tagged((String::from("Access-Control-Allow-Origin"), String::from("*")), "cors-layer");
+    }
+
+    fn keep_alive_layer(resp: &mut Response) {
+        resp.add_header_tagged((String::from("Connection"), String::from("keep-alive")), "keep-alive-layer");
+    }
+
+    fn shutdown_layer(resp: &mut Response) {
+        resp.add_header_tagged((String::from("Connection"), String::from("close")), "shutdown-layer");
+        resp.remove_header_tagged("Access-Control-Allow-Origin", "shutdown-layer");
+    }
+
+    #[test]
+    fn middleware_chain() {
+        let mut resp = resp_presets::ok("body");
+        resp.enable_header_journal();
+        cors_layer(&mut resp);
+        keep_alive_layer(&mut resp);
+        shutdown_layer(&mut resp);
+        resp.remove_header("Missing");
+        let journal = resp.header_journal();
+        assert_eq!(journal.len(), 4);
+        assert_eq!(journal[0].get_action(), HeaderAction::Add);
+        assert_eq!(journal[0].get_tag(), Some("cors-layer"));
+        assert_eq!(journal[2].get_action(), HeaderAction::Set);
+        assert_eq!(journal[2].get_old(), Some("keep-alive"));
+        assert_eq!(journal[2].get_new(), Some("close"));
+        assert_eq!(journal[2].to_string(), "[shutdown-layer] Set Connection: keep-alive -> close");
+        assert_eq!(journal[3].get_action(), HeaderAction::Remove);
+        assert_eq!(journal[3].get_new(), None);
+    }
+
+    #[test]
+    fn mutation_points() {
+        let mut resp = resp_presets::ok("body");
+        resp.enable_header_journal();
+        resp.set_body("longer body");
+        resp.append_body("!");
+        resp.set_body("longer body!");
+        write!(resp.body_writer(), "?").unwrap();
+        resp.set_retry_after_secs(5);
+        resp.try_insert((String::from("Retry-After"), String::from("10")));
+        resp.clear_body();
+        resp.add_header((String::from("Connection"), String::from("close, X-Hop")));
+        resp.add_header((String::from("X-Hop"), String::from("1")));
+        resp.strip_hop_by_hop();
+        let resp = resp.with_added_header("Vary", "Accept").with_removed_header("Vary");
+        let changes: Vec<String> = resp.header_journal().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            changes,
+            [
+                "Set Content-Length: 4 -> 11",
+                "Set Content-Length: 11 -> 12",
+                "Set Content-Length: 12 -> 13",
+                "Add Retry-After: (unset) -> 5",
+                "Remove Content-Length: 13 -> (unset)",
+                "Add Connection: (unset) -> close, X-Hop",
+                "Add X-Hop: (unset) -> 1",
+                "Remove Connection: close, X-Hop -> (unset)",
+                "Remove X-Hop: 1 -> (unset)",
+                "Add Vary: (unset) -> Accept",
+                "Remove Vary: Accept -> (unset)",
+            ]
+        );
+    }
+
+    #[test]
+    fn disabled_and_invisible() {
+        let mut plain = resp_presets::ok("body");
+        let mut journaled = plain.clone();
+        journaled.enable_header_journal();
+        plain.add_header_tagged((String::from("X-A"), String::from("1")), "tag");
+        journaled.add_header_tagged((String::from("X-A"), String::from("1")), "tag");
+        assert!(plain.header_journal().is_empty());
+        assert_eq!(journaled.header_journal().len(), 1);
+        assert_eq!(plain, journaled);
+        assert_eq!(plain.to_string(), journaled.to_string());
+        assert_eq!(plain.to_bytes().unwrap(), journaled.to_bytes().unwrap());
+    }
+}
@@ -1,21 +1,109 @@
+pub use cache_control::CacheControl;
+pub use content_disposition::ContentDisposition;
+pub use content_disposition::parse_content_disposition;
+pub use date::Expires;
+pub use date::format_http_date;
+pub use date::parse_http_date;
+pub use diff::FieldDiff;
 pub use error::HttpParseError;
+pub use error::HttpParseErrorBuilder;
+pub use error::MissingField;
 pub use error::ParseErrorKind;
+pub use etag::ETag;
+pub use fingerprint::FingerprintConfig;
+pub use forwarded::ForwardedElement;
+pub use header::DuplicatePolicy;
+pub use header::HeaderMap;
+pub use host_guard::HostGuard;
+pub use journal::HeaderAction;
+pub use journal::HeaderChange;
+pub use link::Link;
+pub use media_type::MediaType;
+pub use method::BodyExpectation;
 pub use method::HttpMethod;
+#[cfg(any(test, feature = "test-util"))]
+pub use mock_stream::MockStream;
+pub use multipart::MultipartBuilder;
+pub use negotiate::LanguageRange;
+pub use options::ParseOptions;
+pub use options::SoftLimits;
+pub use parse_warning::ParseWarning;
+pub use problem::Problem;
 pub use request::Request;
+pub use request::RequestBuilder;
+pub use request::RequestHead;
+pub use request::RequestView;
 pub use response::resp_presets;
 pub use response::Response;
 pub use response::ResponseBuilder;
+pub use retry_after::RetryAfter;
+pub use router::Capabilities;
+pub use router::handle_options;
+pub use router::MethodMap;
+pub use router::PathMap;
+pub use security::SecurityHeaders;
+pub use server::Connection;
+pub use server::serve_connection;
+pub use server::serve_connection_with_guard;
 pub use status::HttpStatus;
 pub use status::HttpStatusGroup;
 pub use status::status_presets;
+pub use status_error::StatusError;
+pub use stream::BodyFraming;
+#[cfg(any(test, feature = "test-util"))]
+pub use test_util::{assert_response_matches, BodyMatcher, HeaderExpectation, ResponseExpectation};
+pub use uri::Target;
+pub use util::BodyWriter;
 pub use util::Destruct;
+pub use util::escape_html;
+pub use util::is_valid_percent_encoding;
+pub use util::LineEnding;
 pub use util::TryRequest;
+pub use validation::Severity;
+pub use validation::ValidationIssue;
 pub use version::HttpVersion;
+#[cfg(any(test, feature = "websocket"))]
+pub use websocket::WebSocketHandshake;
 
+mod cache_control;
+mod content_disposition;
+mod date;
+mod diff;
 mod error;
+mod etag;
+mod fingerprint;
+mod form;
+mod forwarded;
+#[cfg(test)]
+mod golden;
+mod header;
+mod host_guard;
+mod journal;
+mod link;
+mod media_type;
 mod method;
+#[cfg(any(test, feature = "test-util"))]
+mod mock_stream;
+mod multipart;
+mod negotiate;
+mod options;
+mod parse_warning;
+mod problem;
 mod request;
 mod response;
+mod retry_after;
+mod router;
+mod security;
+mod server;
+mod sha256;
 mod status;
+mod status_error;
+mod stream;
+#[cfg(any(test, feature = "test-util"))]
+mod test_util;
+mod uri;
 mod util;
-mod version;
\ No newline at end of file
+mod validation;
+mod version;
+#[cfg(any(test, feature = "websocket"))]
+mod websocket;
\ No newline at end of file
@@ -0,0 +1,184 @@
+use std::fmt::{Debug, Display, Formatter};
+
+use crate::util::{quote_if_needed, split_quoted, unquote};
+
+const REL: &str = "rel";
+const TARGET_START: char = '<';
+const TARGET_END: char = '>';
+const DOUBLE_QUOTE: char = '"';
+const BACKSLASH: char = '\\';
+const LINK_DELIMITER: char = ',';
+const PARAMETER_DELIMITER: char = ';';
+
+/// Struct for a single link of the Link header ([RFC 8288](https://www.rfc-editor.org/rfc/rfc8288))
+/// with its target and parameters (like the relation types of `rel`). <br>
+/// Parameter names are matched case-insensitive and quoted values are unescaped
+///
+/// Example:
+/// ```
+/// use whdp::Link;
+///
+/// let link = Link::new("https://api.example.com/items?page=2", "next").with_param("title", "Page 2");
+/// assert_eq!(link.to_string(), "<https://api.example.com/items?page=2>; rel=next; title=\"Page 2\"");
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Link {
+    target: String,
+    params: Vec<(String, String)>,
+}
+
+impl Link {
+    /// creates a new Link to the target with the relation type (or several separated by spaces)
+    pub fn new(target: &str, rel: &str) -> Self {
+        Self {
+            target: String::from(target),
+            params: vec![(String::from(REL), String::from(rel))],
+        }
+    }
+    /// adds a parameter (like `type` or `title`)
+    pub fn with_param(mut self, name: &str, value: &str) -> Self {
+        self.params.push((String::from(name), String::from(value)));
+        self
+    }
+    /// returns the target without the angle brackets
+    pub fn get_target(&self) -> &str {
+        &self.target
+    }
+    /// returns the parameters in order with their unescaped values
+    pub fn get_params(&self) -> &[(String, String)] {
+        &self.params
+    }
+    /// returns the unescaped value of the parameter (the name is matched case-insensitive). <br>
+    /// If the parameter is repeated the first one wins
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+    /// returns the relation types of the `rel` parameter (`rel="next prefetch"` has two)
+    pub fn rel_types(&self) -> Vec<&str> {
+        self.param(REL).map(|rel| rel.split_whitespace().collect()).unwrap_or_default()
+    }
+    /// looks if one of the relation types is the given one (case-insensitive)
+    pub fn has_rel(&self, rel: &str) -> bool {
+        self.rel_types().iter().any(|rel_type| rel_type.eq_ignore_ascii_case(rel))
+    }
+}
+
+impl Display for Link {
+    /// writes the link with the values quoted where needed
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", TARGET_START, self.target, TARGET_END)?;
+        for (key, value) in &self.params {
+            write!(f, "{} {}={}", PARAMETER_DELIMITER, key, quote_if_needed(value))?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Link {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// parses the value of the Link header into its links. <br>
+/// Commas inside the target or a quoted value don't separate links, malformed links are ignored
+pub(crate) fn parse_links(header: &str) -> Vec<Link> {
+    split_links(header)
+        .into_iter()
+        .filter_map(parse_link)
+        .collect()
+}
+
+fn parse_link(link: &str) -> Option<Link> {
+    let rest = link.trim().strip_prefix(TARGET_START)?;
+    let (target, params) = rest.split_once(TARGET_END)?;
+    let mut split = split_quoted(params, PARAMETER_DELIMITER).into_iter();
+    if !split.next()?.trim().is_empty() {
+        return None;
+    }
+    let params = split
+        .map(|param| param.split_once('=').unwrap_or((param, "")))
+        .map(|(key, value)| (String::from(key.trim()), unquote(value.trim())))
+        .filter(|(key, _)| !key.is_empty())
+        .collect();
+    Some(Link {
+        target: String::from(target.trim()),
+        params,
+    })
+}
+
+/// splits the header at the commas outside of the targets and quoted values
+fn split_links(header: &str) -> Vec<&str> {
+    let mut links = Vec::new();
+    let (mut target, mut quoted, mut escaped) = (false, false, false);
+    let mut start = 0;
+    for (index, char) in header.char_indices() {
+        match char {
+            _ if escaped => escaped = false,
+            BACKSLASH if quoted => escaped = true,
+            DOUBLE_QUOTE if !target => quoted = !quoted,
+            TARGET_START if !quoted => target = true,
+            TARGET_END if !quoted => target = false,
+            LINK_DELIMITER if !quoted && !target => {
+                links.push(&header[start..index]);
+                start = index + char.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    links.push(&header[start..]);
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Link, resp_presets};
+    use crate::link::parse_links;
+
+    #[test]
+    fn github_pagination() {
+        let links = parse_links(
+            "<https://api.github.com/repositories/1300192/issues?page=2>; rel=\"prev\", \
+            <https://api.github.com/repositories/1300192/issues?page=4>; rel=\"next\", \
+            <https://api.github.com/repositories/1300192/issues?page=515>; rel=\"last\", \
+            <https://api.github.com/repositories/1300192/issues?page=1>; rel=\"first\"",
+        );
+        assert_eq!(links.len(), 4);
+        assert_eq!(links[1].get_target(), "https://api.github.com/repositories/1300192/issues?page=4");
+        assert!(links[1].has_rel("next"));
+        assert!(links[2].has_rel("LAST"));
+    }
+
+    #[test]
+    fn edge_cases() {
+        let links = parse_links("<https://example.com/a,b;c>; rel=\"next prefetch\"; title=\"a, \\\"b\\\"; c\", no-target; rel=x, </x>; rel=alternate; type=text/html");
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[0].get_target(), "https://example.com/a,b;c");
+        assert_eq!(links[0].rel_types(), ["next", "prefetch"]);
+        assert!(links[0].has_rel("prefetch"));
+        assert_eq!(links[0].param("Title"), Some("a, \"b\"; c"));
+        assert_eq!(links[1].param("type"), Some("text/html"));
+        assert_eq!(parse_links(&links[0].to_string()), [links[0].clone()]);
+    }
+
+    #[test]
+    fn response() {
+        let mut resp = resp_presets::ok("items");
+        assert!(resp.get_links().is_empty());
+        resp.add_link(Link::new("/items?page=2", "next"));
+        resp.add_link(Link::new("/items?page=9", "last"));
+        assert_eq!(resp.get_header("Link").unwrap(), "</items?page=2>; rel=next, </items?page=9>; rel=last");
+        assert_eq!(resp.find_link("last").unwrap().get_target(), "/items?page=9");
+        assert!(resp.find_link("prev").is_none());
+
+        let mut resp = resp_presets::ok("items");
+        resp.add_header((String::from("link"), String::from("</a>; rel=\"next prefetch\"")));
+        resp.add_link(Link::new("/b", "last"));
+        assert_eq!(resp.get_headers().len(), 3);
+        assert_eq!(resp.get_links().len(), 2);
+        assert_eq!(resp.find_link("prefetch").unwrap().get_target(), "/a");
+    }
+}
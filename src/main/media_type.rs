@@ -0,0 +1,165 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+use crate::error::HttpParseError;
+use crate::util::{split_quoted, unquote};
+
+const MALFORMED_MEDIA_TYPE: &str = "The media type is malformed:";
+const BOUNDARY: &str = "boundary";
+const CHARSET: &str = "charset";
+const TYPE_DELIMITER: char = '/';
+const PARAMETER_DELIMITER: char = ';';
+const RESTRICTED_NAME_SPECIALS: &str = "!#$&-^_.+";
+
+/// Struct for a media type like the value of the Content-Type header
+/// (for example `multipart/form-data; boundary="----abc"`). <br>
+/// The type and subtype are lowercase, parameter names are matched case-insensitive
+/// and quoted parameter values are unescaped
+///
+/// Example:
+/// ```
+/// use whdp::MediaType;
+///
+/// let media_type: MediaType = "Text/HTML; Charset=\"utf-8\"".parse().unwrap();
+/// assert_eq!(media_type.get_essence(), "text/html");
+/// assert_eq!(media_type.charset(), Some("utf-8"));
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct MediaType {
+    essence: String,
+    parameters: Vec<(String, String)>,
+}
+
+impl MediaType {
+    /// returns the type and subtype without parameters (like `text/html`)
+    pub fn get_essence(&self) -> &str {
+        &self.essence
+    }
+    /// returns the type (like `text`)
+    pub fn get_type(&self) -> &str {
+        self.essence.split(TYPE_DELIMITER).next().unwrap_or_default()
+    }
+    /// returns the subtype (like `html`)
+    pub fn get_subtype(&self) -> &str {
+        self.essence.split(TYPE_DELIMITER).nth(1).unwrap_or_default()
+    }
+    /// returns the parameters in order with their unescaped values
+    pub fn get_parameters(&self) -> &[(String, String)] {
+        &self.parameters
+    }
+    /// returns the unescaped value of the parameter (the name is matched case-insensitive). <br>
+    /// If the parameter is repeated the first one wins
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+    /// returns the boundary parameter of a multipart media type
+    pub fn boundary(&self) -> Option<&str> {
+        self.parameter(BOUNDARY)
+    }
+    /// returns the charset parameter
+    pub fn charset(&self) -> Option<&str> {
+        self.parameter(CHARSET)
+    }
+}
+
+impl FromStr for MediaType {
+    type Err = HttpParseError;
+    /// parses the media type, parameters without a value are ignored
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut split = split_quoted(s, PARAMETER_DELIMITER).into_iter();
+        let essence = split.next().unwrap_or_default().trim();
+        let valid = essence
+            .split_once(TYPE_DELIMITER)
+            .is_some_and(|(main, sub)| is_token(main) && is_token(sub));
+        if !valid {
+            return Err(HttpParseError::util(format!("{} {}", MALFORMED_MEDIA_TYPE, s)));
+        }
+        let parameters = split
+            .filter_map(|param| param.split_once('='))
+            .map(|(key, value)| (String::from(key.trim()), unquote(value.trim())))
+            .filter(|(key, _)| !key.is_empty())
+            .collect();
+        Ok(Self {
+            essence: essence.to_ascii_lowercase(),
+            parameters,
+        })
+    }
+}
+
+impl TryFrom<&str> for MediaType {
+    type Error = HttpParseError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::from_str(value)
+    }
+}
+
+fn is_token(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || RESTRICTED_NAME_SPECIALS.contains(char))
+}
+
+impl Display for MediaType {
+    /// writes the media type with all parameter values quoted
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.essence)?;
+        for (key, value) in &self.parameters {
+            write!(f, "; {}=\"{}\"", key, value.replace('\\', "\\\\").replace('"', "\\\""))?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for MediaType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{MediaType, Request};
+
+    #[test]
+    fn quoted_boundary() {
+        let media_type = MediaType::from_str("multipart/form-data; boundary=\"--=_a;b,c \\\"d\\\"\"; charset=utf-8").unwrap();
+        assert_eq!(media_type.get_type(), "multipart");
+        assert_eq!(media_type.get_subtype(), "form-data");
+        assert_eq!(media_type.boundary(), Some("--=_a;b,c \"d\""));
+        assert_eq!(media_type.charset(), Some("utf-8"));
+        assert_eq!(MediaType::from_str(&media_type.to_string()).unwrap(), media_type);
+    }
+
+    #[test]
+    fn uppercase_parameters() {
+        let media_type = MediaType::from_str("Multipart/Form-Data; BOUNDARY=WebKitFormBoundary7MA4; Boundary=second").unwrap();
+        assert_eq!(media_type.get_essence(), "multipart/form-data");
+        assert_eq!(media_type.boundary(), Some("WebKitFormBoundary7MA4"));
+        assert_eq!(media_type.parameter("Boundary"), Some("WebKitFormBoundary7MA4"));
+        assert_eq!(media_type.get_parameters().len(), 2);
+        assert_eq!(media_type.charset(), None);
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(MediaType::from_str("text").is_err());
+        assert!(MediaType::from_str("text/").is_err());
+        assert!(MediaType::from_str("te xt/html").is_err());
+        assert!(MediaType::from_str("").is_err());
+    }
+
+    #[test]
+    fn request() {
+        let req = Request::try_from("POST / HTTP/1.1\ncontent-type: Application/JSON; Charset=\"UTF-8\"\n\n").unwrap();
+        let media_type = req.get_content_type().unwrap();
+        assert_eq!(media_type.get_essence(), "application/json");
+        assert_eq!(req.charset(), Some(String::from("UTF-8")));
+        assert!(Request::default().get_content_type().is_none());
+    }
+}
@@ -2,7 +2,7 @@ use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 use wjp::{ParseError, Serialize, Values};
 
-use crate::error::{HttpParseError, ParseErrorKind::Method};
+use crate::error::HttpParseError;
 use crate::util::{INDEX_WAS_WRONG, OPTION_WAS_EMPTY};
 
 const NAME_NOT_EXIST: &str = "Couldn't find a valid HTTP method to that string ";
@@ -51,6 +51,46 @@ pub enum HttpMethod {
     Trace,
 }
 
+/// Enum for how a body on a Request with a specific [HttpMethod] is treated
+/// ([RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-9.3))
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum BodyExpectation {
+    /// A body is always a mistake (HEAD and TRACE)
+    Forbidden,
+    /// A body has no defined meaning and many servers reject it (GET, DELETE, CONNECT and OPTIONS)
+    Discouraged,
+    /// A body is the usual case (POST, PUT and PATCH)
+    Expected,
+}
+
+impl HttpMethod {
+    /// returns how a body on a Request with this method is treated
+    pub const fn body_expectation(&self) -> BodyExpectation {
+        match self {
+            HttpMethod::Head | HttpMethod::Trace => BodyExpectation::Forbidden,
+            HttpMethod::Get | HttpMethod::Delete | HttpMethod::Connect | HttpMethod::Options => {
+                BodyExpectation::Discouraged
+            }
+            HttpMethod::Post | HttpMethod::Put | HttpMethod::Patch => BodyExpectation::Expected,
+        }
+    }
+    /// returns every HTTP Method in the same order as their names
+    /// (POST, GET, PUT, DELETE, PATCH, HEAD, CONNECT, OPTIONS, TRACE)
+    pub const fn all() -> [HttpMethod; 9] {
+        [
+            HttpMethod::Post,
+            HttpMethod::Get,
+            HttpMethod::Put,
+            HttpMethod::Delete,
+            HttpMethod::Patch,
+            HttpMethod::Head,
+            HttpMethod::Connect,
+            HttpMethod::Options,
+            HttpMethod::Trace,
+        ]
+    }
+}
+
 impl FromStr for HttpMethod {
     type Err = HttpParseError;
 
@@ -59,14 +99,14 @@ impl FromStr for HttpMethod {
             .iter()
             .position(|&idx| idx.eq_ignore_ascii_case(s))
             .map(HttpMethod::try_from)
-            .ok_or(HttpParseError::from((Method, NAME_NOT_EXIST)))?
+            .ok_or(HttpParseError::method(NAME_NOT_EXIST))?
     }
 }
 
 impl TryFrom<Option<&str>> for HttpMethod {
     type Error = HttpParseError;
     fn try_from(value: Option<&str>) -> Result<Self, Self::Error> {
-        value.ok_or(HttpParseError::from((Method, OPTION_WAS_EMPTY)))
+        value.ok_or(HttpParseError::method(OPTION_WAS_EMPTY))
             .map(HttpMethod::from_str)?
     }
 }
@@ -97,7 +137,7 @@ impl TryFrom<usize> for HttpMethod {
             6 => Ok(HttpMethod::Connect),
             7 => Ok(HttpMethod::Options),
             8 => Ok(HttpMethod::Trace),
-            _ => Err(HttpParseError::from((Method, INDEX_WAS_WRONG))),
+            _ => Err(HttpParseError::method(INDEX_WAS_WRONG)),
         }
     }
 }
@@ -113,3 +153,18 @@ impl Display for HttpMethod {
         Debug::fmt(self, f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::HttpMethod;
+
+    #[test]
+    fn all() {
+        let all = HttpMethod::all();
+        for (index, method) in all.iter().enumerate() {
+            assert_eq!(HttpMethod::try_from(index), Ok(*method));
+        }
+        let names: Vec<String> = all.iter().map(HttpMethod::to_string).collect();
+        assert_eq!(names, vec!["POST", "GET", "PUT", "DELETE", "PATCH", "HEAD", "CONNECT", "OPTIONS", "TRACE"]);
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind, Read, Write};
+use std::thread;
+use std::time::Duration;
+
+/// A single scripted step of the read side of a [MockStream]
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+enum ReadStep {
+    Data(Vec<u8>),
+    WouldBlock,
+    Delay(Duration),
+}
+
+/// In-memory stream implementing [Read] and [Write] with scripted behavior
+/// to test everything that reads from or writes to a connection without opening a socket. <br>
+/// Reads return the queued chunks in order (a chunk is never merged with the next one),
+/// a [WouldBlock](ErrorKind::WouldBlock) step fails a single read and a delay sleeps before the next step.
+/// After the script is exhausted every read returns `0` like a closed connection.
+/// Everything written is captured
+///
+/// Example:
+/// ```
+/// use whdp::{MockStream, ParseOptions, Request};
+///
+/// let mut stream = MockStream::new()
+///     .with_read(b"POST / HTTP/1.1\r\nExpect: 100-continue\r\n")
+///     .with_read(b"Content-Length: 2\r\n\r\nHi");
+/// let req = Request::from_stream_with_continue(&mut stream, &ParseOptions::default()).unwrap();
+/// assert_eq!(req.get_body(), "Hi");
+/// stream.assert_written(b"HTTP/1.1 100 Continue\r\n\r\n");
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct MockStream {
+    reads: VecDeque<ReadStep>,
+    written: Vec<u8>,
+}
+
+impl MockStream {
+    /// creates a new MockStream without anything to read
+    pub const fn new() -> Self {
+        Self {
+            reads: VecDeque::new(),
+            written: Vec::new(),
+        }
+    }
+    /// queues a chunk of bytes to read
+    pub fn with_read(mut self, bytes: &[u8]) -> Self {
+        self.reads.push_back(ReadStep::Data(bytes.to_vec()));
+        self
+    }
+    /// queues a read that fails with [WouldBlock](ErrorKind::WouldBlock)
+    pub fn with_would_block(mut self) -> Self {
+        self.reads.push_back(ReadStep::WouldBlock);
+        self
+    }
+    /// queues a delay before the next step
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.reads.push_back(ReadStep::Delay(delay));
+        self
+    }
+    /// looks if every scripted step was read
+    pub fn is_exhausted(&self) -> bool {
+        self.reads.is_empty()
+    }
+    /// returns everything that was written to the stream
+    pub fn get_written(&self) -> &[u8] {
+        &self.written
+    }
+    /// returns everything that was written to the stream as (lossy) UTF-8
+    pub fn written_string(&self) -> String {
+        String::from_utf8_lossy(&self.written).into_owned()
+    }
+    /// panics with a readable message if the written bytes aren't exactly the expected ones
+    #[track_caller]
+    pub fn assert_written(&self, expected: &[u8]) {
+        assert!(
+            self.written == expected,
+            "the written bytes differ:\nexpected: {:?}\nactual:   {:?}",
+            String::from_utf8_lossy(expected),
+            self.written_string()
+        );
+    }
+}
+
+impl Read for MockStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while let Some(step) = self.reads.pop_front() {
+            match step {
+                ReadStep::Delay(delay) => thread::sleep(delay),
+                ReadStep::WouldBlock => return Err(Error::from(ErrorKind::WouldBlock)),
+                ReadStep::Data(mut data) => {
+                    let length = data.len().min(buf.len());
+                    buf[..length].copy_from_slice(&data[..length]);
+                    if length < data.len() {
+                        self.reads.push_front(ReadStep::Data(data.split_off(length)));
+                    }
+                    if length > 0 || buf.is_empty() {
+                        return Ok(length);
+                    }
+                }
+            }
+        }
+        Ok(0)
+    }
+}
+
+impl Write for MockStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.written.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{ErrorKind, Read, Write};
+    use std::time::{Duration, Instant};
+
+    use crate::MockStream;
+
+    #[test]
+    fn scripted_reads() {
+        let mut stream = MockStream::new()
+            .with_read(b"Hello")
+            .with_delay(Duration::from_millis(20))
+            .with_would_block()
+            .with_read(b"")
+            .with_read(b", World");
+        let mut buf = [0; 3];
+        assert_eq!(stream.read(&mut buf).unwrap(), 3);
+        assert_eq!(&buf, b"Hel");
+        assert_eq!(stream.read(&mut buf).unwrap(), 2);
+        let start = Instant::now();
+        assert_eq!(stream.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        let mut rest = String::new();
+        stream.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, ", World");
+        assert!(stream.is_exhausted());
+        assert_eq!(stream.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn captured_writes() {
+        let mut stream = MockStream::default();
+        stream.write_all(b"HTTP/1.1 ").unwrap();
+        stream.write_all(b"200 OK\r\n").unwrap();
+        stream.flush().unwrap();
+        assert_eq!(stream.get_written(), b"HTTP/1.1 200 OK\r\n");
+        assert_eq!(stream.written_string(), "HTTP/1.1 200 OK\r\n");
+        stream.assert_written(b"HTTP/1.1 200 OK\r\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "the written bytes differ")]
+    fn assert_written_mismatch() {
+        MockStream::new().assert_written(b"missing");
+    }
+}
@@ -0,0 +1,229 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::content_disposition::quote_param;
+use crate::error::HttpParseError;
+use crate::media_type::MediaType;
+
+const MULTIPART_FORM_DATA: &str = "multipart/form-data";
+const BOUNDARY_PREFIX: &str = "whdp-boundary-";
+const DASHES: &str = "--";
+const CRLF: &str = "\r\n";
+const MAX_BOUNDARY_LENGTH: usize = 70;
+const BOUNDARY_SPECIALS: &str = "'()+_,-./:=? ";
+const INVALID_BOUNDARY: &str = "The boundary needs 1 to 70 digits, letters or of the characters '()+_,-./:=? and mustn't end with a space:";
+const INVALID_CONTENT_TYPE: &str = "The Content-Type of a part has to be a media type without CR, LF or NUL:";
+
+static BOUNDARY_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Builder for a `multipart/form-data` body
+/// ([RFC 7578](https://www.rfc-editor.org/rfc/rfc7578)) made of text fields and files. <br>
+/// Names and filenames are written like the parameters of a [ContentDisposition](crate::ContentDisposition)
+/// (quoted and escaped if needed, CR and LF percent-encoded). Files may be binary,
+/// see [build_multipart_bytes](crate::RequestBuilder::build_multipart_bytes).
+/// The boundary is random unless it is overridden with [with_boundary](MultipartBuilder::with_boundary)
+///
+/// Example:
+/// ```
+/// use whdp::MultipartBuilder;
+///
+/// let (content_type, body) = MultipartBuilder::new()
+///     .with_boundary("XyZ")
+///     .unwrap()
+///     .add_text("name", "whdp")
+///     .finish();
+/// assert_eq!(content_type, "multipart/form-data; boundary=XyZ");
+/// assert_eq!(body, b"--XyZ\r\nContent-Disposition: form-data; name=name\r\n\r\nwhdp\r\n--XyZ--\r\n");
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct MultipartBuilder {
+    boundary: String,
+    body: Vec<u8>,
+}
+
+impl MultipartBuilder {
+    /// creates a new MultipartBuilder without parts and with a random boundary
+    pub fn new() -> Self {
+        Self {
+            boundary: random_boundary(),
+            body: Vec::new(),
+        }
+    }
+    /// replaces the random boundary (for example for deterministic tests). <br>
+    /// The boundary mustn't occur in any of the parts.
+    /// Returns a [Util](crate::ParseErrorKind::Util) error if it isn't a valid boundary
+    /// ([RFC 2046](https://www.rfc-editor.org/rfc/rfc2046#section-5.1.1)), which also keeps CR and LF out of the headers
+    pub fn with_boundary(mut self, boundary: &str) -> Result<Self, HttpParseError> {
+        let valid = (1..=MAX_BOUNDARY_LENGTH).contains(&boundary.len())
+            && !boundary.ends_with(' ')
+            && boundary.chars().all(|char| char.is_ascii_alphanumeric() || BOUNDARY_SPECIALS.contains(char));
+        if !valid {
+            return Err(HttpParseError::header(format!("{} {:?}", INVALID_BOUNDARY, boundary)));
+        }
+        self.boundary = String::from(boundary);
+        Ok(self)
+    }
+    /// returns the boundary that separates the parts
+    pub fn get_boundary(&self) -> &str {
+        &self.boundary
+    }
+    /// adds a text field
+    pub fn add_text(mut self, name: &str, value: &str) -> Self {
+        self.add_part(format!("form-data; name={}", quote_param(name)), None, value.as_bytes());
+        self
+    }
+    /// adds a file with its filename and Content-Type (the bytes may be binary). <br>
+    /// Returns a [Util](crate::ParseErrorKind::Util) error if the Content-Type isn't a media type
+    /// or contains CR, LF or NUL
+    pub fn add_file(mut self, name: &str, filename: &str, content_type: &str, bytes: &[u8]) -> Result<Self, HttpParseError> {
+        if content_type.contains(['\r', '\n', '\0']) || content_type.parse::<MediaType>().is_err() {
+            return Err(HttpParseError::header(format!("{} {:?}", INVALID_CONTENT_TYPE, content_type)));
+        }
+        let disposition = format!("form-data; name={}; filename={}", quote_param(name), quote_param(filename));
+        self.add_part(disposition, Some(content_type), bytes);
+        Ok(self)
+    }
+    /// closes the body and returns the value of the Content-Type header with the boundary and the body
+    pub fn finish(mut self) -> (String, Vec<u8>) {
+        self.body
+            .extend_from_slice(format!("{}{}{}{}", DASHES, self.boundary, DASHES, CRLF).as_bytes());
+        (format!("{}; boundary={}", MULTIPART_FORM_DATA, self.boundary), self.body)
+    }
+    fn add_part(&mut self, disposition: String, content_type: Option<&str>, bytes: &[u8]) {
+        let mut head = format!("{}{}{}Content-Disposition: {}{}", DASHES, self.boundary, CRLF, disposition, CRLF);
+        if let Some(content_type) = content_type {
+            head.push_str(&format!("Content-Type: {}{}", content_type, CRLF));
+        }
+        head.push_str(CRLF);
+        self.body.extend_from_slice(head.as_bytes());
+        self.body.extend_from_slice(bytes);
+        self.body.extend_from_slice(CRLF.as_bytes());
+    }
+}
+
+impl Default for MultipartBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// creates a boundary from a randomly seeded hash of the time and a counter
+fn random_boundary() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    hasher.write_u128(nanos);
+    hasher.write_u64(BOUNDARY_COUNTER.fetch_add(1, Ordering::Relaxed));
+    let first = hasher.finish();
+    hasher.write_u64(first);
+    format!("{}{:016x}{:016x}", BOUNDARY_PREFIX, first, hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HttpMethod, HttpVersion, MultipartBuilder, ParseErrorKind, parse_content_disposition, Request};
+
+    /// splits a multipart body into the (head, content) pairs of its parts
+    fn parse_parts<'a>(body: &'a str, boundary: &str) -> Vec<(&'a str, &'a str)> {
+        let delimiter = format!("--{}", boundary);
+        let closing = format!("{}--\r\n", delimiter);
+        assert!(body.ends_with(&closing));
+        body[..body.len() - closing.len()]
+            .split(&format!("{}\r\n", delimiter))
+            .skip(1)
+            .map(|part| {
+                let (head, content) = part.split_once("\r\n\r\n").unwrap();
+                (head, content.strip_suffix("\r\n").unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let multipart = MultipartBuilder::new()
+            .with_boundary("----whdpTest")
+            .unwrap()
+            .add_text("title", "Hello\r\nWorld")
+            .add_file("up\"load", "my \"cv\".txt", "text/plain", "line 1\nline 2".as_bytes())
+            .unwrap();
+        let req = Request::builder()
+            .with_method(HttpMethod::Post)
+            .with_uri("/upload")
+            .with_version(HttpVersion::OnePointOne)
+            .with_multipart(multipart)
+            .unwrap()
+            .build()
+            .unwrap();
+        let bytes = req.to_bytes().unwrap();
+        let (parsed, rest) = Request::parse_with_remainder(&bytes).unwrap();
+        assert!(rest.is_empty());
+        let content_type = parsed.get_content_type().unwrap();
+        assert_eq!(content_type.get_essence(), "multipart/form-data");
+        assert_eq!(parsed.get_content_length(), Some(parsed.get_body().len()));
+        let parts = parse_parts(parsed.get_body(), content_type.boundary().unwrap());
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0], ("Content-Disposition: form-data; name=title", "Hello\r\nWorld"));
+        assert_eq!(
+            parts[1].0,
+            "Content-Disposition: form-data; name=\"up\\\"load\"; filename=\"my \\\"cv\\\".txt\"\r\nContent-Type: text/plain"
+        );
+        assert_eq!(parts[1].1, "line 1\nline 2");
+        let (disposition, _) = parts[1].0.trim_start_matches("Content-Disposition: ").split_once("\r\n").unwrap();
+        let disposition = parse_content_disposition(disposition).unwrap();
+        assert_eq!((disposition.get_name(), disposition.get_filename()), (Some("up\"load"), Some("my \"cv\".txt")));
+    }
+
+    #[test]
+    fn header_injection() {
+        for boundary in ["", "a\r\nX-Evil: 1", "ends with space ", "\"quoted\"", &"a".repeat(71)] {
+            let err = MultipartBuilder::new().with_boundary(boundary).unwrap_err();
+            assert_eq!(err.get_kind(), &ParseErrorKind::Util, "{:?}", boundary);
+        }
+        assert!(MultipartBuilder::new().with_boundary("'()+_,-./:=? x").is_ok());
+        for content_type in ["text/plain\r\nX-Evil: 1", "text/plain; a=\"\r\n\"", "plain"] {
+            assert!(MultipartBuilder::new().add_file("f", "f.txt", content_type, b"").is_err(), "{:?}", content_type);
+        }
+        let (_, body) = MultipartBuilder::new().with_boundary("b").unwrap().add_text("a\r\nX-Evil: 1", "").finish();
+        assert!(body.starts_with(b"--b\r\nContent-Disposition: form-data; name=\"a%0D%0AX-Evil: 1\"\r\n"));
+    }
+
+    #[test]
+    fn binary_part() {
+        let png = [0x89, b'P', b'N', b'G', 0x00, 0xff];
+        let multipart = MultipartBuilder::new()
+            .with_boundary("b")
+            .unwrap()
+            .add_file("avatar", "me.png", "image/png", &png)
+            .unwrap();
+        let builder = Request::builder()
+            .with_method(HttpMethod::Post)
+            .with_uri("/upload")
+            .with_version(HttpVersion::OnePointOne);
+        assert_eq!(builder.clone().with_multipart(multipart.clone()).unwrap_err().get_kind(), &ParseErrorKind::Body);
+
+        let bytes = builder.build_multipart_bytes(multipart.clone()).unwrap();
+        let (_, body) = multipart.finish();
+        let head_end = bytes.windows(4).position(|window| window == b"\r\n\r\n").unwrap() + 4;
+        let head = String::from_utf8(bytes[..head_end].to_vec()).unwrap();
+        assert!(head.starts_with("POST /upload HTTP/1.1\r\n"));
+        assert!(head.contains(&format!("Content-Length: {}\r\n", body.len())));
+        assert!(head.contains("Content-Type: multipart/form-data; boundary=b\r\n"));
+        assert_eq!(&bytes[head_end..], body.as_slice());
+        assert!(body.windows(png.len()).any(|window| window == png));
+    }
+
+    #[test]
+    fn random_boundaries() {
+        let first = MultipartBuilder::new();
+        let second = MultipartBuilder::default();
+        assert_ne!(first.get_boundary(), second.get_boundary());
+        assert!(first.get_boundary().len() <= 70);
+        let (content_type, body) = first.finish();
+        assert!(content_type.starts_with("multipart/form-data; boundary=whdp-boundary-"));
+        assert!(body.ends_with(b"--\r\n"));
+    }
+}
@@ -0,0 +1,317 @@
+use std::fmt::{Debug, Display, Formatter};
+
+use crate::media_type::MediaType;
+use crate::util::unquote;
+
+const QUALITY_PARAM: &str = "q=";
+const WILDCARD: &str = "*";
+const SUBTAG_DELIMITER: char = '-';
+const MAX_QUALITY: u16 = 1000;
+const FULL_WILDCARD: &str = "*/*";
+const TYPE_DELIMITER: char = '/';
+
+/// A single element of a quality-weighted header list like Accept or Accept-Language
+pub(crate) struct WeightedValue<'a> {
+    pub(crate) value: &'a str,
+    pub(crate) quality: u16,
+}
+
+/// parses a comma separated list of values with optional `q` parameters
+/// (in thousandths) sorted by descending quality (repeated header lines are part of the list). <br>
+/// Elements with an invalid quality are ignored, the order of equal qualities is kept
+pub(crate) fn parse_weighted_list(header: &str) -> Vec<WeightedValue<'_>> {
+    let mut list: Vec<WeightedValue> = header
+        .split([',', '\n'])
+        .filter_map(parse_weighted_value)
+        .collect();
+    list.sort_by_key(|weighted| std::cmp::Reverse(weighted.quality));
+    list
+}
+
+/// parses a quality-weighted list of case-insensitive tokens (like the TE header)
+/// into lowercase tokens with their quality between 0 and 1
+pub(crate) fn parse_quality_tokens(header: &str) -> Vec<(String, f32)> {
+    parse_weighted_list(header)
+        .into_iter()
+        .map(|weighted| (weighted.value.to_ascii_lowercase(), f32::from(weighted.quality) / f32::from(MAX_QUALITY)))
+        .collect()
+}
+
+fn parse_weighted_value(element: &str) -> Option<WeightedValue<'_>> {
+    let mut split = element.split(';');
+    let value = split.next()?.trim();
+    if value.is_empty() {
+        return None;
+    }
+    let quality = match split.map(str::trim).find(|param| is_quality_param(param)) {
+        Some(param) => parse_quality(&param[QUALITY_PARAM.len()..])?,
+        None => MAX_QUALITY,
+    };
+    Some(WeightedValue { value, quality })
+}
+
+fn is_quality_param(param: &str) -> bool {
+    param
+        .get(..QUALITY_PARAM.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(QUALITY_PARAM))
+}
+
+/// parses a qvalue (`0` to `1` with at most 3 decimals) into thousandths
+fn parse_quality(value: &str) -> Option<u16> {
+    let (int, frac) = value.split_once('.').unwrap_or((value, ""));
+    if int.len() != 1 || frac.len() > 3 || !frac.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let frac = format!("{:0<3}", frac).parse::<u16>().ok()?;
+    match int {
+        "0" => Some(frac),
+        "1" if frac == 0 => Some(MAX_QUALITY),
+        _ => None,
+    }
+}
+
+/// Struct for a language range of the Accept-Language header with its quality
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct LanguageRange {
+    tag: String,
+    quality: u16,
+}
+
+impl LanguageRange {
+    /// returns the language tag (or `*`)
+    pub fn get_tag(&self) -> &str {
+        &self.tag
+    }
+    /// returns the quality between 0 and 1
+    pub fn get_quality(&self) -> f32 {
+        f32::from(self.quality) / f32::from(MAX_QUALITY)
+    }
+    /// looks if the range is the wildcard `*`
+    pub fn is_wildcard(&self) -> bool {
+        self.tag == WILDCARD
+    }
+    /// looks if the language tag matches this range with the basic filtering of
+    /// [RFC 4647](https://www.rfc-editor.org/rfc/rfc4647#section-3.3.1)
+    pub fn matches(&self, tag: &str) -> bool {
+        if self.is_wildcard() || self.tag.eq_ignore_ascii_case(tag) {
+            return true;
+        }
+        tag.get(..self.tag.len())
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case(&self.tag))
+            && tag[self.tag.len()..].starts_with(SUBTAG_DELIMITER)
+    }
+}
+
+impl Display for LanguageRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{};q={}", self.tag, self.get_quality())
+    }
+}
+
+impl Debug for LanguageRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// parses the Accept-Language header into [LanguageRange]s sorted by preference
+pub(crate) fn parse_language_ranges(header: &str) -> Vec<LanguageRange> {
+    parse_weighted_list(header)
+        .into_iter()
+        .map(|weighted| LanguageRange {
+            tag: String::from(weighted.value),
+            quality: weighted.quality,
+        })
+        .collect()
+}
+
+/// chooses the best available language for the ranges. <br>
+/// Every range prefers an exact match, then a more specific available tag and
+/// finally falls back to the range without its last subtags. Ranges with quality 0 exclude tags
+pub(crate) fn negotiate_language(ranges: &[LanguageRange], available: &[&str]) -> Option<String> {
+    let excluded = |tag: &str| {
+        ranges
+            .iter()
+            .any(|range| range.quality == 0 && !range.is_wildcard() && range.matches(tag))
+    };
+    let find = |predicate: &dyn Fn(&str) -> bool| {
+        available
+            .iter()
+            .find(|tag| !excluded(tag) && predicate(tag))
+            .map(|tag| String::from(*tag))
+    };
+    for range in ranges.iter().filter(|range| range.quality > 0) {
+        let found = find(&|tag| range.tag.eq_ignore_ascii_case(tag)).or_else(|| find(&|tag| range.matches(tag)));
+        if found.is_some() {
+            return found;
+        }
+        let mut prefix = range.tag.as_str();
+        while let Some(index) = prefix.rfind(SUBTAG_DELIMITER) {
+            prefix = &prefix[..index];
+            let found = find(&|tag| prefix.eq_ignore_ascii_case(tag));
+            if found.is_some() {
+                return found;
+            }
+        }
+    }
+    None
+}
+
+/// A media range of the Accept header with its parameters (without `q` and the accept extensions)
+struct MediaRange {
+    main: String,
+    sub: String,
+    params: Vec<(String, String)>,
+    quality: u16,
+}
+
+impl MediaRange {
+    /// `*/*` < `type/*` < `type/subtype` < `type/subtype` with parameters (more are more specific)
+    fn specificity(&self) -> usize {
+        match (self.main.as_str(), self.sub.as_str()) {
+            (WILDCARD, _) => 0,
+            (_, WILDCARD) => 1,
+            _ => 2 + self.params.len(),
+        }
+    }
+    fn matches(&self, offer: &MediaType) -> bool {
+        (self.main == WILDCARD || self.main == offer.get_type())
+            && (self.sub == WILDCARD || self.sub == offer.get_subtype())
+            && self.params.iter().all(|(key, value)| {
+                offer.parameter(key).is_some_and(|offered| offered.eq_ignore_ascii_case(value))
+            })
+    }
+}
+
+/// parses the Accept header into its media ranges in order. <br>
+/// Ranges with an invalid quality or without a subtype are ignored
+fn parse_media_ranges(header: &str) -> Vec<MediaRange> {
+    header
+        .split(',')
+        .filter_map(|element| {
+            let quality = parse_weighted_value(element)?.quality;
+            let mut split = element.split(';').map(str::trim);
+            let (main, sub) = split.next()?.split_once(TYPE_DELIMITER)?;
+            let params = split
+                .take_while(|param| !is_quality_param(param))
+                .filter_map(|param| param.split_once('='))
+                .map(|(key, value)| (key.trim().to_ascii_lowercase(), unquote(value.trim())))
+                .collect();
+            Some(MediaRange {
+                main: main.trim().to_ascii_lowercase(),
+                sub: sub.trim().to_ascii_lowercase(),
+                params,
+                quality,
+            })
+        })
+        .collect()
+}
+
+/// chooses the best offer for the Accept header (a missing header accepts everything)
+/// following [RFC 7231](https://www.rfc-editor.org/rfc/rfc7231#section-5.3.2). <br>
+/// The quality of an offer is the one of the most specific range matching it
+/// (exact with parameters > exact > `type/*` > `*/*`, the first range wins if they are equally specific),
+/// so `text/html;q=0` excludes `text/html` even if `text/*` or `*/*` accept it.
+/// Then the offer with the highest quality wins. Ties are broken by the specificity of the matching range
+/// (`text/html` beats `text/*`) and then by the order of the offers. Offers that aren't valid media types are skipped
+pub(crate) fn negotiate_media_type(header: Option<&str>, offers: &[&str]) -> Option<String> {
+    let ranges = parse_media_ranges(header.unwrap_or(FULL_WILDCARD));
+    let mut best: Option<(&str, u16, usize)> = None;
+    for offer in offers {
+        let Ok(media_type) = offer.parse::<MediaType>() else {
+            continue;
+        };
+        let (quality, specificity) = ranges
+            .iter()
+            .filter(|range| range.matches(&media_type))
+            .fold(None::<&MediaRange>, |best, range| match best {
+                Some(best) if best.specificity() >= range.specificity() => Some(best),
+                _ => Some(range),
+            })
+            .map_or((0, 0), |range| (range.quality, range.specificity()));
+        if quality > 0 && best.is_none_or(|(_, best, best_specificity)| (quality, specificity) > (best, best_specificity)) {
+            best = Some((offer, quality, specificity));
+        }
+    }
+    best.map(|(offer, _, _)| String::from(offer))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::negotiate::{negotiate_language, negotiate_media_type, parse_language_ranges, parse_weighted_list};
+
+    #[test]
+    fn weighted_list() {
+        let list = parse_weighted_list("text/html;level=1;q=0.5, application/json, text/*;q=0.8, broken;q=2, ,");
+        let values: Vec<(&str, u16)> = list.iter().map(|weighted| (weighted.value, weighted.quality)).collect();
+        assert_eq!(values, vec![("application/json", 1000), ("text/*", 800), ("text/html", 500)]);
+    }
+
+    #[test]
+    fn language_ranges() {
+        let ranges = parse_language_ranges("de;q=0.7, en-GB, en;q=0.9");
+        let tags: Vec<&str> = ranges.iter().map(|range| range.get_tag()).collect();
+        assert_eq!(tags, vec!["en-GB", "en", "de"]);
+        assert_eq!(ranges[2].get_quality(), 0.7);
+        assert!(ranges[1].matches("en-US"));
+        assert!(!ranges[1].matches("eng"));
+    }
+
+    #[test]
+    fn region_fallback() {
+        let ranges = parse_language_ranges("en-GB,de;q=0.7");
+        assert_eq!(negotiate_language(&ranges, &["de", "en"]), Some(String::from("en")));
+        assert_eq!(negotiate_language(&ranges, &["en-US", "en-GB"]), Some(String::from("en-GB")));
+        assert_eq!(negotiate_language(&ranges, &["fr"]), None);
+        let ranges = parse_language_ranges("en");
+        assert_eq!(negotiate_language(&ranges, &["de", "en-US"]), Some(String::from("en-US")));
+    }
+
+    #[test]
+    fn wildcard() {
+        let ranges = parse_language_ranges("*");
+        assert_eq!(negotiate_language(&ranges, &["fr", "de"]), Some(String::from("fr")));
+        let ranges = parse_language_ranges("*, fr;q=0");
+        assert_eq!(negotiate_language(&ranges, &["fr-CA", "de"]), Some(String::from("de")));
+        let ranges = parse_language_ranges("*;q=0");
+        assert_eq!(negotiate_language(&ranges, &["fr"]), None);
+    }
+
+    #[test]
+    fn media_type_precedence() {
+        let table: &[(Option<&str>, &[&str], Option<&str>)] = &[
+            (None, &["application/json", "text/html"], Some("application/json")),
+            (Some("*/*"), &["application/json", "text/html"], Some("application/json")),
+            (Some("*/*"), &["text/html", "application/json"], Some("text/html")),
+            (Some("text/*, text/html"), &["text/plain", "text/html"], Some("text/html")),
+            (Some("text/*, text/html"), &["text/html", "text/plain"], Some("text/html")),
+            (Some("text/*"), &["text/plain", "text/html"], Some("text/plain")),
+            (Some("text/*;q=0.5, text/html"), &["text/plain", "text/html"], Some("text/html")),
+            (Some("text/html, text/*;q=0.5"), &["text/plain", "text/html"], Some("text/html")),
+            (Some("text/html;q=0.5, text/*"), &["text/html", "text/plain"], Some("text/plain")),
+            (Some("text/html;q=0, */*"), &["text/html"], None),
+            (Some("*/*, text/html;q=0"), &["text/html", "application/json"], Some("application/json")),
+            (Some("text/*;q=0, */*"), &["text/css", "image/png"], Some("image/png")),
+            (Some("*/*;q=0"), &["text/html"], None),
+            (Some("application/json;q=0.9, text/html;q=0.9"), &["text/html", "application/json"], Some("text/html")),
+            (Some("application/json"), &["text/html"], None),
+            (Some("text/html;level=1"), &["text/html"], None),
+            (Some("text/html;level=1"), &["text/html;level=1", "text/plain"], Some("text/html;level=1")),
+            (Some("text/html;level=1;q=0.2, text/html;q=0.8"), &["text/html;level=1", "text/html;level=2"], Some("text/html;level=2")),
+            (Some("text/html;level=1, text/html;q=0.1, */*;q=0.5"), &["text/html", "image/png"], Some("image/png")),
+            (Some("TEXT/HTML"), &["text/html"], Some("text/html")),
+            (Some("text/html;q=2, image/png"), &["text/html", "image/png"], Some("image/png")),
+            (Some("text/html"), &["not a media type", "text/html"], Some("text/html")),
+            (Some(""), &["text/html"], None),
+        ];
+        for (header, offers, expected) in table {
+            assert_eq!(
+                negotiate_media_type(*header, offers).as_deref(),
+                *expected,
+                "Accept: {:?} with offers {:?}",
+                header,
+                offers
+            );
+        }
+    }
+}
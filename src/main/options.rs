@@ -0,0 +1,204 @@
+use std::time::Duration;
+
+/// Options for parsing a [Request] or [Response]
+/// (with [parse_with](crate::Request::parse_with) or from a stream). <br>
+/// All the parsing policies live here instead of in separate entry points:
+///
+/// | Option | Effect |
+/// |---|---|
+/// | strict | rejects tabs in the start line, conflicting duplicates and a TRACE body |
+/// | header timeout | deadline for the complete header block from a stream |
+/// | idle timeout | maximum time between two reads from a stream |
+/// | max body size | maximum size of a body read from a stream |
+/// | max head size | maximum size of the start line and the headers read from a stream (64 KiB for a served connection) |
+/// | max headers | maximum number of header lines |
+/// | buffer cap | capacity a reused connection buffer is shrunk back to |
+/// | soft limits | thresholds that only add a [ParseWarning](crate::ParseWarning) to the parsed message |
+/// | joined headers | headers whose repetitions are always joined |
+///
+/// The [Default] options are lenient, have no timeouts or limits and match the behaviour of [FromStr]
+///
+/// [Request]: crate::Request
+/// [Response]: crate::Response
+/// [FromStr]: std::str::FromStr
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ParseOptions {
+    strict: bool,
+    header_timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
+    max_body_size: Option<usize>,
+    max_head_size: Option<usize>,
+    max_headers: Option<usize>,
+    buffer_cap: Option<usize>,
+    soft_limits: SoftLimits,
+    joined_headers: &'static [&'static str],
+}
+
+impl ParseOptions {
+    /// creates new lenient ParseOptions
+    pub const fn new() -> Self {
+        Self {
+            strict: false,
+            header_timeout: None,
+            idle_timeout: None,
+            max_body_size: None,
+            max_head_size: None,
+            max_headers: None,
+            buffer_cap: None,
+            soft_limits: SoftLimits::new(),
+            joined_headers: &[],
+        }
+    }
+    /// creates new strict ParseOptions
+    pub const fn strict() -> Self {
+        Self::new().with_strict(true)
+    }
+    /// replaces the current value with the strict parameter
+    pub const fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+    /// replaces the deadline for receiving the complete header block from a stream
+    pub const fn with_header_timeout(mut self, timeout: Duration) -> Self {
+        self.header_timeout = Some(timeout);
+        self
+    }
+    /// replaces the maximum time to wait between two reads from a stream
+    pub const fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+    /// replaces the maximum size of a body read from a stream
+    pub const fn with_max_body_size(mut self, size: usize) -> Self {
+        self.max_body_size = Some(size);
+        self
+    }
+    /// replaces the maximum size of the start line and the headers read from a stream
+    pub const fn with_max_head_size(mut self, size: usize) -> Self {
+        self.max_head_size = Some(size);
+        self
+    }
+    /// replaces the maximum number of header lines (repetitions count separately)
+    pub const fn with_max_headers(mut self, count: usize) -> Self {
+        self.max_headers = Some(count);
+        self
+    }
+    /// replaces the capacity the reused buffer of a connection is shrunk back to
+    /// after handling an unusually large message
+    pub const fn with_buffer_cap(mut self, cap: usize) -> Self {
+        self.buffer_cap = Some(cap);
+        self
+    }
+    /// replaces the soft thresholds, crossing one never fails the parsing
+    /// but adds a [ParseWarning](crate::ParseWarning) to the message
+    pub const fn with_soft_limits(mut self, limits: SoftLimits) -> Self {
+        self.soft_limits = limits;
+        self
+    }
+    /// replaces the headers whose repetitions are always joined into a single
+    /// comma separated value, regardless of their [DuplicatePolicy](crate::DuplicatePolicy)
+    pub const fn with_joined_headers(mut self, names: &'static [&'static str]) -> Self {
+        self.joined_headers = names;
+        self
+    }
+    /// looks if the parsing should be strict
+    pub const fn is_strict(&self) -> bool {
+        self.strict
+    }
+    /// returns the deadline for receiving the complete header block from a stream
+    pub const fn get_header_timeout(&self) -> Option<Duration> {
+        self.header_timeout
+    }
+    /// returns the maximum size of a body read from a stream
+    pub const fn get_max_body_size(&self) -> Option<usize> {
+        self.max_body_size
+    }
+    /// returns the maximum size of the start line and the headers read from a stream
+    pub const fn get_max_head_size(&self) -> Option<usize> {
+        self.max_head_size
+    }
+    /// returns the maximum number of header lines
+    pub const fn get_max_headers(&self) -> Option<usize> {
+        self.max_headers
+    }
+    /// returns the capacity the reused buffer of a connection is shrunk back to
+    pub const fn get_buffer_cap(&self) -> Option<usize> {
+        self.buffer_cap
+    }
+    /// returns the soft thresholds
+    pub const fn get_soft_limits(&self) -> &SoftLimits {
+        &self.soft_limits
+    }
+    /// returns the maximum time to wait between two reads from a stream
+    pub const fn get_idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+    /// returns the headers whose repetitions are always joined
+    pub const fn get_joined_headers(&self) -> &'static [&'static str] {
+        self.joined_headers
+    }
+    /// looks if the repetitions of the header are always joined (case-insensitive)
+    pub fn is_joined_header(&self, key: &str) -> bool {
+        self.joined_headers.iter().any(|name| name.eq_ignore_ascii_case(key))
+    }
+}
+
+/// Soft thresholds of the [ParseOptions] to notice traffic that comes close to the hard limits
+/// (for example a warning at 90 header lines with a hard limit of 100). <br>
+/// Crossing a threshold adds a [ParseWarning](crate::ParseWarning) to the parsed message,
+/// see [get_warnings](crate::Request::get_warnings). The [Default] has no thresholds
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct SoftLimits {
+    header_count: Option<usize>,
+    header_size: Option<usize>,
+    uri_length: Option<usize>,
+    body_size: Option<usize>,
+}
+
+impl SoftLimits {
+    /// creates new SoftLimits without any threshold
+    pub const fn new() -> Self {
+        Self {
+            header_count: None,
+            header_size: None,
+            uri_length: None,
+            body_size: None,
+        }
+    }
+    /// replaces the threshold for the number of header lines
+    pub const fn with_header_count(mut self, count: usize) -> Self {
+        self.header_count = Some(count);
+        self
+    }
+    /// replaces the threshold for the size of a single header line (name and value)
+    pub const fn with_header_size(mut self, size: usize) -> Self {
+        self.header_size = Some(size);
+        self
+    }
+    /// replaces the threshold for the length of the uri of a Request
+    pub const fn with_uri_length(mut self, length: usize) -> Self {
+        self.uri_length = Some(length);
+        self
+    }
+    /// replaces the threshold for the size of the body
+    pub const fn with_body_size(mut self, size: usize) -> Self {
+        self.body_size = Some(size);
+        self
+    }
+    /// returns the threshold for the number of header lines
+    pub const fn get_header_count(&self) -> Option<usize> {
+        self.header_count
+    }
+    /// returns the threshold for the size of a single header line
+    pub const fn get_header_size(&self) -> Option<usize> {
+        self.header_size
+    }
+    /// returns the threshold for the length of the uri
+    pub const fn get_uri_length(&self) -> Option<usize> {
+        self.uri_length
+    }
+    /// returns the threshold for the size of the body
+    pub const fn get_body_size(&self) -> Option<usize> {
+        self.body_size
+    }
+}
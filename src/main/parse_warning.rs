@@ -0,0 +1,143 @@
+use std::fmt::{Debug, Display, Formatter};
+
+use crate::options::SoftLimits;
+const COLON: char = ':';
+
+/// Enum for a [soft threshold](crate::SoftLimits) a parsed message crossed
+/// without making the parsing fail (the actual value comes first, the threshold second)
+#[non_exhaustive]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ParseWarning {
+    /// the number of header lines
+    HeaderCount(usize, usize),
+    /// the size of a single header line with the name of the header
+    HeaderSize(String, usize, usize),
+    /// the length of the uri of a Request
+    UriLength(usize, usize),
+    /// the size of the body
+    BodySize(usize, usize),
+}
+
+impl Display for ParseWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseWarning::HeaderCount(count, limit) => write!(f, "{} header lines reach the soft limit of {}", count, limit),
+            ParseWarning::HeaderSize(key, size, limit) => {
+                write!(f, "The header {} with {} bytes reaches the soft limit of {}", key, size, limit)
+            }
+            ParseWarning::UriLength(length, limit) => write!(f, "The uri with {} bytes reaches the soft limit of {}", length, limit),
+            ParseWarning::BodySize(size, limit) => write!(f, "The body with {} bytes reaches the soft limit of {}", size, limit),
+        }
+    }
+}
+
+impl Debug for ParseWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// The header lines of a head measured before repeated headers get combined
+pub(crate) struct HeaderLines {
+    count: usize,
+    sizes: Vec<(String, usize)>,
+}
+
+/// measures the header lines until the empty line, but only if there is a threshold for them. <br>
+/// The size of a header is the one of its largest line (name and value without the colon and whitespace)
+pub(crate) fn measure_header_lines<'a>(limits: &SoftLimits, lines: impl Iterator<Item = &'a str>) -> Option<HeaderLines> {
+    if limits.get_header_count().is_none() && limits.get_header_size().is_none() {
+        return None;
+    }
+    let mut header_lines = HeaderLines { count: 0, sizes: Vec::new() };
+    for line in lines.take_while(|line| !line.is_empty()) {
+        header_lines.count += 1;
+        let Some((key, value)) = line.split_once(COLON) else {
+            continue;
+        };
+        let (key, size) = (key.trim(), key.trim().len() + value.trim().len());
+        match header_lines.sizes.iter_mut().find(|(name, _)| name.eq_ignore_ascii_case(key)) {
+            Some((_, largest)) => *largest = size.max(*largest),
+            None => header_lines.sizes.push((String::from(key), size)),
+        }
+    }
+    Some(header_lines)
+}
+
+/// collects the warnings for every threshold of the head that is reached
+pub(crate) fn head_warnings(limits: &SoftLimits, header_lines: Option<HeaderLines>, uri: Option<&str>) -> Vec<ParseWarning> {
+    let mut warnings = Vec::new();
+    if let Some(header_lines) = header_lines {
+        if let Some(limit) = limits.get_header_count().filter(|limit| header_lines.count >= *limit) {
+            warnings.push(ParseWarning::HeaderCount(header_lines.count, limit));
+        }
+        if let Some(limit) = limits.get_header_size() {
+            for (key, size) in header_lines.sizes.into_iter().filter(|(_, size)| *size >= limit) {
+                warnings.push(ParseWarning::HeaderSize(key, size, limit));
+            }
+        }
+    }
+    if let Some((length, limit)) = uri.map(str::len).zip(limits.get_uri_length()) {
+        if length >= limit {
+            warnings.push(ParseWarning::UriLength(length, limit));
+        }
+    }
+    warnings
+}
+
+/// replaces the warning for the body size (the body of a stream is read after the head)
+pub(crate) fn update_body_warning(warnings: &mut Vec<ParseWarning>, limits: &SoftLimits, body: &str) {
+    warnings.retain(|warning| !matches!(warning, ParseWarning::BodySize(..)));
+    if let Some(limit) = limits.get_body_size().filter(|limit| body.len() >= *limit) {
+        warnings.push(ParseWarning::BodySize(body.len(), limit));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MockStream, ParseOptions, ParseWarning, Request, Response, SoftLimits};
+
+    #[test]
+    fn request() {
+        let limits = SoftLimits::new().with_header_count(2).with_header_size(20).with_uri_length(8).with_body_size(4);
+        let options = ParseOptions::new().with_max_headers(100).with_soft_limits(limits);
+        let req = Request::parse_with(
+            "POST /a/long/uri HTTP/1.1\nHost: a\nX-Long: 0123456789abcdef\nX-Many: 1\nX-Many: 2\n\nHello",
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            req.get_warnings(),
+            [
+                ParseWarning::HeaderCount(4, 2),
+                ParseWarning::HeaderSize(String::from("X-Long"), 22, 20),
+                ParseWarning::UriLength(11, 8),
+                ParseWarning::BodySize(5, 4),
+            ]
+        );
+        assert_eq!(req.get_warnings()[3].to_string(), "The body with 5 bytes reaches the soft limit of 4");
+        assert!(Request::parse_with("GET / HTTP/1.1\n\n", &options).unwrap().get_warnings().is_empty());
+        assert!(Request::try_from("POST /a/long/uri HTTP/1.1\n\nHello").unwrap().get_warnings().is_empty());
+    }
+
+    #[test]
+    fn header_size_per_line() {
+        let options = ParseOptions::new().with_soft_limits(SoftLimits::new().with_header_size(12));
+        let req = Request::parse_with("GET / HTTP/1.1\nX-A: 1234\nx-a: 5678\nX-A: 9\n\n", &options).unwrap();
+        assert_eq!(req.get_header("X-A").unwrap(), "1234, 5678, 9");
+        assert!(req.get_warnings().is_empty());
+        let req = Request::parse_with("GET / HTTP/1.1\nX-A: 1\nx-a: 0123456789\n\n", &options).unwrap();
+        assert_eq!(req.get_warnings(), [ParseWarning::HeaderSize(String::from("X-A"), 13, 12)]);
+        let resp = Response::parse_with("HTTP/1.1 200 OK\nSet-Cookie: a\nSet-Cookie: b\n\n", &options).unwrap();
+        assert!(resp.get_warnings().is_empty());
+    }
+
+    #[test]
+    fn response_from_stream() {
+        let options = ParseOptions::new().with_soft_limits(SoftLimits::new().with_body_size(3));
+        let mut stream = MockStream::new().with_read(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nHello");
+        let resp = Response::from_stream(&mut stream, &options).unwrap();
+        assert_eq!(resp.get_body(), "Hello");
+        assert_eq!(resp.get_warnings(), [ParseWarning::BodySize(5, 3)]);
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Display, Formatter};
+
+use wjp::{ParseError, Serialize, SerializeHelper, Values};
+
+use crate::status::HttpStatus;
+
+pub(crate) const PROBLEM_JSON: &str = "application/problem+json";
+const ABOUT_BLANK: &str = "about:blank";
+const TYPE: &str = "type";
+const TITLE: &str = "title";
+const STATUS: &str = "status";
+const DETAIL: &str = "detail";
+const INSTANCE: &str = "instance";
+
+/// Struct for a Problem Details document
+/// ([RFC 9457](https://www.rfc-editor.org/rfc/rfc9457)) sent as `application/problem+json`. <br>
+/// Additional members that aren't part of the standard are kept as extensions
+#[derive(Debug, Clone)]
+pub struct Problem {
+    problem_type: String,
+    title: String,
+    status: u16,
+    detail: Option<String>,
+    instance: Option<String>,
+    extensions: BTreeMap<String, Values>,
+}
+
+impl Problem {
+    /// creates a new Problem of the type `about:blank` for the given [HttpStatus]
+    pub fn new(status: &HttpStatus, title: &str) -> Self {
+        Self {
+            problem_type: String::from(ABOUT_BLANK),
+            title: String::from(title),
+            status: *status.get_code(),
+            detail: None,
+            instance: None,
+            extensions: BTreeMap::new(),
+        }
+    }
+    /// replaces the URI reference identifying the problem type
+    pub fn with_type(mut self, problem_type: &str) -> Self {
+        self.problem_type = String::from(problem_type);
+        self
+    }
+    /// replaces the explanation specific to this occurrence of the problem
+    pub fn with_detail(mut self, detail: &str) -> Self {
+        self.detail = Some(String::from(detail));
+        self
+    }
+    /// replaces the URI reference identifying this occurrence of the problem
+    pub fn with_instance(mut self, instance: &str) -> Self {
+        self.instance = Some(String::from(instance));
+        self
+    }
+    /// adds an extension member
+    pub fn with_extension(mut self, key: &str, value: Values) -> Self {
+        self.extensions.insert(String::from(key), value);
+        self
+    }
+    /// returns the URI reference identifying the problem type
+    pub fn get_type(&self) -> &str {
+        &self.problem_type
+    }
+    /// returns the short summary of the problem type
+    pub fn get_title(&self) -> &str {
+        &self.title
+    }
+    /// returns the HTTP Status Code of the problem
+    pub const fn get_status(&self) -> u16 {
+        self.status
+    }
+    /// returns the explanation specific to this occurrence of the problem
+    pub fn get_detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+    /// returns the URI reference identifying this occurrence of the problem
+    pub fn get_instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
+    /// returns the extension members
+    pub const fn get_extensions(&self) -> &BTreeMap<String, Values> {
+        &self.extensions
+    }
+    /// returns a specific extension member
+    pub fn get_extension(&self, key: &str) -> Option<&Values> {
+        self.extensions.get(key)
+    }
+}
+
+impl TryFrom<Values> for Problem {
+    type Error = ParseError;
+    fn try_from(value: Values) -> Result<Self, Self::Error> {
+        let mut struc = value.get_struct().ok_or(ParseError::new())?;
+        let problem_type = struc.remove(TYPE).and_then(|val| String::try_from(val).ok());
+        let title = struc.remove(TITLE).and_then(|val| String::try_from(val).ok());
+        let status = struc.map_val(STATUS, usize::try_from)?;
+        let detail = struc.remove(DETAIL).and_then(|val| String::try_from(val).ok());
+        let instance = struc.remove(INSTANCE).and_then(|val| String::try_from(val).ok());
+        Ok(Self {
+            problem_type: problem_type.unwrap_or(String::from(ABOUT_BLANK)),
+            title: title.unwrap_or_default(),
+            status: u16::try_from(status).map_err(|_err| ParseError::new())?,
+            detail,
+            instance,
+            extensions: struc.into_iter().collect(),
+        })
+    }
+}
+
+impl Serialize for Problem {
+    fn serialize(&self) -> Values {
+        let mut struc = self.extensions.clone();
+        struc.insert(String::from(TYPE), self.problem_type.serialize());
+        struc.insert(String::from(TITLE), self.title.serialize());
+        struc.insert(String::from(STATUS), self.status.serialize());
+        if let Some(detail) = &self.detail {
+            struc.insert(String::from(DETAIL), detail.serialize());
+        }
+        if let Some(instance) = &self.instance {
+            struc.insert(String::from(INSTANCE), instance.serialize());
+        }
+        Values::Struct(struc.into_iter().collect())
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.status, self.title)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wjp::{Deserialize, Serialize, Values};
+
+    use crate::{Problem, resp_presets, status_presets};
+
+    #[test]
+    fn json_shape() {
+        let problem = Problem::new(&status_presets::not_found(), "Not Found")
+            .with_detail("no user 42")
+            .with_instance("/users/42")
+            .with_extension("user", Values::Number(42.0));
+        let struc = problem.serialize().get_struct().unwrap();
+        assert_eq!(struc.len(), 6);
+        assert_eq!(struc.get("type").and_then(Values::get_string).unwrap(), "about:blank");
+        assert_eq!(struc.get("title").and_then(Values::get_string).unwrap(), "Not Found");
+        assert_eq!(struc.get("status").and_then(Values::get_number).unwrap(), 404.0);
+        assert_eq!(struc.get("detail").and_then(Values::get_string).unwrap(), "no user 42");
+        assert_eq!(struc.get("instance").and_then(Values::get_string).unwrap(), "/users/42");
+        assert_eq!(struc.get("user").and_then(Values::get_number).unwrap(), 42.0);
+
+        let without_detail = Problem::new(&status_presets::bad_request(), "Bad Request").serialize();
+        assert_eq!(without_detail.get_struct().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn round_trip() {
+        let problem = Problem::new(&status_presets::bad_request(), "Invalid Input")
+            .with_type("https://example.com/probs/invalid")
+            .with_extension("field", Values::String(String::from("name")));
+        let parsed = Problem::deserialize_str(&problem.json()).unwrap();
+        assert_eq!(parsed.get_type(), "https://example.com/probs/invalid");
+        assert_eq!(parsed.get_title(), "Invalid Input");
+        assert_eq!(parsed.get_status(), 400);
+        assert_eq!(parsed.get_detail(), None);
+        assert_eq!(parsed.get_extensions().len(), 1);
+        assert_eq!(parsed.get_extension("field").and_then(Values::get_string).unwrap(), "name");
+    }
+
+    #[test]
+    fn preset() {
+        let resp = resp_presets::problem(status_presets::not_found(), "Not Found", Some("gone"));
+        assert_eq!(resp.get_status().get_code(), &404);
+        assert_eq!(resp.get_header("Content-Type").unwrap(), "application/problem+json");
+        let problem = resp.get_problem().unwrap().unwrap();
+        assert_eq!(problem.get_status(), 404);
+        assert_eq!(problem.get_detail(), Some("gone"));
+
+        assert!(resp_presets::ok("{}").get_problem().is_none());
+    }
+}
@@ -1,24 +1,141 @@
-use std::collections::BTreeMap;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Debug, Display, Formatter};
-use std::io::{BufRead, BufReader};
-use std::net::TcpStream;
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{IpAddr, TcpStream};
+use std::ops::Range;
+use std::path::Path;
 use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use wjp::{Deserialize, map, ParseError, Serialize, SerializeHelper, Values};
 
-use crate::error::{HttpParseError, ParseErrorKind::Req};
-use crate::method::HttpMethod;
-use crate::util::{Destruct, EMPTY_CHAR, OPTION_WAS_EMPTY, parse_body, parse_header, parse_uri, ParseKeyValue};
+use crate::cache_control::{CacheControl, CACHE_CONTROL};
+use crate::date::parse_http_date;
+use crate::diff::{diff_field, diff_headers, FieldDiff};
+use crate::error::{HttpParseError, MissingField, ParseErrorKind::Req};
+use crate::etag::ETag;
+use crate::fingerprint::{fingerprint, FingerprintConfig};
+use crate::form::{form_fields, query_pairs_iter, FORM_URLENCODED};
+use crate::forwarded::{ForwardedElement, parse_forwarded};
+use crate::header::{DuplicatePolicy, HeaderMap};
+use crate::media_type::MediaType;
+use crate::method::{BodyExpectation, HttpMethod};
+use crate::multipart::MultipartBuilder;
+use crate::negotiate::{LanguageRange, negotiate_language, negotiate_media_type, parse_language_ranges, parse_quality_tokens};
+use crate::options::ParseOptions;
+#[cfg(any(test, feature = "websocket"))]
+use crate::websocket::{parse_handshake, WebSocketHandshake};
+use crate::parse_warning::{head_warnings, measure_header_lines, ParseWarning, update_body_warning};
+use crate::response::{resp_presets, Response};
+use crate::status::{HttpStatus, status_presets};
+use crate::stream::{BodyFraming, BODY_INCOMPLETE, INVALID_CONTENT_LENGTH, chunked_length, read_body, read_chunked_body, read_head, sniff_http};
+use crate::server::{Connection, TimedReader};
+use crate::util::{CONTENT_TYPE, replace_header_ignore_case, MESSAGE_HTTP, has_media_type, note_bom, strip_bom, split_host_port, is_meta_delimiter, content_type, normalized_headers, is_chunked, is_valid_percent_encoding, charset, is_textual, check_transfer_codings, UNSUPPORTED_VERSION, field_state, missing_fields_error, check_http1_framing, CONNECTION, CONTENT_LENGTH, Destruct, get_header_ignore_case, has_body, is_hop_by_hop, is_sensitive, OPTION_WAS_EMPTY, parse_body, parse_body_opt, parse_body_to, parse_content_length, parse_header, parse_uri, ParseKeyValue, read_body_file, REDACTED, check_raw_header_line, shell_quote, strip_hop_by_hop, to_wire_string, LineEnding, parse_token_list, TRANSFER_ENCODING, UPGRADE};
+use crate::uri::{raw_query, Target};
+use crate::validation::{check_issues, into_result, request_issues, ValidationIssue};
 use crate::version::HttpVersion;
 
+const HOST: &str = "Host";
+const CONTENT_LENGTH_MISMATCH: &str = "The Content-Length doesn't match the body";
+const IF_NONE_MATCH: &str = "If-None-Match";
+const IF_MODIFIED_SINCE: &str = "If-Modified-Since";
+const IF_MATCH: &str = "If-Match";
+const IF_UNMODIFIED_SINCE: &str = "If-Unmodified-Since";
+const ACCEPT_LANGUAGE: &str = "Accept-Language";
+const ACCEPT: &str = "Accept";
+const ANY: &str = "*";
+const TRUTHY: [&str; 3] = ["1", "true", "yes"];
+const KEEP_ALIVE: &str = "keep-alive";
+const CLOSE: &str = "close";
+const TE: &str = "TE";
+const MULTIPART: &str = "multipart";
+const X_FORWARDED_FOR: &str = "X-Forwarded-For";
+const FORWARDED: &str = "Forwarded";
+const EXPECT: &str = "Expect";
+const CONTINUE: &str = "100-continue";
+const PSEUDO_HEADER_PREFIX: char = ':';
+const AUTHORITY_PSEUDO_HEADER: &str = ":authority";
+const DEFAULT_HOST: &str = "localhost";
+const HTTP_SCHEME: &str = "http://";
+pub(crate) const BODY_FORBIDDEN: &str = "A body isn't allowed on a Request with the method";
+const H2_PREFACE: &str = "PRI * HTTP/2.0";
+const CHUNKED_NOT_LAST: &str = "The Transfer-Encoding of a Request has to end with chunked";
+const LINE_BREAK: [char; 2] = ['\r', '\n'];
+
 /// Struct for representing a HTTP Request
-#[derive(Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Default)]
+///
+/// Two Requests are equal if their method, uri, version, headers and body are.
+/// What was noted while parsing or building (like repeated headers or warnings) isn't compared
+#[derive(Clone, Default)]
 pub struct Request {
     method: HttpMethod,
     uri: String,
     version: HttpVersion,
     headers: BTreeMap<String, String>,
     body: String,
+    duplicates: BTreeSet<String>,
+    warnings: Vec<ParseWarning>,
+    raw_header_lines: Vec<String>,
+    force_http1: bool,
+    skip_validation: bool,
+}
+
+/// Struct for the head of a HTTP Request (everything but the body),
+/// see [parse_head](Request::parse_head)
+#[derive(Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Default, Debug)]
+pub struct RequestHead {
+    method: HttpMethod,
+    uri: String,
+    version: HttpVersion,
+    headers: BTreeMap<String, String>,
+}
+
+impl RequestHead {
+    /// Get the [HttpMethod] of the Request
+    pub fn get_method(&self) -> &HttpMethod {
+        &self.method
+    }
+    /// Get the uri of the Request
+    pub fn get_uri(&self) -> &String {
+        &self.uri
+    }
+    /// Get the [HttpVersion] of the Request
+    pub fn get_version(&self) -> &HttpVersion {
+        &self.version
+    }
+    /// Get the Headers of the Request
+    pub fn get_headers(&self) -> &BTreeMap<String, String> {
+        &self.headers
+    }
+}
+
+/// Struct for a Request parsed from borrowed bytes that keeps the original bytes,
+/// see [parse_view](Request::parse_view)
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct RequestView<'a> {
+    head: RequestHead,
+    raw: &'a [u8],
+    body: Range<usize>,
+}
+
+impl<'a> RequestView<'a> {
+    /// Get the parsed [RequestHead]
+    pub fn get_head(&self) -> &RequestHead {
+        &self.head
+    }
+    /// Get the bytes of the body (a chunked body isn't decoded)
+    pub fn get_body(&self) -> &'a [u8] {
+        &self.raw[self.body.clone()]
+    }
+    /// Get the bytes of the Request exactly as they were received
+    /// (with the original order, casing and whitespace of the headers),
+    /// for example to forward it unchanged in a transparent proxy
+    pub fn forward_raw(&self) -> &'a [u8] {
+        self.raw
+    }
 }
 
 impl<'a> TryFrom<&'a str> for Request {
@@ -31,17 +148,7 @@ impl<'a> TryFrom<&'a str> for Request {
 impl FromStr for Request {
     type Err = HttpParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.lines();
-        let (method, uri, version) = Self::parse_meta_data_line(lines.next())?;
-        let headers = parse_header(&mut lines)?;
-        let body = parse_body(&mut lines);
-        Ok(Self {
-            method,
-            uri,
-            version,
-            headers,
-            body,
-        })
+        Self::parse_with(s, &ParseOptions::default())
     }
 }
 
@@ -62,31 +169,250 @@ impl TryFrom<&[u8]> for Request {
 impl TryFrom<Vec<u8>> for Request {
     type Error = HttpParseError;
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
+        sniff_http(&value)?;
         let string = String::from_utf8(value)
-            .map_err(|err| HttpParseError::from((Req, err.to_string())))?;
+            .map_err(|err| HttpParseError::request(err.to_string()))?;
         Self::try_from(string)
     }
 }
 
 impl TryFrom<&mut TcpStream> for Request {
     type Error = HttpParseError;
+    /// reads the Request from the stream. The body is read according to its [framing](Request::body_framing)
     fn try_from(value: &mut TcpStream) -> Result<Self, Self::Error> {
-        let mut reader = BufReader::new(value);
-        let received: Vec<u8> = reader
-            .fill_buf()
-            .map_err(|err| HttpParseError::from((Req, err.to_string())))?
-            .to_vec();
-        reader.consume(received.len());
-        Self::try_from(received)
+        Self::from_reader(value)
     }
 }
 
 impl Request {
+    /// Creates a new Instance of a [RequestBuilder]
+    /// to "construct" a Request
+    pub const fn builder() -> RequestBuilder {
+        RequestBuilder::new()
+    }
+    /// Reads the Request from the reader with the default [ParseOptions],
+    /// see [from_reader_with](Request::from_reader_with)
+    pub fn from_reader<R: Connection>(reader: &mut R) -> Result<Request, HttpParseError> {
+        Self::from_reader_with(reader, &ParseOptions::default())
+    }
+    /// Reads the Request from the reader with the given [ParseOptions].
+    /// The body is read according to its [framing](Request::body_framing). <br>
+    /// The body only grows with the bytes that actually arrive, a declared length above
+    /// the [max body size](ParseOptions::with_max_body_size) fails before anything is read,
+    /// so untrusted input should always be read with a limit. <br>
+    /// The [header timeout](ParseOptions::with_header_timeout) and the [idle timeout](ParseOptions::with_idle_timeout)
+    /// are enforced like in [serve_connection](crate::serve_connection), hitting one is a [Timeout](crate::ParseErrorKind::Timeout) error
+    pub fn from_reader_with<R: Connection>(reader: &mut R, options: &ParseOptions) -> Result<Request, HttpParseError> {
+        let mut reader = BufReader::new(TimedReader::new(reader, options));
+        let mut req = Self::parse_with(&read_head(&mut reader, options.get_max_head_size(), Req)?, options)?;
+        reader.get_mut().head_read();
+        req.body = req.read_framed_body(&mut reader, options.get_max_body_size())?;
+        req.check_body_soft_limit(options);
+        Ok(req)
+    }
+    /// Reads the Request from the stream with the given [ParseOptions]. If the Request contains an
+    /// `Expect: 100-continue` header the interim `100 Continue` Response is written to the stream before the body is read
+    /// (only for HTTP/1.1 and later, an HTTP/1.0 client never gets a 1xx Response). <br>
+    /// The body is read according to its [framing](Request::body_framing), the head and the body are limited
+    /// and the timeouts are enforced like in [from_reader_with](Request::from_reader_with)
+    pub fn from_stream_with_continue<S: Connection>(stream: &mut S, options: &ParseOptions) -> Result<Request, HttpParseError> {
+        let mut reader = BufReader::new(TimedReader::new(stream, options));
+        let mut req = Self::parse_with(&read_head(&mut reader, options.get_max_head_size(), Req)?, options)?;
+        reader.get_mut().head_read();
+        let has_body = !matches!(req.body_framing(), BodyFraming::None | BodyFraming::ContentLength(0));
+        // a 1xx Response mustn't be sent to an HTTP/1.0 client (RFC 7231 section 5.1.1)
+        if req.expects_continue() && has_body && req.version >= HttpVersion::OnePointOne {
+            let interim = resp_presets::from_status(status_presets::r#continue()).to_raw_string(true);
+            let stream = reader.get_mut().get_mut();
+            stream
+                .write_all(interim.as_bytes())
+                .and_then(|_| stream.flush())
+                .map_err(|err| HttpParseError::request(err.to_string()))?;
+        }
+        req.body = req.read_framed_body(&mut reader, options.get_max_body_size())?;
+        req.check_body_soft_limit(options);
+        Ok(req)
+    }
+    /// Decides how the body is delimited on the wire from the headers
+    /// ([RFC 7230](https://www.rfc-editor.org/rfc/rfc7230#section-3.3.3)):
+    /// 1. a Transfer-Encoding wins over the Content-Length: [Chunked](BodyFraming::Chunked) if chunked is the last coding,
+    ///    otherwise [Invalid](BodyFraming::Invalid) because a Request can't be delimited by closing the connection
+    /// 2. a valid Content-Length is the [ContentLength](BodyFraming::ContentLength),
+    ///    an invalid one is [Invalid](BodyFraming::Invalid)
+    /// 3. without both headers the Request has no body ([None](BodyFraming::None))
+    ///
+    /// Example:
+    /// ```
+    /// use whdp::{BodyFraming, Request};
+    ///
+    /// let req = Request::try_from("POST / HTTP/1.1\nContent-Length: 4\nTransfer-Encoding: chunked\n\n").unwrap();
+    /// assert_eq!(req.body_framing(), BodyFraming::Chunked);
+    /// ```
+    pub fn body_framing(&self) -> BodyFraming {
+        if get_header_ignore_case(&self.headers, TRANSFER_ENCODING).is_some() {
+            return if is_chunked(&self.headers) { BodyFraming::Chunked } else { BodyFraming::Invalid };
+        }
+        match parse_content_length(&self.headers) {
+            Ok(Some(length)) => BodyFraming::ContentLength(length as u64),
+            Ok(None) => BodyFraming::None,
+            Err(_) => BodyFraming::Invalid,
+        }
+    }
+    /// reads the body according to the framing (the transfer codings have to be supported)
+    fn read_framed_body<R: BufRead>(&self, reader: &mut R, limit: Option<usize>) -> Result<String, HttpParseError> {
+        check_transfer_codings(&self.headers)?;
+        match self.body_framing() {
+            BodyFraming::ContentLength(length) => {
+                let length = usize::try_from(length).map_err(|err| HttpParseError::length(err.to_string()))?;
+                read_body(reader, length, limit, Req)
+            }
+            BodyFraming::Chunked => read_chunked_body(reader, limit, Req),
+            BodyFraming::None | BodyFraming::CloseDelimited => Ok(String::new()),
+            BodyFraming::Invalid => Err(self.invalid_framing()),
+        }
+    }
+    /// the error for an [Invalid](BodyFraming::Invalid) framing
+    pub(crate) fn invalid_framing(&self) -> HttpParseError {
+        if get_header_ignore_case(&self.headers, TRANSFER_ENCODING).is_some() {
+            HttpParseError::request(CHUNKED_NOT_LAST)
+        } else {
+            HttpParseError::length(INVALID_CONTENT_LENGTH)
+        }
+    }
+    /// Parses the Request with the given [ParseOptions]
+    pub fn parse_with(s: &str, options: &ParseOptions) -> Result<Self, HttpParseError> {
+        let mut lines = strip_bom(s).lines();
+        let line = lines.next();
+        let (method, uri, version) =
+            Self::parse_meta_data_line(line, options.is_strict()).map_err(|err| note_bom(err, line))?;
+        let header_lines = measure_header_lines(options.get_soft_limits(), lines.clone());
+        let (headers, duplicates) = parse_header(&mut lines, options)?;
+        let warnings = head_warnings(options.get_soft_limits(), header_lines, Some(&uri));
+        if options.is_strict() && method == HttpMethod::Trace && parse_content_length(&headers)?.unwrap_or(0) > 0 {
+            return Err(HttpParseError::request(format!("{} {}", BODY_FORBIDDEN, method)));
+        }
+        let body = parse_body(&mut lines);
+        let mut req = Self {
+            method,
+            uri,
+            version,
+            headers,
+            body,
+            duplicates,
+            warnings,
+            raw_header_lines: Vec::new(),
+            force_http1: false,
+            skip_validation: false,
+        };
+        req.check_body_soft_limit(options);
+        Ok(req)
+    }
+    /// Parses the first Request of the string and returns it with the number of bytes it took. <br>
+    /// Leading empty lines are skipped and the body is read according to its [framing](Request::body_framing)
+    pub fn parse_prefix(s: &str) -> Result<(Request, usize), HttpParseError> {
+        let (req, remainder) = Self::parse_with_remainder(s.as_bytes())?;
+        Ok((req, s.len() - remainder.len()))
+    }
+    /// Parses the first Request of the bytes and returns it with the unconsumed bytes
+    /// (like the next pipelined Request or the tunnel data after a CONNECT Request). <br>
+    /// The framing is the same as for [parse_prefix](Request::parse_prefix)
+    pub fn parse_with_remainder(bytes: &[u8]) -> Result<(Request, &[u8]), HttpParseError> {
+        Self::parse_with_remainder_within(bytes, None)
+    }
+    /// parses the first Request of the bytes like [parse_with_remainder](Request::parse_with_remainder),
+    /// but fails before reading a body that exceeds the limit
+    pub(crate) fn parse_with_remainder_within(bytes: &[u8], limit: Option<usize>) -> Result<(Request, &[u8]), HttpParseError> {
+        sniff_http(bytes)?;
+        let start = bytes
+            .iter()
+            .position(|byte| *byte != b'\r' && *byte != b'\n')
+            .unwrap_or(bytes.len());
+        let mut reader = &bytes[start..];
+        let mut req = Self::from_str(&read_head(&mut reader, None, Req)?)?;
+        req.body = req.read_framed_body(&mut reader, limit)?;
+        Ok((req, reader))
+    }
+    /// Parses the first Request of the bytes like [parse_head](Request::parse_head)
+    /// but keeps the bytes of the whole Request (without the empty lines in front of it),
+    /// so it can be [forwarded](RequestView::forward_raw) byte-for-byte
+    ///
+    /// Example:
+    /// ```
+    /// use whdp::Request;
+    ///
+    /// let bytes = b"GET /a HTTP/1.1\r\nhost:  example.com\r\n\r\nGET /b HTTP/1.1\r\n\r\n";
+    /// let view = Request::parse_view(bytes).unwrap();
+    /// assert_eq!(view.get_head().get_uri(), "/a");
+    /// assert_eq!(view.forward_raw(), b"GET /a HTTP/1.1\r\nhost:  example.com\r\n\r\n");
+    /// ```
+    pub fn parse_view(bytes: &[u8]) -> Result<RequestView<'_>, HttpParseError> {
+        let (head, body) = Self::parse_head(bytes)?;
+        let start = skip_empty_lines(bytes);
+        Ok(RequestView {
+            head,
+            raw: &bytes[start..body.end],
+            body: body.start - start..body.end - start,
+        })
+    }
+    /// Parses the head of the first Request of the bytes without copying the body
+    /// and returns it with the range of the body within the bytes. <br>
+    /// The body is framed like for [parse_prefix](Request::parse_prefix), but a chunked body isn't decoded:
+    /// the range covers the chunks including the trailers.
+    /// Returns a [Req](crate::ParseErrorKind::Req) error if the bytes end before the body does
+    ///
+    /// Example:
+    /// ```
+    /// use whdp::Request;
+    ///
+    /// let bytes = b"POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nHello";
+    /// let (head, body) = Request::parse_head(bytes).unwrap();
+    /// assert_eq!(head.get_uri(), "/");
+    /// assert_eq!(&bytes[body], b"Hello");
+    /// ```
+    pub fn parse_head(bytes: &[u8]) -> Result<(RequestHead, Range<usize>), HttpParseError> {
+        sniff_http(bytes)?;
+        let start = skip_empty_lines(bytes);
+        let mut reader = &bytes[start..];
+        let req = Self::from_str(&read_head(&mut reader, None, Req)?)?;
+        check_transfer_codings(&req.headers)?;
+        let body_start = bytes.len() - reader.len();
+        let length = match req.body_framing() {
+            BodyFraming::Chunked => chunked_length(reader, None, Req)?,
+            BodyFraming::ContentLength(length) => usize::try_from(length).ok().filter(|length| *length <= reader.len()),
+            BodyFraming::None | BodyFraming::CloseDelimited => Some(0),
+            BodyFraming::Invalid => return Err(req.invalid_framing()),
+        };
+        let length = length.ok_or(HttpParseError::request(BODY_INCOMPLETE))?;
+        let head = RequestHead {
+            method: req.method,
+            uri: req.uri,
+            version: req.version,
+            headers: req.headers,
+        };
+        Ok((head, body_start..body_start + length))
+    }
+    /// Parses all consecutive Requests of the string (like a capture of pipelined Requests),
+    /// see [parse_prefix](Request::parse_prefix) for the framing. <br>
+    /// Empty lines between the Requests are skipped
+    pub fn parse_many(s: &str) -> Result<Vec<Request>, HttpParseError> {
+        let mut requests = Vec::new();
+        let mut rest = s;
+        while !rest.trim_start_matches(LINE_BREAK).is_empty() {
+            let (req, consumed) = Self::parse_prefix(rest)?;
+            requests.push(req);
+            rest = &rest[consumed..];
+        }
+        Ok(requests)
+    }
     fn parse_meta_data_line(
         str: Option<&str>,
+        strict: bool,
     ) -> Result<(HttpMethod, String, HttpVersion), HttpParseError> {
-        let mut split = str
-            .ok_or(HttpParseError::from((Req, OPTION_WAS_EMPTY)))?.split(EMPTY_CHAR);
+        let str = str.ok_or(HttpParseError::request(OPTION_WAS_EMPTY))?;
+        if str.trim_end() == H2_PREFACE {
+            return Err(HttpParseError::version_not_supported(format!("{} {}", UNSUPPORTED_VERSION, H2_PREFACE)));
+        }
+        let mut split = str.split(|char| is_meta_delimiter(char, strict));
         Ok((
             HttpMethod::try_from(split.next())?,
             parse_uri(split.next())?,
@@ -94,29 +420,817 @@ impl Request {
         ))
     }
     /// Get the [HttpMethod] of this Request
-    pub const fn get_method(&self) -> &HttpMethod {
+    pub fn get_method(&self) -> &HttpMethod {
         &self.method
     }
+    /// Looks if the percent-encoding of the uri is well-formed
+    /// (see [is_valid_percent_encoding](crate::is_valid_percent_encoding))
+    pub fn has_valid_uri(&self) -> bool {
+        is_valid_percent_encoding(&self.uri)
+    }
     /// Get the uri of this Request
-    pub const fn get_uri(&self) -> &String {
+    pub fn get_uri(&self) -> &String {
         &self.uri
     }
     /// Get the headers of this Request
-    pub const fn get_headers(&self) -> &BTreeMap<String, String> {
+    pub fn get_headers(&self) -> &BTreeMap<String, String> {
         &self.headers
     }
+    /// Get the Headers as a mutable reference to manipulate it yourself
+    pub fn get_headers_mut(&mut self) -> &mut BTreeMap<String, String> {
+        &mut self.headers
+    }
+    /// Replaces all the headers of this Request
+    pub fn set_headers(&mut self, headers: BTreeMap<String, String>) -> &mut Self {
+        self.headers = headers;
+        self
+    }
+    /// Get the header value to a specific key (the key is matched case-insensitive)
+    pub fn get_header(&self, key: &str) -> Option<&String> {
+        get_header_ignore_case(&self.headers, key)
+    }
+    /// Retains only the headers for which the predicate returns true
+    pub fn retain_headers<F: FnMut(&str, &str) -> bool>(&mut self, mut f: F) -> &mut Self {
+        self.headers.retain(|key, value| f(key, value));
+        self
+    }
+    /// Removes all hop-by-hop headers (the standard ones, the ones named in the Connection header
+    /// and the Connection header itself) and returns the removed headers
+    pub fn strip_hop_by_hop(&mut self) -> Vec<(String, String)> {
+        strip_hop_by_hop(&mut self.headers)
+    }
+    /// Looks if the header is present with a truthy value (`1`, `true` or `yes`),
+    /// for example `Upgrade-Insecure-Requests: 1` or `DNT: 1`
+    pub fn header_flag(&self, key: &str) -> bool {
+        self.get_header(key)
+            .is_some_and(|value| TRUTHY.iter().any(|truthy| truthy.eq_ignore_ascii_case(value.trim())))
+    }
+    /// Get the details of the WebSocket opening handshake, if the Upgrade header names websocket
+    /// and the Sec-WebSocket-Key and Sec-WebSocket-Version headers are present
+    #[cfg(any(test, feature = "websocket"))]
+    pub fn websocket_handshake(&self) -> Option<WebSocketHandshake> {
+        parse_handshake(&self.headers)
+    }
+    /// Get the header lines added verbatim with [with_raw_header_line](RequestBuilder::with_raw_header_line)
+    /// (they aren't part of [get_headers](Request::get_headers) and parsing never fills them)
+    pub fn get_raw_header_lines(&self) -> &[String] {
+        &self.raw_header_lines
+    }
+    /// Get the warnings for the [soft limits](crate::SoftLimits) of the [ParseOptions]
+    /// the Request reached while parsing (always empty without soft limits)
+    pub fn get_warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+    pub(crate) fn check_body_soft_limit(&mut self, options: &ParseOptions) {
+        update_body_warning(&mut self.warnings, options.get_soft_limits(), &self.body);
+    }
+    /// Looks if the header was repeated while parsing (case-insensitive). <br>
+    /// How the values were combined depends on the [DuplicatePolicy](crate::DuplicatePolicy)
+    pub fn had_duplicate(&self, key: &str) -> bool {
+        self.duplicates.contains(&key.to_ascii_lowercase())
+    }
+    /// Compares the method, uri, body and headers of both Requests while ignoring
+    /// the given headers (like `Date` or `User-Agent`). <br>
+    /// Header names are compared case-insensitive, unlike the derived [PartialEq]
+    pub fn semantically_eq(&self, other: &Request, ignore: &[&str]) -> bool {
+        self.method == other.method
+            && self.uri == other.uri
+            && self.body == other.body
+            && normalized_headers(&self.headers, ignore) == normalized_headers(&other.headers, ignore)
+    }
+    /// Get an owned copy of the header value to a specific key (the key is matched case-insensitive)
+    pub fn header_value(&self, key: &str) -> Option<String> {
+        self.get_header(key).cloned()
+    }
     /// Get the body of this Request
-    pub const fn get_body(&self) -> &String {
+    pub fn get_body(&self) -> &String {
         &self.body
     }
+    /// Get the part of the body before the first occurrence of the delimiter
+    /// (returns [None] if the body doesn't contain the delimiter)
+    pub fn body_until(&self, delim: &str) -> Option<&str> {
+        self.body.find(delim).map(|index| &self.body[..index])
+    }
+    /// Get the value of the Content-Length header
+    /// (returns [None] if it's missing or not a valid number)
+    pub fn get_content_length(&self) -> Option<usize> {
+        parse_content_length(&self.headers).ok().flatten()
+    }
+    /// Checks if the declared Content-Length matches the actual length of the body. <br>
+    /// A missing Content-Length header is accepted
+    pub fn validate_content_length(&self) -> Result<(), HttpParseError> {
+        match parse_content_length(&self.headers)? {
+            Some(length) if length != self.body.len() => Err(HttpParseError::length(format!("{}: {} but the body has {} bytes", CONTENT_LENGTH_MISMATCH, length, self.body.len()))),
+            _ => Ok(()),
+        }
+    }
+    /// Looks if the Request is a plain [GET](HttpMethod::Get) with an empty body
+    /// and neither a Content-Length nor a Transfer-Encoding header
+    pub fn is_simple_get(&self) -> bool {
+        self.method == HttpMethod::Get
+            && self.body.is_empty()
+            && self.get_header(CONTENT_LENGTH).is_none()
+            && self.get_header(TRANSFER_ENCODING).is_none()
+    }
+    /// Get the ETags of the If-None-Match header (`*` is returned as an empty list)
+    pub fn get_if_none_match(&self) -> Option<Vec<ETag>> {
+        let value = self.get_header(IF_NONE_MATCH)?;
+        if value.trim() == ANY {
+            return Some(Vec::new());
+        }
+        Some(value.split(',').filter_map(|etag| ETag::from_str(etag).ok()).collect())
+    }
+    /// Looks if the If-None-Match header matches the [ETag] with the weak comparison,
+    /// which means a [GET](HttpMethod::Get) can be answered with Not Modified
+    pub fn if_none_match(&self, etag: &ETag) -> bool {
+        self.get_if_none_match()
+            .is_some_and(|etags| etags.is_empty() || etags.iter().any(|other| other.matches(etag, false)))
+    }
+    /// Get the If-Modified-Since header parsed to a [SystemTime]
+    pub fn if_modified_since(&self) -> Option<SystemTime> {
+        get_header_ignore_case(&self.headers, IF_MODIFIED_SINCE).and_then(|date| parse_http_date(date))
+    }
+    /// Looks if the resource wasn't modified since the If-Modified-Since date,
+    /// which means the Request can be answered with Not Modified. <br>
+    /// Only a [GET](HttpMethod::Get) or [HEAD](HttpMethod::Head) without an If-None-Match header
+    /// is considered (the ETags take precedence, see [if_none_match](Request::if_none_match)).
+    /// The last modification is compared in whole seconds like the HTTP date
+    pub fn is_not_modified(&self, last_modified: SystemTime) -> bool {
+        if !matches!(self.method, HttpMethod::Get | HttpMethod::Head)
+            || get_header_ignore_case(&self.headers, IF_NONE_MATCH).is_some()
+        {
+            return false;
+        }
+        let Some(since) = self.if_modified_since() else {
+            return false;
+        };
+        modified_since(last_modified, since) == Some(false)
+    }
+    /// Get the If-Unmodified-Since header parsed to a [SystemTime]
+    pub fn get_if_unmodified_since(&self) -> Option<SystemTime> {
+        get_header_ignore_case(&self.headers, IF_UNMODIFIED_SINCE).and_then(|date| parse_http_date(date))
+    }
+    /// Looks if the resource was modified since the If-Unmodified-Since date,
+    /// which means the Request has to be answered with Precondition Failed. <br>
+    /// The header is ignored with an If-Match header (the ETags take precedence) or an invalid date.
+    /// The last modification is compared in whole seconds like the HTTP date
+    pub fn precondition_failed(&self, last_modified: SystemTime) -> bool {
+        if get_header_ignore_case(&self.headers, IF_MATCH).is_some() {
+            return false;
+        }
+        let Some(since) = self.get_if_unmodified_since() else {
+            return false;
+        };
+        modified_since(last_modified, since) == Some(true)
+    }
+    /// Get the language ranges of the Accept-Language header sorted by preference
+    /// (an empty list if the header is missing)
+    pub fn get_accept_language(&self) -> Vec<LanguageRange> {
+        self.get_header(ACCEPT_LANGUAGE)
+            .map(|value| parse_language_ranges(value))
+            .unwrap_or_default()
+    }
+    /// Chooses the best of the available languages for the Accept-Language header
+    /// (see [RFC 4647](https://www.rfc-editor.org/rfc/rfc4647)). <br>
+    /// A region falls back to its language (`en-GB` matches `en`) and ranges with `q=0` exclude languages
+    pub fn negotiate_language(&self, available: &[&str]) -> Option<String> {
+        negotiate_language(&self.get_accept_language(), available)
+    }
+    /// Chooses the best of the offered media types for the Accept header
+    /// (see [RFC 7231](https://www.rfc-editor.org/rfc/rfc7231#section-5.3.2)). <br>
+    /// Every offer gets the quality of the most specific matching range
+    /// (`text/html;level=1` > `text/html` > `text/*` > `*/*`) and ranges with `q=0` exclude offers.
+    /// If several offers have the highest quality the one matched by the more specific range wins,
+    /// then the first offered one (also without an Accept header)
+    pub fn negotiate(&self, offers: &[&str]) -> Option<String> {
+        negotiate_media_type(self.get_header(ACCEPT).map(String::as_str), offers)
+    }
+    /// Get the directives of the Cache-Control header (empty if it is absent),
+    /// for example `no-cache` to bypass a stored Response or `max-age`
+    pub fn cache_control(&self) -> CacheControl {
+        get_header_ignore_case(&self.headers, CACHE_CONTROL).map_or(CacheControl::default(), |value| CacheControl::parse(value))
+    }
+    /// Get the addresses of the X-Forwarded-For header in order (client first, last proxy last)
+    pub fn forwarded_for(&self) -> Vec<String> {
+        self.forwarded_for_iter().map(String::from).collect()
+    }
+    /// Get the address of the client behind the given number of trusted proxies. <br>
+    /// Every trusted proxy appends the address it received the Request from, so the client
+    /// is the `trusted_hops`-th address from the right. Returns [None] without trusted proxies
+    /// or if the X-Forwarded-For header contains less addresses
+    pub fn client_ip(&self, trusted_hops: usize) -> Option<&str> {
+        let addresses: Vec<&str> = self.forwarded_for_iter().collect();
+        addresses.len().checked_sub(trusted_hops).and_then(|idx| match trusted_hops {
+            0 => None,
+            _ => addresses.get(idx).copied(),
+        })
+    }
+    /// Get the elements of the Forwarded header in order (client side first, last proxy last)
+    pub fn forwarded(&self) -> Vec<ForwardedElement> {
+        self.get_header(FORWARDED)
+            .map(|value| parse_forwarded(value))
+            .unwrap_or_default()
+    }
+    /// Appends the element to the Forwarded header (comma separated) or creates the header. <br>
+    /// Used by proxies to record the hop they received the Request from
+    pub fn append_forwarded(&mut self, element: ForwardedElement) -> &mut Self {
+        self.append_list_header(FORWARDED, element.to_string())
+    }
+    /// Appends the address to the X-Forwarded-For header (comma separated) or creates the header
+    pub fn append_x_forwarded_for(&mut self, ip: IpAddr) -> &mut Self {
+        self.append_list_header(X_FORWARDED_FOR, ip.to_string())
+    }
+    fn append_list_header(&mut self, key: &str, value: String) -> &mut Self {
+        // merging never fails, only the strict Error and Identical policies do
+        let _ = self.headers.insert_with(String::from(key), value, DuplicatePolicy::Merge, false);
+        self
+    }
+    fn forwarded_for_iter(&self) -> impl Iterator<Item=&str> {
+        self.get_header(X_FORWARDED_FOR)
+            .into_iter()
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+    }
+    /// Looks if the Request contains an `Expect: 100-continue` header
+    pub fn expects_continue(&self) -> bool {
+        self.get_header(EXPECT)
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case(CONTINUE))
+    }
+    /// Looks if the Request has a body that isn't empty or only whitespace
+    pub fn has_body(&self) -> bool {
+        has_body(&self.body)
+    }
+    /// Looks if the body is uncompressed text that can be transformed. <br>
+    /// The Content-Type has to be text/*, application/json, application/*+json, application/xml,
+    /// application/*+xml or application/x-www-form-urlencoded and a Content-Encoding mustn't be present
+    pub fn is_textual(&self) -> bool {
+        is_textual(&self.headers)
+    }
+    /// Get how a body is treated for the method of the Request (see [BodyExpectation])
+    pub fn body_expectation(&self) -> BodyExpectation {
+        self.method.body_expectation()
+    }
+    /// Get the parsed Content-Type header ([None] if it is absent or malformed). <br>
+    /// This is the media type the body parsers (like forms and multipart) consult
+    pub fn get_content_type(&self) -> Option<MediaType> {
+        content_type(&self.headers)
+    }
+    /// Get the (unquoted) boundary parameter of a `multipart/*` Content-Type header
+    /// ([None] for other Content-Types or a missing or empty boundary)
+    pub fn multipart_boundary(&self) -> Option<String> {
+        let content_type = self.get_content_type().filter(|media_type| media_type.get_type() == MULTIPART)?;
+        content_type
+            .boundary()
+            .map(str::trim_end)
+            .filter(|boundary| !boundary.is_empty())
+            .map(String::from)
+    }
+    /// Lazily iterates over the decoded pairs of an `application/x-www-form-urlencoded` body
+    /// (empty for other Content-Types). <br>
+    /// A pair that can't be decoded yields an error, the following pairs are still yielded
+    pub fn form_fields_iter(&self) -> impl Iterator<Item = Result<(String, String), HttpParseError>> + '_ {
+        let body = if has_media_type(&self.headers, FORM_URLENCODED) {
+            self.body.as_str()
+        } else {
+            ""
+        };
+        form_fields(body)
+    }
+    /// Get the charset parameter of the Content-Type header
+    pub fn charset(&self) -> Option<String> {
+        charset(&self.headers)
+    }
+    /// Looks if the body is empty (a body of only whitespace isn't empty, see [has_body](Request::has_body))
+    pub fn is_empty_body(&self) -> bool {
+        self.body.is_empty()
+    }
+    /// Get the [Response] embedded in the body if the Content-Type is `message/http`
+    /// (like a webhook callback carrying a Response) or [None] for every other Content-Type. <br>
+    /// The body of the embedded Response is framed by its own Content-Length
+    /// (or chunked Transfer-Encoding) and otherwise extends to the end of the outer body.
+    /// An embedded body that is declared longer than the outer body is a [Length](crate::ParseErrorKind::Length) error
+    pub fn get_embedded_response(&self) -> Option<Result<Response, HttpParseError>> {
+        let options = ParseOptions::new().with_max_body_size(self.body.len());
+        has_media_type(&self.headers, MESSAGE_HTTP).then(|| Response::read_from(&mut self.body.as_bytes(), &options))
+    }
+    /// Set the body to a specific String
+    pub fn set_body(&mut self, body: &str) -> &mut Self {
+        self.body = String::from(body);
+        self
+    }
+    /// Looks if the connection should be kept alive after this Request. <br>
+    /// HTTP/1.1 (and newer) defaults to keep-alive unless `Connection: close` is sent,
+    /// HTTP/1.0 defaults to close unless `Connection: keep-alive` is sent
+    pub fn is_keep_alive(&self) -> bool {
+        let connection = self.get_header(CONNECTION);
+        let has_token = |token: &str| {
+            connection.is_some_and(|value| value.split(',').any(|item| item.trim().eq_ignore_ascii_case(token)))
+        };
+        match self.version {
+            HttpVersion::One => has_token(KEEP_ALIVE),
+            _ => !has_token(CLOSE),
+        }
+    }
+    /// Get the transfer codings of the TE header the client accepts in the Response
+    /// (lowercase, like `trailers`) with their quality sorted by preference. <br>
+    /// Codings with the quality 0 are kept, because they are explicitly refused
+    pub fn te(&self) -> Vec<(String, f32)> {
+        self.get_header(TE).map(|value| parse_quality_tokens(value)).unwrap_or_default()
+    }
+    /// Get the protocols offered in the Upgrade header in order of preference
+    /// (an empty list if the header is missing)
+    pub fn upgrade_protocols(&self) -> Vec<String> {
+        self.get_header(UPGRADE).map(|value| parse_token_list(value)).unwrap_or_default()
+    }
+    /// Get the body of this Request parsed to the Type T. <br>
+    /// Returns a [Body](crate::ParseErrorKind::Body) error if the body is empty or malformed
+    pub fn get_parsed_body<T: Deserialize>(&self) -> Result<T, HttpParseError> {
+        parse_body_to(&self.body)
+    }
     /// Get the body of this Request parsed to the Type T
-    pub fn get_parsed_body<T: Deserialize>(&self) -> Result<T, ParseError> {
-        T::deserialize_str(self.get_body().as_str())
+    /// or [None] if the body is empty or only whitespace
+    pub fn get_parsed_body_opt<T: Deserialize>(&self) -> Result<Option<T>, HttpParseError> {
+        parse_body_opt(&self.body)
     }
     /// Get the version of this Request
-    pub const fn get_version(&self) -> &HttpVersion {
+    pub fn get_version(&self) -> &HttpVersion {
         &self.version
     }
+    /// Get the host the Request is targeted at without the port
+    /// (and without the brackets of an IPv6 literal like `[2001:db8::1]:8080`). <br>
+    /// The authority of an absolute uri takes precedence over the Host header
+    pub fn get_host(&self) -> Option<String> {
+        let host = self.effective_host()?;
+        split_host_port(&host).map(|(host, _)| String::from(host))
+    }
+    /// Get the port of the host the Request is targeted at ([None] if the host has no port)
+    pub fn get_port(&self) -> Option<u16> {
+        let host = self.effective_host()?;
+        split_host_port(&host).and_then(|(_, port)| port)
+    }
+    /// Get the host the Request is targeted at. <br>
+    /// The authority of an absolute uri takes precedence over the Host header
+    pub(crate) fn effective_host(&self) -> Option<String> {
+        let target = Target::split(&self.uri);
+        match target.get_authority() {
+            Some(authority) if target.is_http() => Some(String::from(authority)),
+            _ => self.header_value(HOST),
+        }
+    }
+    /// Get the full url the client intended to reach (for access logs or upstream connections). <br>
+    /// An absolute uri is returned as is, the authority of a CONNECT Request (`host:port`) too.
+    /// Otherwise the url is composed of the `http` scheme, the Host header and the path with the query
+    /// ([None] if there is no Host header)
+    pub fn effective_url(&self) -> Option<String> {
+        let target = Target::split(&self.uri);
+        if target.is_http() {
+            return Some(self.uri.clone());
+        }
+        if self.method == HttpMethod::Connect {
+            return Some(self.uri.clone()).filter(|uri| !uri.is_empty());
+        }
+        let host = self.header_value(HOST)?;
+        let path = if target.is_asterisk() { "" } else { self.uri.as_str() };
+        Some(format!("{}{}{}", HTTP_SCHEME, host.trim(), path))
+    }
+    /// Get the decoded pairs of the query in order, repeated keys included. <br>
+    /// Pairs are separated by `&` or the legacy `;`, empty pairs are skipped,
+    /// a key without `=` has an empty value and `+` stands for a space.
+    /// A component that isn't validly percent-encoded is kept as it is
+    pub fn get_query_pairs(&self) -> Vec<(String, String)> {
+        Target::split(&self.uri).get_query_pairs()
+    }
+    /// Get the decoded query parameters (the first value of a repeated key wins,
+    /// see [get_query_pairs](Request::get_query_pairs))
+    pub fn get_query_params(&self) -> BTreeMap<String, String> {
+        let mut params = BTreeMap::new();
+        for (key, value) in self.get_query_pairs() {
+            params.entry(key).or_insert(value);
+        }
+        params
+    }
+    /// Get all the decoded values of the query parameter in order (`?tag=a&tag=b` has two). <br>
+    /// Values that don't need decoding are borrowed from the uri
+    pub fn query_values(&self, key: &str) -> Vec<Cow<'_, str>> {
+        raw_query(&self.uri)
+            .into_iter()
+            .flat_map(query_pairs_iter)
+            .filter(|(name, _)| name == key)
+            .map(|(_, value)| value)
+            .collect()
+    }
+    /// Get the first value of the query parameter parsed to the type
+    /// ([None] if the parameter is absent)
+    pub fn query_param<T: FromStr>(&self, key: &str) -> Option<Result<T, T::Err>> {
+        self.query_values(key).first().map(|value| T::from_str(value))
+    }
+    /// Get the path of the uri as a canonical routing key (for route tables or cache keys):
+    /// duplicate slashes are collapsed, `.` segments removed and `..` segments resolved
+    /// without escaping the root (`/a//b/./c/` becomes `/a/b/c/`). <br>
+    /// The query, the fragment and the scheme and authority of an absolute uri are dropped.
+    /// Percent-encoded bytes are left as they are, so the path can be decoded before or afterwards
+    pub fn canonical_path(&self) -> String {
+        Target::split(&self.uri).canonical_path()
+    }
+    /// Get the uri parsed to a [Target] (scheme, authority, path, query and fragment). <br>
+    /// Returns a [Util](crate::ParseErrorKind::Util) error if it is empty or contains whitespace
+    pub fn get_target(&self) -> Result<Target, HttpParseError> {
+        Target::parse(&self.uri)
+    }
+    /// Get the [effective url](Request::effective_url) or one with the default host if there is no Host header
+    fn absolute_url(&self) -> String {
+        self.effective_url()
+            .unwrap_or_else(|| format!("{}{}{}", HTTP_SCHEME, DEFAULT_HOST, self.uri))
+    }
+    /// Computes a stable SHA-256 fingerprint of the components selected by the [FingerprintConfig]
+    /// (for example to detect duplicate webhook deliveries). <br>
+    /// The fingerprint of a Request and a config never changes between versions of this crate
+    pub fn fingerprint(&self, config: &FingerprintConfig) -> [u8; 32] {
+        fingerprint(config, &self.method.to_string(), &self.uri, &self.headers, &self.body)
+    }
+    /// Converts the Request into a single access log line in the format
+    /// `method uri version status bytes` (for example `GET /index.html HTTP/1.1 200 512`)
+    pub fn to_clf(&self, status: &HttpStatus, bytes: usize) -> String {
+        format!("{} {} {} {} {}", self.method, self.uri, self.version, status.get_code(), bytes)
+    }
+    /// Converts the Request into a single access log line like [to_clf](Request::to_clf)
+    /// prefixed by the remote address
+    pub fn to_clf_with_addr(&self, addr: &str, status: &HttpStatus, bytes: usize) -> String {
+        format!("{} {}", addr, self.to_clf(status, bytes))
+    }
+    /// Converts the Request into a runnable curl command to reproduce it. <br>
+    /// Hop-by-hop headers are omitted and sensitive headers (like Authorization or Cookie)
+    /// are redacted unless `include_secrets` is true
+    pub fn to_curl(&self, include_secrets: bool) -> String {
+        let mut string = format!("curl -X {} {}", self.method, shell_quote(&self.absolute_url()));
+        for (key, value) in &self.headers {
+            if is_hop_by_hop(key) {
+                continue;
+            }
+            let value = if include_secrets || !is_sensitive(key) { value } else { REDACTED };
+            string.push_str(" -H ");
+            string.push_str(&shell_quote(&format!("{}: {}", key, value)));
+        }
+        if !self.body.is_empty() {
+            string.push_str(" --data-raw ");
+            string.push_str(&shell_quote(&self.body));
+        }
+        string
+    }
+    /// Writes the Request in the HTTP/1.x wire format to the [Write]. <br>
+    /// Returns a [Version](crate::ParseErrorKind::Version) error for HTTP/2 and HTTP/3
+    /// unless [force_http1_framing](Request::force_http1_framing) was set.
+    /// The Request is checked with [validate_for_send](Request::validate_for_send) first:
+    /// an [Error](crate::Severity::Error) aborts with a [Req](crate::ParseErrorKind::Req) error, warnings are ignored
+    pub fn write_to<W: Write>(&self, w: &mut W) -> Result<(), HttpParseError> {
+        self.write_to_with(w, LineEnding::Crlf)
+    }
+    /// Checks the Request for common mistakes before it is sent
+    /// (like a missing Host header on HTTP/1.1 or a Content-Length that doesn't match the body)
+    /// and returns every [ValidationIssue] found
+    pub fn validate_for_send(&self) -> Result<(), Vec<ValidationIssue>> {
+        into_result(request_issues(&self.uri, &self.version, &self.headers, &self.body))
+    }
+    /// Disables the [validate_for_send](Request::validate_for_send) check of [write_to](Request::write_to)
+    /// (for example to send a deliberately malformed Request in a test)
+    pub fn skip_send_validation(&mut self) -> &mut Self {
+        self.skip_validation = true;
+        self
+    }
+    /// Writes the Request like [write_to](Request::write_to) but with the given [LineEnding]
+    pub fn write_to_with<W: Write>(&self, w: &mut W, line_ending: LineEnding) -> Result<(), HttpParseError> {
+        if !self.skip_validation {
+            check_issues(request_issues(&self.uri, &self.version, &self.headers, &self.body), Req)?;
+        }
+        w.write_all(&self.to_bytes_with(line_ending)?)
+            .map_err(|err| HttpParseError::request(err.to_string()))
+    }
+    /// Converts the Request into the bytes of the HTTP/1.x wire format. <br>
+    /// Returns a [Version](crate::ParseErrorKind::Version) error for HTTP/2 and HTTP/3
+    /// unless [force_http1_framing](Request::force_http1_framing) was set
+    pub fn to_bytes(&self) -> Result<Vec<u8>, HttpParseError> {
+        self.to_bytes_with(LineEnding::Crlf)
+    }
+    /// Converts the Request like [to_bytes](Request::to_bytes) but with the given [LineEnding]
+    /// (the body is kept as it is)
+    pub fn to_bytes_with(&self, line_ending: LineEnding) -> Result<Vec<u8>, HttpParseError> {
+        check_http1_framing(&self.version, self.force_http1)?;
+        Ok(to_wire_string(&self.start_line(), &self.headers, &self.raw_header_lines, &self.body, true, line_ending).into_bytes())
+    }
+    /// Allows writing HTTP/2 and HTTP/3 Requests in the HTTP/1.x wire format (for example for logging)
+    pub fn force_http1_framing(&mut self) -> &mut Self {
+        self.force_http1 = true;
+        self
+    }
+    /// Switches the version and adjusts the headers to it. <br>
+    /// Pseudo headers (starting with a colon) are dropped and a Host header is ensured for HTTP/1.x
+    pub fn downgrade_to(&mut self, version: HttpVersion) -> &mut Self {
+        let authority = self.headers.get(AUTHORITY_PSEUDO_HEADER).cloned();
+        self.headers.retain(|key, _| !key.starts_with(PSEUDO_HEADER_PREFIX));
+        self.version = version;
+        let http1 = matches!(version, HttpVersion::One | HttpVersion::OnePointOne);
+        if http1 && self.get_header(HOST).is_none() {
+            let host = authority.or_else(|| self.effective_host()).unwrap_or_default();
+            self.headers.insert(String::from(HOST), host);
+        }
+        self
+    }
+    /// Converts the Request into its raw CRLF wire format. <br>
+    /// Sensitive headers are redacted unless `include_secrets` is true
+    pub fn to_raw_string(&self, include_secrets: bool) -> String {
+        to_wire_string(&self.start_line(), &self.headers, &self.raw_header_lines, &self.body, include_secrets, LineEnding::Crlf)
+    }
+    fn start_line(&self) -> String {
+        format!("{} {} {}", self.method, self.uri, self.version)
+    }
+}
+
+/// Builder impl for [Request]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct RequestBuilder {
+    method: Option<HttpMethod>,
+    uri: Option<String>,
+    version: Option<HttpVersion>,
+    headers: Option<BTreeMap<String, String>>,
+    body: Option<String>,
+    raw_header_lines: Vec<String>,
+    strict: bool,
+}
+
+impl RequestBuilder {
+    /// validates if all the items are present
+    pub fn validate(&self) -> bool {
+        self.method.is_some()
+            && self.uri.is_some()
+            && self.version.is_some()
+            && self.headers.is_some()
+            && self.body.is_some()
+    }
+    /// validates if all the items are present
+    /// and otherwise returns every [MissingField]
+    pub fn validate_detailed(&self) -> Result<(), Vec<MissingField>> {
+        let missing = self.missing_fields();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(missing)
+        }
+    }
+    /// looks if all the items are present (same as [validate](RequestBuilder::validate))
+    pub fn is_complete(&self) -> bool {
+        self.validate()
+    }
+    /// returns the differences of the method, uri, version, headers (per header) and body
+    /// from this builder to the other one
+    pub fn diff(&self, other: &RequestBuilder) -> Vec<FieldDiff> {
+        let mut diffs = Vec::new();
+        diff_field(&mut diffs, "method", &self.method, &other.method);
+        diff_field(&mut diffs, "uri", &self.uri, &other.uri);
+        diff_field(&mut diffs, "version", &self.version, &other.version);
+        diff_headers(&mut diffs, "headers", &self.headers, &other.headers);
+        diff_field(&mut diffs, "body", &self.body, &other.body);
+        diffs
+    }
+    fn missing_fields(&self) -> Vec<MissingField> {
+        [
+            (self.method.is_none(), MissingField::Method),
+            (self.uri.is_none(), MissingField::Uri),
+            (self.version.is_none(), MissingField::Version),
+            (self.headers.is_none(), MissingField::Headers),
+            (self.body.is_none(), MissingField::Body),
+        ]
+        .into_iter()
+        .filter_map(|(missing, field)| missing.then_some(field))
+        .collect()
+    }
+    /// creates a new instance of RequestBuilder with [None] values
+    pub const fn new() -> Self {
+        Self {
+            method: None,
+            uri: None,
+            version: None,
+            headers: None,
+            body: None,
+            raw_header_lines: Vec::new(),
+            strict: false,
+        }
+    }
+    /// trys to make it to a [Request] otherwise returns a [HttpParseError]. <br>
+    /// A strict builder (see [with_strict](RequestBuilder::with_strict)) also returns an error
+    /// for a non-empty body on a method where it's [Forbidden](BodyExpectation::Forbidden)
+    pub fn build(self) -> Result<Request, HttpParseError> {
+        if !self.validate() {
+            return Err(missing_fields_error(Req, &self.missing_fields()));
+        }
+        let method = self.method.unwrap();
+        let forbidden = method.body_expectation() == BodyExpectation::Forbidden;
+        if self.strict && forbidden && self.body.as_ref().is_some_and(|body| !body.is_empty()) {
+            return Err(HttpParseError::request(format!("{} {}", BODY_FORBIDDEN, method)));
+        }
+        Ok(Request {
+            method,
+            uri: self.uri.unwrap(),
+            version: self.version.unwrap(),
+            headers: self.headers.unwrap(),
+            body: self.body.unwrap(),
+            duplicates: BTreeSet::new(),
+            warnings: Vec::new(),
+            raw_header_lines: self.raw_header_lines,
+            force_http1: false,
+            skip_validation: false,
+        })
+    }
+    /// replaces the current value with the strict parameter. <br>
+    /// A lenient builder (the default) accepts a body on every method,
+    /// use [body_expectation](Request::body_expectation) to check it yourself
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+    /// replaces the current value with the method parameter
+    pub fn with_method(mut self, method: HttpMethod) -> Self {
+        self.method = Some(method);
+        self
+    }
+    /// replaces the current value with the uri parameter
+    pub fn with_uri(mut self, uri: &str) -> Self {
+        self.uri = Some(String::from(uri));
+        self
+    }
+    /// replaces the current value with the version parameter
+    pub fn with_version(mut self, version: HttpVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+    /// replaces the current value with the header parameter
+    pub fn with_headers(mut self, headers: BTreeMap<String, String>) -> Self {
+        self.headers = Some(headers);
+        self
+    }
+    /// replaces the current value with empty header
+    pub fn with_empty_headers(self) -> Self {
+        self.with_headers(BTreeMap::new())
+    }
+    /// replaces the current value with the body parameter
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = Some(String::from(body));
+        self
+    }
+    /// replaces the current value with an empty body
+    pub fn with_empty_body(self) -> Self {
+        self.with_body("")
+    }
+    /// replaces the current body with a [`serializable`] Body
+    ///
+    /// [`serializable`]: Serialize
+    pub fn with_body_ser<T: Serialize>(self, body: T) -> Self {
+        self.with_body(&body.json())
+    }
+    /// replaces the current body with the content of the file and sets the Content-Length
+    /// and the Content-Type (guessed from the extension) headers. <br>
+    /// Returns a [Body](crate::ParseErrorKind::Body) error if the file can't be read or has a binary Content-Type (like an image)
+    pub fn with_body_from_file(mut self, path: &Path) -> Result<Self, HttpParseError> {
+        let mut headers = self.headers.take().unwrap_or_default();
+        self.body = Some(read_body_file(path, &mut headers)?);
+        Ok(self.with_headers(headers))
+    }
+    /// adds a header line that is written verbatim after the normal headers
+    /// (see [add_raw_header_line](crate::Response::add_raw_header_line)). <br>
+    /// **This bypasses the header-injection protections:** only CR, LF and NUL are rejected
+    pub fn with_raw_header_line(mut self, line: &str) -> Result<Self, HttpParseError> {
+        self.raw_header_lines.push(check_raw_header_line(line)?);
+        Ok(self)
+    }
+    /// adds the Content-Type header with the media type (like `application/json`)
+    pub fn with_content_type(mut self, mime: &str) -> Self {
+        let mut headers = self.headers.take().unwrap_or_default();
+        replace_header_ignore_case(&mut headers, CONTENT_TYPE, Some(String::from(mime)));
+        self.with_headers(headers)
+    }
+    /// replaces the current body with the finished multipart body and sets the Content-Type
+    /// (with the boundary) and the Content-Length headers. <br>
+    /// Returns a [Body](crate::ParseErrorKind::Body) error if the body isn't valid UTF-8,
+    /// use [build_multipart_bytes](RequestBuilder::build_multipart_bytes) for binary parts
+    pub fn with_multipart(self, multipart: MultipartBuilder) -> Result<Self, HttpParseError> {
+        let (content_type, body) = multipart.finish();
+        let body = String::from_utf8(body).map_err(|err| HttpParseError::body(err.to_string()))?;
+        Ok(self.with_multipart_head(content_type, body.len()).with_body(&body))
+    }
+    /// builds the Request with the finished multipart body and returns it in the HTTP/1.x wire format
+    /// (like [to_bytes](Request::to_bytes)). <br>
+    /// The body is appended as bytes, so unlike [with_multipart](RequestBuilder::with_multipart)
+    /// the parts may be binary (like images). Returns the errors of [build](RequestBuilder::build)
+    /// and [to_bytes](Request::to_bytes)
+    pub fn build_multipart_bytes(self, multipart: MultipartBuilder) -> Result<Vec<u8>, HttpParseError> {
+        let (content_type, body) = multipart.finish();
+        let req = self.with_multipart_head(content_type, body.len()).with_empty_body().build()?;
+        let mut bytes = req.to_bytes()?;
+        bytes.extend_from_slice(&body);
+        Ok(bytes)
+    }
+    fn with_multipart_head(mut self, content_type: String, length: usize) -> Self {
+        let mut headers = self.headers.take().unwrap_or_default();
+        replace_header_ignore_case(&mut headers, CONTENT_TYPE, Some(content_type));
+        replace_header_ignore_case(&mut headers, CONTENT_LENGTH, Some(length.to_string()));
+        self.with_headers(headers)
+    }
+}
+
+impl Debug for RequestBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RequestBuilder")
+            .field("method", &field_state(&self.method))
+            .field("uri", &field_state(&self.uri))
+            .field("version", &field_state(&self.version))
+            .field("headers", &field_state(&self.headers))
+            .field("body", &field_state(&self.body))
+            .finish()
+    }
+}
+
+impl Default for RequestBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serialize for RequestBuilder {
+    /// serializes the fields that are set
+    fn serialize(&self) -> Values {
+        let mut struc = BTreeMap::new();
+        if let Some(version) = &self.version {
+            struc.insert(String::from("version"), version.serialize());
+        }
+        if let Some(headers) = &self.headers {
+            struc.insert(String::from("headers"), headers.serialize());
+        }
+        if let Some(body) = &self.body {
+            struc.insert(String::from("body"), body.serialize());
+        }
+        if let Some(uri) = &self.uri {
+            struc.insert(String::from("uri"), uri.serialize());
+        }
+        if let Some(method) = &self.method {
+            struc.insert(String::from("method"), method.serialize());
+        }
+        Values::Struct(struc.into_iter().collect())
+    }
+}
+
+impl Destruct for RequestBuilder {
+    type Item = (Option<HttpMethod>, Option<String>, Option<HttpVersion>, Option<BTreeMap<String, String>>, Option<String>);
+    fn destruct(self) -> Self::Item {
+        (self.method, self.uri, self.version, self.headers, self.body)
+    }
+}
+
+/// returns the index of the first byte after the empty lines at the start
+fn skip_empty_lines(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .position(|byte| *byte != b'\r' && *byte != b'\n')
+        .unwrap_or(bytes.len())
+}
+
+/// looks if the last modification is after the date, compared in whole seconds like the HTTP date
+/// ([None] for times before the UNIX epoch)
+fn modified_since(last_modified: SystemTime, since: SystemTime) -> Option<bool> {
+    let seconds = |time: SystemTime| time.duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).ok();
+    Some(seconds(last_modified)? > seconds(since)?)
+}
+
+impl Request {
+    /// the parts of the message that are compared and hashed
+    fn message(&self) -> (&HttpMethod, &String, &HttpVersion, &BTreeMap<String, String>, &String) {
+        (&self.method, &self.uri, &self.version, &self.headers, &self.body)
+    }
+}
+
+impl PartialEq for Request {
+    fn eq(&self, other: &Self) -> bool {
+        self.message() == other.message()
+    }
+}
+
+impl Eq for Request {}
+
+impl PartialOrd for Request {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Request {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.message().cmp(&other.message())
+    }
+}
+
+impl Hash for Request {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.message().hash(state)
+    }
 }
 
 impl Debug for Request {
@@ -139,6 +1253,21 @@ impl Display for Request {
     }
 }
 
+impl From<(Option<HttpMethod>, Option<String>, Option<HttpVersion>, Option<BTreeMap<String, String>>, Option<String>)> for RequestBuilder {
+    fn from(value: (Option<HttpMethod>, Option<String>, Option<HttpVersion>, Option<BTreeMap<String, String>>, Option<String>)) -> Self {
+        let (method, uri, version, headers, body) = value;
+        Self {
+            method,
+            uri,
+            version,
+            headers,
+            body,
+            raw_header_lines: Vec::new(),
+            strict: false,
+        }
+    }
+}
+
 impl Destruct for Request {
     type Item = (HttpMethod, String, HttpVersion, BTreeMap<String, String>, String);
     fn destruct(self) -> Self::Item {
@@ -146,6 +1275,24 @@ impl Destruct for Request {
     }
 }
 
+impl From<(HttpMethod, String, HttpVersion, BTreeMap<String, String>, String)> for Request {
+    fn from(value: (HttpMethod, String, HttpVersion, BTreeMap<String, String>, String)) -> Self {
+        let (method, uri, version, headers, body) = value;
+        Self {
+            method,
+            uri,
+            version,
+            headers,
+            body,
+            duplicates: BTreeSet::new(),
+            warnings: Vec::new(),
+            raw_header_lines: Vec::new(),
+            force_http1: false,
+            skip_validation: false,
+        }
+    }
+}
+
 impl TryFrom<Values> for Request {
     type Error = ParseError;
     fn try_from(value: Values) -> Result<Self, Self::Error> {
@@ -155,7 +1302,18 @@ impl TryFrom<Values> for Request {
         let method = struc.map_val("method", HttpMethod::try_from)?;
         let version = struc.map_val("version", HttpVersion::try_from)?;
         let uri = struc.map_val("uri", String::try_from)?;
-        Ok(Self { body, headers, method, version, uri })
+        Ok(Self {
+            body,
+            headers,
+            method,
+            version,
+            uri,
+            duplicates: BTreeSet::new(),
+            warnings: Vec::new(),
+            raw_header_lines: Vec::new(),
+            force_http1: false,
+            skip_validation: false,
+        })
     }
 }
 
@@ -173,11 +1331,17 @@ impl Serialize for Request {
 
 #[cfg(test)]
 mod tests {
+    use std::borrow::Cow;
+    use std::collections::BTreeMap;
     use std::fs::read_to_string;
+    use std::net::IpAddr;
+    use std::str::FromStr;
+    use std::time::{Duration, UNIX_EPOCH};
 
-    use wjp::Serialize;
+    use wjp::{ParseError, Serialize, SerializeHelper, Values};
 
-    use crate::Request;
+    use crate::{BodyExpectation, BodyFraming, Destruct, ETag, ForwardedElement, HttpMethod, HttpVersion, MissingField, MockStream, MultipartBuilder, ParseErrorKind, ParseOptions, Request, RequestBuilder, resp_presets, Response};
+    use crate::status_presets::{not_found, ok};
 
     #[test]
     pub fn test() {
@@ -187,4 +1351,918 @@ mod tests {
         println!();
         println!("{}", req.json());
     }
+
+    #[test]
+    fn header_ignore_case() {
+        let req = Request::try_from("GET / HTTP/1.1\nContent-Type: text/plain\n\n").unwrap();
+        assert_eq!(req.get_header("content-type").unwrap(), "text/plain");
+        assert_eq!(req.header_value("CONTENT-TYPE"), Some(String::from("text/plain")));
+        assert_eq!(req.header_value("Accept"), None);
+    }
+
+    #[test]
+    fn validate_content_length() {
+        let req = Request::try_from("POST / HTTP/1.1\nContent-Length: 4\n\nbody").unwrap();
+        assert_eq!(req.get_content_length(), Some(4));
+        assert!(req.validate_content_length().is_ok());
+        let req = Request::try_from("POST / HTTP/1.1\nContent-Length: 10\n\nbody").unwrap();
+        assert_eq!(req.validate_content_length().unwrap_err().get_kind(), &ParseErrorKind::Length);
+        let req = Request::try_from("POST / HTTP/1.1\nContent-Length: abc\n\nbody").unwrap();
+        assert!(req.validate_content_length().is_err());
+        let req = Request::try_from("POST / HTTP/1.1\n\nbody").unwrap();
+        assert!(req.validate_content_length().is_ok());
+    }
+
+    #[test]
+    fn header_error_line() {
+        let err = Request::try_from("GET / HTTP/1.1\nHost: a\nbroken\n\n").unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Util);
+        assert_eq!(err.get_line(), Some(3));
+    }
+
+    #[test]
+    fn header_error_message() {
+        let err = Request::try_from("GET / HTTP/1.1\nHost: a\nbroken\n\n").unwrap_err();
+        assert_eq!(
+            err.get_msg(),
+            Some("The header line needs a name and a value separated by a colon and a space: \"broken\"")
+        );
+    }
+
+    #[test]
+    fn is_empty_body() {
+        assert!(Request::try_from("PATCH / HTTP/1.1\n\n").unwrap().is_empty_body());
+        assert!(!Request::try_from("PATCH / HTTP/1.1\n\n  \n ").unwrap().is_empty_body());
+        assert!(!Request::try_from("PATCH / HTTP/1.1\n\n\"value\"").unwrap().is_empty_body());
+    }
+
+    #[test]
+    fn parsed_body() {
+        let req = Request::try_from("PATCH / HTTP/1.1\n\n").unwrap();
+        assert!(!req.has_body());
+        let err = req.get_parsed_body::<String>().unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Body);
+        assert_eq!(err.get_msg(), Some("empty body"));
+        assert_eq!(req.get_parsed_body_opt::<String>(), Ok(None));
+
+        let req = Request::try_from("PATCH / HTTP/1.1\n\n  \n ").unwrap();
+        assert!(!req.has_body());
+        assert_eq!(req.get_parsed_body_opt::<String>(), Ok(None));
+
+        let req = Request::try_from("PATCH / HTTP/1.1\n\n\"value\"").unwrap();
+        assert!(req.has_body());
+        assert_eq!(req.get_parsed_body::<String>(), Ok(String::from("value")));
+        assert_eq!(req.get_parsed_body_opt::<String>(), Ok(Some(String::from("value"))));
+
+        let req = Request::try_from("PATCH / HTTP/1.1\n\n{broken").unwrap();
+        assert_eq!(req.get_parsed_body::<String>().unwrap_err().get_kind(), &ParseErrorKind::Body);
+    }
+
+    struct Id(usize);
+
+    impl TryFrom<Values> for Id {
+        type Error = ParseError;
+        fn try_from(value: Values) -> Result<Self, Self::Error> {
+            let mut struc = value.get_struct().ok_or(ParseError::new().with_msg("Id isn't an object"))?;
+            let id = struc.map_val("id", usize::try_from).map_err(|_err| ParseError::new().with_msg("missing field id"))?;
+            Ok(Id(id))
+        }
+    }
+
+    #[test]
+    fn parsed_body_error_detail() {
+        let req = Request::try_from("PATCH / HTTP/1.1\n\n{\"name\":\"a\"}").unwrap();
+        let err = req.get_parsed_body::<Id>().err().unwrap();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Body);
+        assert_eq!(err.get_msg(), Some("the body couldn't get parsed: missing field id"));
+        assert_eq!(Request::try_from("PATCH / HTTP/1.1\n\n{\"id\":7}").unwrap().get_parsed_body::<Id>().map(|id| id.0), Ok(7));
+        let err = Request::try_from("PATCH / HTTP/1.1\n\n{broken").unwrap().get_parsed_body::<String>().unwrap_err();
+        assert_eq!(err.get_msg(), Some("the body couldn't get parsed"));
+    }
+
+    #[test]
+    fn duplicates() {
+        let raw = "GET / HTTP/1.1\nHost: a\nAccept: text/html\nAccept: */*\nhost: b\n\n";
+        let req = Request::try_from(raw).unwrap();
+        assert!(req.had_duplicate("HOST"));
+        assert!(req.had_duplicate("accept"));
+        assert!(!req.had_duplicate("Content-Type"));
+        assert_eq!(req.get_header("Host").unwrap(), "a");
+        assert_eq!(req.get_header("Accept").unwrap(), "text/html, */*");
+        let err = Request::parse_with(raw, &ParseOptions::strict()).unwrap_err();
+        assert_eq!(err.get_line(), Some(5));
+        let raw = "POST / HTTP/1.1\nContent-Length: 1\nContent-Length: 2\n\na";
+        assert!(Request::parse_with(raw, &ParseOptions::strict()).is_err());
+    }
+
+    #[test]
+    fn retain_headers() {
+        let raw = "GET / HTTP/1.1\nX-Forwarded-For: a\nX-Forwarded-Proto: b\nHost: c\n\n";
+        let mut req = Request::try_from(raw).unwrap();
+        req.retain_headers(|key, _| !key.starts_with("X-Forwarded-"));
+        assert_eq!(req.get_headers().len(), 1);
+        assert!(req.get_header("Host").is_some());
+    }
+
+    #[test]
+    fn simple_get() {
+        assert!(Request::try_from("GET / HTTP/1.1\nHost: a\n\n").unwrap().is_simple_get());
+        assert!(!Request::try_from("HEAD / HTTP/1.1\n\n").unwrap().is_simple_get());
+        assert!(!Request::try_from("GET / HTTP/1.1\ncontent-length: 0\n\n").unwrap().is_simple_get());
+        assert!(!Request::try_from("GET / HTTP/1.1\nTransfer-Encoding: chunked\n\n").unwrap().is_simple_get());
+        assert!(!Request::try_from("GET / HTTP/1.1\n\nbody").unwrap().is_simple_get());
+    }
+
+    #[test]
+    fn strip_hop_by_hop() {
+        let raw = "GET / HTTP/1.1\nConnection: close, X-Internal-Token\nX-Internal-Token: 1\nX-Internal-Token-Id: 2\nKeep-Alive: timeout=5\nHost: a\n\n";
+        let mut req = Request::try_from(raw).unwrap();
+        let removed = req.strip_hop_by_hop();
+        assert_eq!(removed.len(), 3);
+        assert!(removed.contains(&(String::from("X-Internal-Token"), String::from("1"))));
+        assert_eq!(req.get_headers().len(), 2);
+        assert!(req.get_header("X-Internal-Token-Id").is_some());
+
+        let mut req = Request::try_from("GET / HTTP/1.1\nTE: trailers\nUpgrade: h2c\nAccept: */*\n\n").unwrap();
+        assert_eq!(req.strip_hop_by_hop().len(), 2);
+        assert_eq!(req.get_headers().len(), 1);
+    }
+
+    #[test]
+    fn equality() {
+        use std::collections::HashSet;
+
+        let repeated = Request::try_from("GET / HTTP/1.1\nAccept: a\nAccept: b\n\n").unwrap();
+        let joined = Request::try_from("GET / HTTP/1.1\nAccept: a, b\n\n").unwrap();
+        assert!(repeated.had_duplicate("Accept"));
+        assert_eq!(repeated, joined);
+        assert_eq!(repeated.cmp(&joined), std::cmp::Ordering::Equal);
+        assert_eq!(HashSet::from([repeated, joined]).len(), 1);
+
+        let raw = "POST / HTTP/1.1\nHost: a\n\nbody";
+        let limits = crate::SoftLimits::new().with_body_size(1);
+        let warned = Request::parse_with(raw, &ParseOptions::new().with_soft_limits(limits)).unwrap();
+        assert!(!warned.get_warnings().is_empty());
+        assert_eq!(warned, Request::try_from(raw).unwrap());
+        assert_ne!(warned, Request::try_from("POST / HTTP/1.1\nHost: a\n\nother").unwrap());
+    }
+
+    #[test]
+    fn body_framing() {
+        let framing = |req: &str| Request::try_from(req).unwrap().body_framing();
+        assert_eq!(framing("POST / HTTP/1.1\nContent-Length: 5\n\n"), BodyFraming::ContentLength(5));
+        assert_eq!(framing("POST / HTTP/1.1\nTransfer-Encoding: chunked\nContent-Length: 5\n\n"), BodyFraming::Chunked);
+        assert_eq!(framing("POST / HTTP/1.1\nTransfer-Encoding: chunked, identity\n\n"), BodyFraming::Invalid);
+        assert_eq!(framing("POST / HTTP/1.1\nContent-Length: five\n\n"), BodyFraming::Invalid);
+        assert_eq!(framing("GET / HTTP/1.0\n\n"), BodyFraming::None);
+
+        // the Content-Length of a chunked Request must not be used to frame it
+        let smuggled = b"POST / HTTP/1.1\r\nContent-Length: 4\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nok\r\n0\r\n\r\nGET /admin HTTP/1.1\r\n\r\n";
+        let req = Request::from_reader(&mut MockStream::new().with_read(smuggled)).unwrap();
+        assert_eq!(req.get_body(), "ok");
+        let (req, rest) = Request::parse_with_remainder(smuggled).unwrap();
+        assert_eq!(req.get_body(), "ok");
+        assert_eq!(rest, b"GET /admin HTTP/1.1\r\n\r\n");
+        let (_, body) = Request::parse_head(smuggled).unwrap();
+        assert_eq!(&smuggled[body], b"2\r\nok\r\n0\r\n\r\n");
+
+        let invalid = b"POST / HTTP/1.1\r\nContent-Length: 4x\r\n\r\nbody";
+        assert_eq!(Request::parse_with_remainder(invalid).unwrap_err().get_kind(), &ParseErrorKind::Length);
+        assert_eq!(Request::parse_head(invalid).unwrap_err().get_kind(), &ParseErrorKind::Length);
+    }
+
+    #[test]
+    fn from_reader_with() {
+        let options = ParseOptions::new().with_max_body_size(4);
+        let mut stream = MockStream::new().with_read(b"POST / HTTP/1.1\r\nContent-Length: 4\r\n\r\nbody");
+        assert_eq!(Request::from_reader_with(&mut stream, &options).unwrap().get_body(), "body");
+        let mut stream = MockStream::new().with_read(b"POST / HTTP/1.1\r\nContent-Length: 99999999999999999\r\n\r\nbody");
+        assert_eq!(Request::from_reader_with(&mut stream, &options).unwrap_err().get_kind(), &ParseErrorKind::Length);
+        let mut stream = MockStream::new().with_read(b"POST / HTTP/1.1\r\nContent-Length: 99999999999999999\r\n\r\nbody");
+        assert_eq!(Request::from_reader(&mut stream).unwrap_err().get_msg(), Some("The input ends before the end of the body"));
+    }
+
+    #[test]
+    fn from_stream_with_continue() {
+        let mut stream = MockStream::new()
+            .with_read(b"POST / HTTP/1.1\r\nExpect: 100-continue\r\nContent-Length: 4\r\n\r\n")
+            .with_read(b"body")
+            .with_would_block();
+        let req = Request::from_stream_with_continue(&mut stream, &ParseOptions::default()).unwrap();
+        stream.assert_written(b"HTTP/1.1 100 Continue\r\n\r\n");
+        assert!(req.expects_continue());
+        assert_eq!(req.get_body(), "body");
+
+        let mut stream = MockStream::new().with_read(b"POST / HTTP/1.1\r\nExpect: 100-continue\r\n\r\n");
+        let req = Request::from_stream_with_continue(&mut stream, &ParseOptions::default()).unwrap();
+        assert!(stream.get_written().is_empty());
+        assert!(req.get_body().is_empty());
+
+        let mut stream = MockStream::new().with_read(b"POST / HTTP/1.0\r\nExpect: 100-continue\r\nContent-Length: 4\r\n\r\nbody");
+        let req = Request::from_stream_with_continue(&mut stream, &ParseOptions::default()).unwrap();
+        assert!(stream.get_written().is_empty());
+        assert_eq!(req.get_body(), "body");
+
+        let options = ParseOptions::new().with_max_head_size(16).with_max_body_size(4);
+        let mut stream = MockStream::new().with_read(b"POST / HTTP/1.1\r\nX-Padding: 0123456789\r\n\r\n");
+        assert_eq!(Request::from_stream_with_continue(&mut stream, &options).unwrap_err().get_kind(), &ParseErrorKind::HeaderFieldsTooLarge);
+        let mut stream = MockStream::new().with_read(b"POST / HTTP/1.1\r\nExpect: 100-continue\r\nContent-Length: 5\r\n\r\nbody!");
+        let options = ParseOptions::new().with_max_body_size(4);
+        assert_eq!(Request::from_stream_with_continue(&mut stream, &options).unwrap_err().get_kind(), &ParseErrorKind::Length);
+    }
+
+    #[test]
+    fn version_aware_wire_format() {
+        let mut req = Request::try_from("GET / HTTP/2\n:authority: example.com\n\n").unwrap();
+        assert_eq!(req.to_bytes().unwrap_err().get_kind(), &ParseErrorKind::Version);
+        req.force_http1_framing();
+        assert!(req.to_bytes().is_ok());
+
+        let mut req = Request::try_from("GET / HTTP/2\n:authority: example.com\n\n").unwrap();
+        req.downgrade_to(HttpVersion::OnePointOne);
+        let mut bytes = Vec::new();
+        req.write_to(&mut bytes).unwrap();
+        assert_eq!(bytes, b"GET / HTTP/1.1\r\nHost: example.com\r\n\r\n");
+    }
+
+    #[test]
+    fn set_headers() {
+        let mut req = Request::try_from("GET / HTTP/1.1\nHost: a\n\n").unwrap();
+        req.get_headers_mut().insert(String::from("Accept"), String::from("*/*"));
+        assert_eq!(req.get_headers().len(), 2);
+        req.set_headers(BTreeMap::new());
+        assert!(req.get_headers().is_empty());
+    }
+
+    #[test]
+    fn if_none_match() {
+        let req = Request::try_from("GET / HTTP/1.1\nIf-None-Match: \"a\", W/\"b\"\n\n").unwrap();
+        assert_eq!(req.get_if_none_match(), Some(vec![ETag::strong("a"), ETag::weak("b")]));
+        assert!(req.if_none_match(&ETag::strong("b")));
+        assert!(!req.if_none_match(&ETag::strong("c")));
+        let req = Request::try_from("GET / HTTP/1.1\nIf-None-Match: *\n\n").unwrap();
+        assert!(req.if_none_match(&ETag::strong("c")));
+    }
+
+    #[test]
+    fn if_modified_since() {
+        let modified = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let req = Request::try_from("GET / HTTP/1.1\nIf-Modified-Since: Tue, 14 Nov 2023 22:13:20 GMT\n\n").unwrap();
+        assert_eq!(req.if_modified_since(), Some(modified));
+        assert!(req.is_not_modified(modified));
+        assert!(req.is_not_modified(modified + Duration::from_millis(999)));
+        assert!(req.is_not_modified(modified - Duration::from_secs(60)));
+        assert!(!req.is_not_modified(modified + Duration::from_secs(1)));
+
+        let req = Request::try_from("POST / HTTP/1.1\nIf-Modified-Since: Tue, 14 Nov 2023 22:13:20 GMT\n\n").unwrap();
+        assert!(!req.is_not_modified(modified));
+        let req = Request::try_from("GET / HTTP/1.1\nIf-Modified-Since: Tue, 14 Nov 2023 22:13:20 GMT\nIf-None-Match: \"a\"\n\n").unwrap();
+        assert!(!req.is_not_modified(modified));
+        let req = Request::try_from("GET / HTTP/1.1\nIf-Modified-Since: yesterday\n\n").unwrap();
+        assert_eq!(req.if_modified_since(), None);
+        assert!(!req.is_not_modified(modified));
+    }
+
+    #[test]
+    fn if_unmodified_since() {
+        let modified = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        for date in [
+            "Tue, 14 Nov 2023 22:13:20 GMT",
+            "Tuesday, 14-Nov-23 22:13:20 GMT",
+            "Tue Nov 14 22:13:20 2023",
+        ] {
+            let req = Request::try_from(format!("PUT / HTTP/1.1\nIf-Unmodified-Since: {}\n\n", date).as_str()).unwrap();
+            assert_eq!(req.get_if_unmodified_since(), Some(modified), "{}", date);
+            assert!(!req.precondition_failed(modified));
+            assert!(!req.precondition_failed(modified + Duration::from_millis(999)));
+            assert!(req.precondition_failed(modified + Duration::from_secs(1)));
+        }
+
+        let req = Request::try_from("PUT / HTTP/1.1\nIf-Unmodified-Since: Tue, 14 Nov 2023 22:13:20 GMT\nIf-Match: \"a\"\n\n").unwrap();
+        assert!(!req.precondition_failed(modified + Duration::from_secs(60)));
+        let req = Request::try_from("PUT / HTTP/1.1\nIf-Unmodified-Since: 0\n\n").unwrap();
+        assert_eq!(req.get_if_unmodified_since(), None);
+        assert!(!req.precondition_failed(modified));
+    }
+
+    #[test]
+    fn keep_alive() {
+        assert!(Request::try_from("GET / HTTP/1.1\n\n").unwrap().is_keep_alive());
+        assert!(!Request::try_from("GET / HTTP/1.1\nConnection: Close\n\n").unwrap().is_keep_alive());
+        assert!(!Request::try_from("GET / HTTP/1.0\n\n").unwrap().is_keep_alive());
+        assert!(Request::try_from("GET / HTTP/1.0\nConnection: keep-alive\n\n").unwrap().is_keep_alive());
+    }
+
+    #[test]
+    fn multipart_boundary() {
+        let boundary = |content_type: &str| {
+            Request::try_from(format!("POST / HTTP/1.1\nContent-Type: {}\n\n", content_type).as_str()).unwrap().multipart_boundary()
+        };
+        assert_eq!(boundary("multipart/form-data; boundary=----abc123"), Some(String::from("----abc123")));
+        assert_eq!(boundary("Multipart/Form-Data;boundary=\"a:b c\""), Some(String::from("a:b c")));
+        assert_eq!(boundary("multipart/mixed ;  charset=utf-8 ; BOUNDARY = \"xyz \""), Some(String::from("xyz")));
+        assert_eq!(boundary("multipart/form-data"), None);
+        assert_eq!(boundary("multipart/form-data; boundary=\"\""), None);
+        assert_eq!(boundary("text/plain; boundary=abc"), None);
+        assert_eq!(Request::try_from("POST / HTTP/1.1\n\n").unwrap().multipart_boundary(), None);
+
+        let (content_type, _) = MultipartBuilder::new().with_boundary("XyZ").unwrap().finish();
+        assert_eq!(boundary(&content_type), Some(String::from("XyZ")));
+    }
+
+    #[test]
+    fn te_and_upgrade() {
+        let req = Request::try_from("GET / HTTP/1.1\nTE: deflate;q=0.5, Trailers\nte: gzip;q=0\nUpgrade: h2c, websocket/13\n\n").unwrap();
+        assert_eq!(
+            req.te(),
+            vec![(String::from("trailers"), 1.0), (String::from("deflate"), 0.5), (String::from("gzip"), 0.0)]
+        );
+        assert_eq!(req.upgrade_protocols(), vec![String::from("h2c"), String::from("websocket/13")]);
+
+        let req = Request::try_from("GET / HTTP/1.1\nTE: trailers;q=2, ,chunked\n\n").unwrap();
+        assert_eq!(req.te(), vec![(String::from("chunked"), 1.0)]);
+        assert!(req.upgrade_protocols().is_empty());
+    }
+
+    #[test]
+    fn with_body_from_file() {
+        let path = std::env::temp_dir().join(format!("whdp-{}-body.json", std::process::id()));
+        std::fs::write(&path, "{\"a\":1}").unwrap();
+        let req = Request::builder()
+            .with_method(HttpMethod::Post)
+            .with_uri("/upload")
+            .with_version(HttpVersion::OnePointOne)
+            .with_body_from_file(&path)
+            .unwrap()
+            .build()
+            .unwrap();
+        let resp = Response::builder()
+            .with_status(ok())
+            .with_version(HttpVersion::OnePointOne)
+            .with_body_from_file(&path)
+            .unwrap()
+            .build()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(req.get_body(), "{\"a\":1}");
+        assert_eq!(req.get_header("Content-Length").unwrap(), "7");
+        assert_eq!(req.get_header("Content-Type").unwrap(), "application/json");
+        assert_eq!(resp.get_body(), req.get_body());
+        assert_eq!(resp.get_headers(), req.get_headers());
+
+        let err = Request::builder().with_body_from_file(&path).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Body);
+        assert!(err.get_msg().unwrap().contains("body.json"));
+
+        let path = std::env::temp_dir().join(format!("whdp-{}-body.png", std::process::id()));
+        std::fs::write(&path, [0x89, b'P', b'N', b'G']).unwrap();
+        let err = Response::builder().with_body_from_file(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Body);
+        assert!(err.get_msg().unwrap().contains("body.png (image/png)"));
+    }
+
+    #[test]
+    fn with_body_from_file_replaces_any_casing() {
+        let path = std::env::temp_dir().join(format!("whdp-{}-casing.txt", std::process::id()));
+        std::fs::write(&path, "Hello").unwrap();
+        let mut headers = BTreeMap::new();
+        headers.insert(String::from("content-type"), String::from("application/json"));
+        headers.insert(String::from("content-length"), String::from("99"));
+        let req = Request::builder()
+            .with_method(HttpMethod::Post)
+            .with_uri("/upload")
+            .with_version(HttpVersion::OnePointOne)
+            .with_headers(headers.clone())
+            .with_body_from_file(&path)
+            .unwrap()
+            .build()
+            .unwrap();
+        let resp = Response::builder()
+            .with_status(ok())
+            .with_version(HttpVersion::OnePointOne)
+            .with_headers(headers)
+            .with_body_from_file(&path)
+            .unwrap()
+            .build()
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        for headers in [req.get_headers(), resp.get_headers()] {
+            assert_eq!(headers.len(), 2);
+            assert_eq!(headers.get("Content-Type").unwrap(), "text/plain; charset=utf-8");
+            assert_eq!(headers.get("Content-Length").unwrap(), "5");
+        }
+    }
+
+    #[test]
+    fn forwarded_for() {
+        let raw = "GET / HTTP/1.1\nX-Forwarded-For: 203.0.113.7 , 10.0.0.1\nX-Forwarded-For: 10.0.0.2\n\n";
+        let req = Request::try_from(raw).unwrap();
+        assert_eq!(req.forwarded_for(), vec!["203.0.113.7", "10.0.0.1", "10.0.0.2"]);
+        assert_eq!(req.client_ip(0), None);
+        assert_eq!(req.client_ip(1), Some("10.0.0.2"));
+        assert_eq!(req.client_ip(3), Some("203.0.113.7"));
+        assert_eq!(req.client_ip(4), None);
+        assert!(Request::try_from("GET / HTTP/1.1\n\n").unwrap().forwarded_for().is_empty());
+    }
+
+    #[test]
+    fn header_flag() {
+        let req = Request::try_from("GET / HTTP/1.1\nDNT: 1\nUpgrade-Insecure-Requests: Yes\nX-Flag: 0\n\n").unwrap();
+        assert!(req.header_flag("dnt"));
+        assert!(req.header_flag("Upgrade-Insecure-Requests"));
+        assert!(!req.header_flag("X-Flag"));
+        assert!(!req.header_flag("X-Missing"));
+    }
+
+    #[test]
+    fn to_clf() {
+        let req = Request::try_from("GET /index.html?a=b HTTP/1.1\nHost: a\n\n").unwrap();
+        assert_eq!(req.to_clf(&ok(), 512), "GET /index.html?a=b HTTP/1.1 200 512");
+        assert_eq!(
+            req.to_clf_with_addr("127.0.0.1", &not_found(), 0),
+            "127.0.0.1 GET /index.html?a=b HTTP/1.1 404 0"
+        );
+    }
+
+    #[test]
+    fn negotiate() {
+        let req = Request::try_from("GET / HTTP/1.1\nAccept: text/*;q=0.8, application/json\naccept: text/html\n\n").unwrap();
+        assert_eq!(req.negotiate(&["text/plain", "text/html", "application/json"]).unwrap(), "text/html");
+        assert_eq!(req.negotiate(&["image/png"]), None);
+        let req = Request::try_from("GET / HTTP/1.1\n\n").unwrap();
+        assert_eq!(req.negotiate(&["application/json", "text/html"]).unwrap(), "application/json");
+    }
+
+    #[test]
+    fn effective_url() {
+        let req = Request::try_from("GET /search?q=whdp HTTP/1.1\nHost: example.com:8080\n\n").unwrap();
+        assert_eq!(req.effective_url().unwrap(), "http://example.com:8080/search?q=whdp");
+        let req = Request::try_from("GET https://example.com/a?b=c HTTP/1.1\nHost: other.com\n\n").unwrap();
+        assert_eq!(req.effective_url().unwrap(), "https://example.com/a?b=c");
+        let req = Request::try_from("CONNECT example.com:443 HTTP/1.1\nHost: example.com:443\n\n").unwrap();
+        assert_eq!(req.effective_url().unwrap(), "example.com:443");
+        let req = Request::try_from("OPTIONS * HTTP/1.1\nHost: example.com\n\n").unwrap();
+        assert_eq!(req.effective_url().unwrap(), "http://example.com");
+        let req = Request::try_from("GET /index.html HTTP/1.1\n\n").unwrap();
+        assert!(req.effective_url().is_none());
+    }
+
+    #[test]
+    fn embedded_response() {
+        let callback = "POST /hook HTTP/1.1\r\nContent-Type: message/http\r\n\r\nHTTP/1.1 201 Created\r\nContent-Length: 2\r\n\r\nokIGNORED";
+        let resp = Request::try_from(callback).unwrap().get_embedded_response().unwrap().unwrap();
+        assert_eq!(resp.get_status().get_code(), &201);
+        assert_eq!(resp.get_body(), "ok");
+
+        let callback = "POST /hook HTTP/1.1\r\nContent-Type: message/http\r\n\r\nHTTP/1.1 200 OK\r\n\r\nto the end";
+        let resp = Request::try_from(callback).unwrap().get_embedded_response().unwrap().unwrap();
+        assert_eq!(resp.get_body(), "to the end");
+        assert!(Request::try_from("POST / HTTP/1.1\r\n\r\nHTTP/1.1 200 OK\r\n\r\n").unwrap().get_embedded_response().is_none());
+
+        let callback = "POST /hook HTTP/1.1\r\nContent-Type: message/http\r\n\r\nHTTP/1.1 200 OK\r\nContent-Length: 99999999999999999\r\n\r\nok";
+        let err = Request::try_from(callback).unwrap().get_embedded_response().unwrap().unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Length);
+    }
+
+    #[test]
+    fn query() {
+        let req = Request::try_from("GET /items?page=2&tag=a&flag&debug=true;tag=b+c&&tag=%zz&empty=#top HTTP/1.1\n\n").unwrap();
+        assert_eq!(req.query_param::<u32>("page"), Some(Ok(2)));
+        assert_eq!(req.query_param::<bool>("debug"), Some(Ok(true)));
+        assert!(req.query_param::<u32>("tag").unwrap().is_err());
+        assert_eq!(req.query_param::<u32>("missing"), None);
+        let tags = req.query_values("tag");
+        assert_eq!(tags, ["a", "b c", "%zz"]);
+        assert!(matches!((&tags[0], &tags[1], &tags[2]), (Cow::Borrowed(_), Cow::Owned(_), Cow::Borrowed(_))));
+        assert_eq!(req.get_query_pairs().len(), 7);
+        let params = req.get_query_params();
+        assert_eq!(params.get("tag").unwrap(), "a");
+        assert_eq!(params.get("flag").unwrap(), "");
+        assert_eq!(params.get("empty").unwrap(), "");
+        assert!(Request::try_from("GET / HTTP/1.1\n\n").unwrap().get_query_pairs().is_empty());
+    }
+
+    #[test]
+    fn canonical_path() {
+        let path = |uri: &str| Request::try_from(format!("GET {} HTTP/1.1\n\n", uri).as_str()).unwrap().canonical_path();
+        assert_eq!(path("/a//b/./c/"), "/a/b/c/");
+        assert_eq!(path("/a/b/../c"), "/a/c");
+        assert_eq!(path("/../../etc/passwd"), "/etc/passwd");
+        assert_eq!(path("/a/.."), "/");
+        assert_eq!(path("/a/."), "/a/");
+        assert_eq!(path("//"), "/");
+        assert_eq!(path("/a%2F..//b?x=/../y#frag"), "/a%2F../b");
+        assert_eq!(path("http://example.com//a/./b?q"), "/a/b");
+        assert_eq!(path("https://example.com"), "/");
+    }
+
+    #[test]
+    fn to_curl() {
+        let req = Request::try_from(
+            "POST /path HTTP/1.1\nHost: example.com\nConnection: keep-alive\nAuthorization: Bearer 123\n\nit's\nöäü",
+        )
+        .unwrap();
+        assert_eq!(
+            req.to_curl(false),
+            "curl -X POST 'http://example.com/path' -H 'Authorization: [REDACTED]' -H 'Host: example.com' --data-raw 'it'\\''s\nöäü'"
+        );
+        assert!(req.to_curl(true).contains("-H 'Authorization: Bearer 123'"));
+    }
+
+    #[test]
+    fn effective_host_scheme_case() {
+        for scheme in ["HTTP", "Https", "hTTp"] {
+            let req = Request::try_from(format!("GET {}://example.com:81/a HTTP/1.1\nHost: other\n\n", scheme).as_str()).unwrap();
+            assert_eq!(req.effective_host(), Some(String::from("example.com:81")), "{}", scheme);
+            assert_eq!(req.to_curl(false), format!("curl -X GET '{}://example.com:81/a' -H 'Host: other'", scheme));
+        }
+        let req = Request::try_from("GET FTP://example.com/a HTTP/1.1\nHost: other\n\n").unwrap();
+        assert_eq!(req.effective_host(), Some(String::from("other")));
+    }
+
+    #[test]
+    fn to_raw_string() {
+        let req = Request::try_from("GET / HTTP/1.1\nCookie: a=b\n\n").unwrap();
+        assert_eq!(req.to_raw_string(false), "GET / HTTP/1.1\r\nCookie: [REDACTED]\r\n\r\n");
+        assert_eq!(req.to_raw_string(true), "GET / HTTP/1.1\r\nCookie: a=b\r\n\r\n");
+    }
+
+    #[test]
+    fn raw_header_lines() {
+        let req = Request::builder()
+            .with_method(HttpMethod::Get)
+            .with_uri("/")
+            .with_version(HttpVersion::OnePointOne)
+            .with_content_type("text/plain")
+            .with_raw_header_line("X-List:(a  b);q=1")
+            .unwrap()
+            .with_body("")
+            .build()
+            .unwrap();
+        assert_eq!(req.get_raw_header_lines(), ["X-List:(a  b);q=1"]);
+        assert_eq!(req.to_bytes().unwrap(), b"GET / HTTP/1.1\r\nContent-Type: text/plain\r\nX-List:(a  b);q=1\r\n\r\n");
+        assert!(Request::builder().with_raw_header_line("X-Evil: a\r\n\r\nGET /admin HTTP/1.1").is_err());
+    }
+
+    #[test]
+    fn const_constructors() {
+        const REQUEST_BUILDER: RequestBuilder = Request::builder();
+        assert_eq!(REQUEST_BUILDER, RequestBuilder::new());
+    }
+
+    #[test]
+    fn builder_content_type() {
+        let mut headers = BTreeMap::new();
+        headers.insert(String::from("content-type"), String::from("text/plain"));
+        let req = Request::builder()
+            .with_method(HttpMethod::Post)
+            .with_uri("/")
+            .with_version(HttpVersion::OnePointOne)
+            .with_headers(headers)
+            .with_content_type("application/json")
+            .with_body("{}")
+            .build()
+            .unwrap();
+        assert_eq!(req.get_content_type().unwrap().get_essence(), "application/json");
+        assert_eq!(req.get_headers().keys().collect::<Vec<_>>(), ["Content-Type"]);
+    }
+
+    #[test]
+    fn accept_language() {
+        let req = Request::try_from("GET / HTTP/1.1\naccept-language: en-GB,en;q=0.9,de;q=0.7\n\n").unwrap();
+        let ranges = req.get_accept_language();
+        let tags: Vec<&str> = ranges.iter().map(|range| range.get_tag()).collect();
+        assert_eq!(tags, vec!["en-GB", "en", "de"]);
+        assert_eq!(req.negotiate_language(&["de", "en"]), Some(String::from("en")));
+        assert_eq!(req.negotiate_language(&["fr"]), None);
+
+        let req = Request::try_from("GET / HTTP/1.1\nAccept-Language: *\n\n").unwrap();
+        assert_eq!(req.negotiate_language(&["fr", "de"]), Some(String::from("fr")));
+        let req = Request::try_from("GET / HTTP/1.1\n\n").unwrap();
+        assert!(req.get_accept_language().is_empty());
+        assert_eq!(req.negotiate_language(&["fr"]), None);
+    }
+
+    #[test]
+    fn builder_missing_fields() {
+        assert_eq!(
+            Request::builder().validate_detailed(),
+            Err(vec![MissingField::Method, MissingField::Uri, MissingField::Version, MissingField::Headers, MissingField::Body])
+        );
+        let builder = Request::builder().with_method(HttpMethod::Get).with_uri("/").with_empty_body();
+        assert_eq!(builder.validate_detailed(), Err(vec![MissingField::Version, MissingField::Headers]));
+        assert!(!builder.is_complete());
+        assert_eq!(
+            format!("{:?}", builder),
+            "RequestBuilder { method: set, uri: set, version: None, headers: None, body: set }"
+        );
+        let err = builder.clone().build().unwrap_err();
+        assert_eq!(err.get_msg(), Some("min. 1 field was not filled with a value: Version, Headers"));
+        let builder = builder.with_version(HttpVersion::OnePointOne).with_empty_headers();
+        assert_eq!(builder.validate_detailed(), Ok(()));
+        assert!(builder.is_complete());
+    }
+
+    #[test]
+    fn tls_client_hello() {
+        let hello: &[u8] = &[0x16, 0x03, 0x01, 0x00, 0xa5, 0x01, 0x00, 0x00, 0xa1, 0x03, 0x03];
+        let err = Request::try_from(hello).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::NotHttp);
+
+        let mut stream = MockStream::new().with_read(&[0x00, 0x9f, 0xff, 0x10, 0x42]);
+        let err = Request::from_reader(&mut stream).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::NotHttp);
+    }
+
+    #[test]
+    fn body_until() {
+        let mut req = Request::default();
+        req.set_body("first line\r\nsecond line\r\n");
+        assert_eq!(req.body_until("\r\n"), Some("first line"));
+        assert_eq!(req.body_until("line"), Some("first "));
+        assert_eq!(req.body_until("f"), Some(""));
+        assert_eq!(req.body_until("END"), None);
+    }
+
+    #[test]
+    fn unsupported() {
+        let err = Request::try_from("PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n").unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::VersionNotSupported);
+        assert!(err.get_msg().unwrap().contains("PRI * HTTP/2.0"));
+        assert_eq!(resp_presets::from_error(&err).get_status().get_code(), &505);
+
+        let mut stream = MockStream::new().with_read(b"POST / HTTP/1.1\r\nTransfer-Encoding: br, chunked\r\n\r\n");
+        let err = Request::from_reader(&mut stream).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Unsupported);
+        assert!(err.get_msg().unwrap().ends_with(" br"));
+        assert_eq!(resp_presets::from_error(&err).get_status().get_code(), &501);
+    }
+
+    #[test]
+    fn destruct_round_trip() {
+        let req = Request::try_from("POST /items HTTP/1.1\nHost: localhost\n\n{}").unwrap();
+        let (method, uri, version, headers, body) = req.clone().destruct();
+        assert_eq!((method, uri.as_str(), version, body.as_str()), (HttpMethod::Post, "/items", HttpVersion::OnePointOne, "{}"));
+        assert_eq!(headers.get("Host").unwrap(), "localhost");
+        assert_eq!(Request::from(req.clone().destruct()), req);
+
+        let builder = Request::builder().with_method(HttpMethod::Put).with_uri("/");
+        assert_eq!(RequestBuilder::from(builder.clone().destruct()), builder);
+    }
+
+    #[test]
+    fn forwarded() {
+        let raw = "GET / HTTP/1.1\nForwarded: for=192.0.2.60;proto=https\nforwarded: for=\"[2001:db8::1]\"\n\n";
+        let req = Request::try_from(raw).unwrap();
+        let elements = req.forwarded();
+        assert_eq!(elements.len(), 2);
+        assert_eq!(elements[0].get_proto(), Some("https"));
+        assert_eq!(elements[1].get_for(), Some("[2001:db8::1]"));
+        assert!(Request::default().forwarded().is_empty());
+    }
+
+    #[test]
+    fn semantically_eq() {
+        let first = Request::try_from("POST /a HTTP/1.1\nDate: Mon, 01 Jan 2024 00:00:00 GMT\nuser-agent: curl\ncontent-type: text/plain\n\nbody").unwrap();
+        let second = Request::try_from("POST /a HTTP/1.0\nContent-Type: text/plain\nUser-Agent: wget\n\nbody").unwrap();
+        assert_ne!(first, second);
+        assert!(first.semantically_eq(&second, &["Date", "User-Agent"]));
+        assert!(!first.semantically_eq(&second, &["date"]));
+        assert!(!first.semantically_eq(&second, &[]));
+        let other_body = Request::try_from("POST /a HTTP/1.1\nContent-Type: text/plain\n\nother").unwrap();
+        assert!(!second.semantically_eq(&other_body, &["User-Agent"]));
+    }
+
+    #[test]
+    fn tab_delimiter() {
+        let req = Request::try_from("GET\t/index.html\tHTTP/1.1\n\n").unwrap();
+        assert_eq!(req.get_uri(), "/index.html");
+        assert_eq!(req.get_version(), &HttpVersion::OnePointOne);
+        assert!(Request::parse_with("GET\t/index.html HTTP/1.1\n\n", &ParseOptions::strict()).is_err());
+    }
+
+    #[test]
+    fn builder_diff() {
+        let before = Request::builder()
+            .with_method(HttpMethod::Get)
+            .with_uri("/")
+            .with_headers(BTreeMap::from([(String::from("Accept"), String::from("*/*"))]));
+        let after = before
+            .clone()
+            .with_method(HttpMethod::Post)
+            .with_headers(BTreeMap::from([(String::from("Host"), String::from("example.com"))]))
+            .with_body("x");
+        let diffs: Vec<String> = before.diff(&after).iter().map(ToString::to_string).collect();
+        assert_eq!(diffs, vec![
+            "method: GET -> POST",
+            "headers Accept: */* -> (unset)",
+            "headers Host: (unset) -> example.com",
+            "body: (unset) -> x",
+        ]);
+        let struc = after.serialize().get_struct().unwrap();
+        assert_eq!(struc.len(), 4);
+        assert_eq!(struc.get("method").and_then(Values::get_string).unwrap(), "POST");
+        assert!(!struc.contains_key("version"));
+    }
+
+    #[test]
+    fn host_and_port() {
+        let req = Request::try_from("GET / HTTP/1.1\nHost: [2001:db8::1]:8080\n\n").unwrap();
+        assert_eq!(req.get_host(), Some(String::from("2001:db8::1")));
+        assert_eq!(req.get_port(), Some(8080));
+        let req = Request::try_from("GET / HTTP/1.1\nHost: [2001:db8::1]\n\n").unwrap();
+        assert_eq!(req.get_host(), Some(String::from("2001:db8::1")));
+        assert_eq!(req.get_port(), None);
+        let req = Request::try_from("GET http://example.com:81/a HTTP/1.1\nHost: other\n\n").unwrap();
+        assert_eq!(req.get_host(), Some(String::from("example.com")));
+        assert_eq!(req.get_port(), Some(81));
+        let req = Request::try_from("GET / HTTP/1.1\nHost: example.com:http\n\n").unwrap();
+        assert_eq!(req.get_host(), None);
+        assert_eq!(Request::default().get_host(), None);
+    }
+
+    #[test]
+    fn body_expectation() {
+        let matrix = [
+            (HttpMethod::Get, BodyExpectation::Discouraged),
+            (HttpMethod::Head, BodyExpectation::Forbidden),
+            (HttpMethod::Post, BodyExpectation::Expected),
+            (HttpMethod::Put, BodyExpectation::Expected),
+            (HttpMethod::Patch, BodyExpectation::Expected),
+            (HttpMethod::Delete, BodyExpectation::Discouraged),
+            (HttpMethod::Connect, BodyExpectation::Discouraged),
+            (HttpMethod::Options, BodyExpectation::Discouraged),
+            (HttpMethod::Trace, BodyExpectation::Forbidden),
+        ];
+        for (method, expectation) in matrix {
+            let req = Request::try_from(format!("{} / HTTP/1.1\n\n", method)).unwrap();
+            assert_eq!(req.body_expectation(), expectation, "{}", method);
+            let builder = || Request::builder()
+                .with_method(method)
+                .with_uri("/")
+                .with_version(HttpVersion::OnePointOne)
+                .with_empty_headers()
+                .with_body("body");
+            assert!(builder().build().is_ok());
+            assert_eq!(builder().with_strict(true).build().is_err(), expectation == BodyExpectation::Forbidden, "{}", method);
+            assert!(builder().with_strict(true).with_empty_body().build().is_ok());
+        }
+    }
+
+    #[test]
+    fn max_headers() {
+        let raw = "GET / HTTP/1.1\nHost: a\nAccept: */*\nAccept: text/html\n\n";
+        assert!(Request::parse_with(raw, &ParseOptions::new().with_max_headers(3)).is_ok());
+        let err = Request::parse_with(raw, &ParseOptions::new().with_max_headers(2)).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Length);
+        assert_eq!(err.get_line(), Some(4));
+        assert!(Request::try_from(raw).is_ok());
+        let raw = "HTTP/1.1 200 OK\nServer: a\nVary: b\n\n";
+        assert!(Response::parse_with(raw, &ParseOptions::new().with_max_headers(1)).is_err());
+    }
+
+    #[test]
+    fn strict_trace_body() {
+        let trace = "TRACE / HTTP/1.1\nContent-Length: 4\n\nbody";
+        assert!(Request::parse_with(trace, &ParseOptions::default()).is_ok());
+        let err = Request::parse_with(trace, &ParseOptions::strict()).unwrap_err();
+        assert_eq!(err.get_msg(), Some("A body isn't allowed on a Request with the method TRACE"));
+        assert!(Request::parse_with("TRACE / HTTP/1.1\nContent-Length: 0\n\n", &ParseOptions::strict()).is_ok());
+        assert!(Request::parse_with("POST / HTTP/1.1\nContent-Length: 4\n\nbody", &ParseOptions::strict()).is_ok());
+    }
+
+    #[test]
+    fn parse_head() {
+        let bytes = b"\r\nPOST /upload HTTP/1.1\r\nHost: example.com\r\nContent-Length: 11\r\n\r\nhello world GET";
+        let (head, body) = Request::parse_head(bytes).unwrap();
+        assert_eq!(head.get_method(), &HttpMethod::Post);
+        assert_eq!(head.get_uri(), "/upload");
+        assert_eq!(head.get_version(), &HttpVersion::OnePointOne);
+        assert_eq!(head.get_headers().get("Host").unwrap(), "example.com");
+        assert_eq!(body, 66..77);
+        assert_eq!(&bytes[body], b"hello world");
+
+        let bytes = b"POST / HTTP/1.1\nTransfer-Encoding: chunked\n\n5\nhello\n0\n\nnext";
+        let (_, body) = Request::parse_head(bytes).unwrap();
+        assert_eq!(&bytes[body], b"5\nhello\n0\n\n");
+
+        let (_, body) = Request::parse_head(b"GET / HTTP/1.1\n\nGET / HTTP/1.1\n\n").unwrap();
+        assert_eq!(body, 16..16);
+
+        let err = Request::parse_head(b"POST / HTTP/1.1\nContent-Length: 10\n\nshort").unwrap_err();
+        assert_eq!(err.get_msg(), Some("The input ends before the end of the body"));
+        assert!(Request::parse_head(b"POST / HTTP/1.1\nTransfer-Encoding: chunked\n\n5\nhel").is_err());
+    }
+
+    #[test]
+    fn parse_view() {
+        let message = b"POST /upload?a=%20 HTTP/1.1\r\nhost:  example.com\r\nX-Signed:  v1 \r\nX-Signed: v2\r\ncontent-length: 5\r\n\r\nhello";
+        let mut bytes = b"\r\n".to_vec();
+        bytes.extend_from_slice(message);
+        bytes.extend_from_slice(b"GET / HTTP/1.1\r\n\r\n");
+        let view = Request::parse_view(&bytes).unwrap();
+        assert_eq!(view.forward_raw(), message);
+        assert_eq!(view.get_body(), b"hello");
+        assert_eq!(view.get_head().get_uri(), "/upload?a=%20");
+        assert_ne!(Request::try_from(message.as_slice()).unwrap().to_bytes().unwrap(), message);
+
+        let chunked = b"POST / HTTP/1.1\nTransfer-Encoding: chunked\n\n5\nhello\n0\n\n";
+        let view = Request::parse_view(chunked).unwrap();
+        assert_eq!(view.forward_raw(), chunked);
+        assert_eq!(view.get_body(), b"5\nhello\n0\n\n");
+        assert!(Request::parse_view(b"POST / HTTP/1.1\nContent-Length: 10\n\nshort").is_err());
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        let plain = Request::try_from(read_to_string("src/resources/request.txt").unwrap()).unwrap();
+        let bytes = std::fs::read("src/resources/request_bom.txt").unwrap();
+        assert_eq!(Request::try_from(bytes.clone()).unwrap(), plain);
+        assert_eq!(Request::try_from(String::from_utf8(bytes.clone()).unwrap()).unwrap(), plain);
+        let (req, _) = Request::parse_with_remainder(&bytes).unwrap();
+        assert_eq!(req.get_headers(), plain.get_headers());
+
+        let err = Request::try_from("\u{feff}\u{feff}GET / HTTP/1.1\n\n").unwrap_err();
+        assert!(err.get_msg().unwrap().ends_with("which is only skipped at the very start of a message)"));
+        let err = Request::parse_many("GET / HTTP/1.1\n\n\r\n\u{feff}GET / HTTP/1.1\n\n").unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::NotHttp);
+        assert!(err.get_msg().unwrap().contains("byte order mark"));
+    }
+
+    #[test]
+    fn parse_many() {
+        let capture = "POST /a HTTP/1.1\r\nContent-Length: 11\r\n\r\nfirst\n\nbody\r\n\r\nGET /b HTTP/1.1\r\nHost: example.com\r\n\r\n\
+            PUT /c HTTP/1.1\nTransfer-Encoding: chunked\n\n3\nabc\n0\n\n";
+        let requests = Request::parse_many(capture).unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].get_uri(), "/a");
+        assert_eq!(requests[0].get_body(), "first\n\nbody");
+        assert_eq!(requests[1].get_method(), &HttpMethod::Get);
+        assert_eq!(requests[1].get_header("Host").unwrap(), "example.com");
+        assert_eq!(requests[1].get_body(), "");
+        assert_eq!(requests[2].get_body(), "abc");
+
+        let (req, consumed) = Request::parse_prefix("GET / HTTP/1.1\n\nGET /next HTTP/1.1\n\n").unwrap();
+        assert_eq!(req.get_uri(), "/");
+        assert_eq!(consumed, 16);
+        assert!(Request::parse_many("").unwrap().is_empty());
+        let err = Request::parse_many("POST / HTTP/1.1\nContent-Length: 10\n\nshort").unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Req);
+    }
+
+    #[test]
+    fn parse_with_remainder() {
+        let mut bytes = b"CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n".to_vec();
+        bytes.extend_from_slice(&[0x16, 0x03, 0x01, 0xff, 0x00]);
+        let (req, remainder) = Request::parse_with_remainder(&bytes).unwrap();
+        assert_eq!(req.get_method(), &HttpMethod::Connect);
+        assert_eq!(req.get_body(), "");
+        assert_eq!(remainder, &[0x16, 0x03, 0x01, 0xff, 0x00]);
+
+        let bytes = b"\r\nPOST / HTTP/1.1\nContent-Length: 3\n\nabcGET /next HTTP/1.1\n\n";
+        let (req, remainder) = Request::parse_with_remainder(bytes).unwrap();
+        assert_eq!(req.get_body(), "abc");
+        assert_eq!(remainder, b"GET /next HTTP/1.1\n\n");
+        let (_, remainder) = Request::parse_with_remainder(remainder).unwrap();
+        assert!(remainder.is_empty());
+    }
+
+    #[test]
+    fn append_forwarded() {
+        let mut req = Request::try_from("GET / HTTP/1.1\nforwarded: for=192.0.2.60, for=unknown;proto=http\n\n").unwrap();
+        req.append_forwarded(ForwardedElement::new().with_for("[2001:db8:cafe::17]:4711").with_by("unknown"));
+        assert_eq!(req.get_headers().len(), 1);
+        assert_eq!(
+            req.get_header("Forwarded").unwrap(),
+            "for=192.0.2.60, for=unknown;proto=http, for=\"[2001:db8:cafe::17]:4711\";by=unknown"
+        );
+        let elements = req.forwarded();
+        assert_eq!(elements.len(), 3);
+        assert_eq!(elements[2].for_ip(), Some(IpAddr::from_str("2001:db8:cafe::17").unwrap()));
+
+        let mut req = Request::default();
+        req.append_x_forwarded_for(IpAddr::from_str("203.0.113.7").unwrap())
+            .append_x_forwarded_for(IpAddr::from_str("2001:db8::1").unwrap());
+        assert_eq!(req.forwarded_for(), vec!["203.0.113.7", "2001:db8::1"]);
+    }
+
+    #[test]
+    fn textual() {
+        let req = Request::try_from("POST / HTTP/1.1\nContent-Type: application/vnd.api+json; charset=utf-8\n\n{}").unwrap();
+        assert!(req.is_textual());
+        assert_eq!(req.charset(), Some(String::from("utf-8")));
+        let req = Request::try_from("POST / HTTP/1.1\nContent-Type: text/plain\nContent-Encoding: br\n\n").unwrap();
+        assert!(!req.is_textual());
+        assert!(!Request::default().is_textual());
+    }
+
+    #[test]
+    fn valid_uri() {
+        assert!(Request::try_from("GET /a%20b?c=%2F HTTP/1.1\n\n").unwrap().has_valid_uri());
+        assert!(Request::try_from("GET /plain HTTP/1.1\n\n").unwrap().has_valid_uri());
+        assert!(!Request::try_from("GET /a%2 HTTP/1.1\n\n").unwrap().has_valid_uri());
+        assert!(!Request::try_from("GET /a%zz HTTP/1.1\n\n").unwrap().has_valid_uri());
+        assert!(!Request::try_from("GET /a% HTTP/1.1\n\n").unwrap().has_valid_uri());
+    }
 }
\ No newline at end of file
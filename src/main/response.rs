@@ -1,25 +1,69 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Debug, Display, Formatter};
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::net::TcpStream;
+use std::path::Path;
 use std::str::FromStr;
+use std::time::SystemTime;
 
 use wjp::{Deserialize, map, ParseError, Serialize, SerializeHelper, Values};
 
-use crate::error::{HttpParseError, ParseErrorKind::Req};
-use crate::status::HttpStatus;
+use crate::date::{format_http_date, parse_http_date, Expires};
+use crate::cache_control::{CacheControl, CACHE_CONTROL};
+use crate::content_disposition::{ContentDisposition, CONTENT_DISPOSITION, parse_content_disposition};
+use crate::diff::{diff_field, diff_headers, FieldDiff};
+use crate::error::{HttpParseError, MissingField, ParseErrorKind::{Req, Resp}};
+use crate::etag::ETag;
+use crate::journal::{HeaderChange, HeaderJournal};
+use crate::link::{Link, parse_links};
+use crate::media_type::MediaType;
+use crate::method::HttpMethod;
+use crate::options::ParseOptions;
+use crate::parse_warning::{head_warnings, measure_header_lines, ParseWarning, update_body_warning};
+use crate::problem::{Problem, PROBLEM_JSON};
+use crate::request::Request;
+use crate::retry_after::RetryAfter;
+use crate::security::SecurityHeaders;
+use crate::status::{check_code, HttpStatus, HttpStatusGroup};
 use crate::status::status_presets::ok;
-use crate::util::{Destruct, EMPTY_CHAR, error_option_empty, parse_body, parse_header, ParseKeyValue};
+use crate::status_error::StatusError;
+use crate::stream::{BodyFraming, INVALID_CONTENT_LENGTH, read_body, read_body_to_end, read_chunked_body, read_head};
+use crate::server::{Connection, TimedReader};
+use crate::util::{note_bom, strip_bom, CONTENT_LENGTH, CONTENT_TYPE, MESSAGE_HTTP, TRANSFER_ENCODING, is_meta_delimiter, content_type, UPGRADE, parse_token_list, decode_body, fnv1a, charset, is_textual, check_transfer_codings, field_state, missing_fields_error, BodyWriter, check_http1_framing, Destruct, error_option_empty, get_header_ignore_case, has_body, has_media_type, is_chunked, parse_body, parse_body_opt, parse_body_to, parse_content_length, parse_header, ParseKeyValue, read_body_file, check_raw_header_line, sync_content_length, replace_header_ignore_case, strip_hop_by_hop, to_wire_string, LineEnding};
+use crate::validation::{check_issues, into_result, response_issues, ValidationIssue};
 use crate::version::HttpVersion;
 
-const VALIDATE: &str = "min. 1 field was not filled with a value";
+const ETAG: &str = "ETag";
+const DATE: &str = "Date";
+const EXPIRES: &str = "Expires";
+const LAST_MODIFIED: &str = "Last-Modified";
+const AUTHORIZATION: &str = "Authorization";
+const RETRY_AFTER: &str = "Retry-After";
+const LINK: &str = "Link";
+const CHARSET_UTF8: &str = "; charset=utf-8";
+const SWITCHING_PROTOCOLS: u16 = 101;
+const NOT_INTERIM: &str = "An interim Response needs an informational status, but was";
 
 /// Struct for representing a HTTP Response
+///
+/// Only the constructors ([builder](Response::builder) and [ResponseBuilder::new]) are `const`.
+/// The accessors aren't, so the internal representation of the headers and the body can still change
+///
+/// Two Responses are equal if their version, status, headers and body are.
+/// What was noted while parsing or building (like repeated headers or warnings) isn't compared
+#[derive(Clone)]
 pub struct Response {
     version: HttpVersion,
     status: HttpStatus,
     headers: BTreeMap<String, String>,
     body: String,
+    duplicates: BTreeSet<String>,
+    warnings: Vec<ParseWarning>,
+    raw_header_lines: Vec<String>,
+    force_http1: bool,
+    skip_validation: bool,
+    close_delimited: bool,
+    journal: HeaderJournal,
 }
 
 impl Response {
@@ -29,30 +73,144 @@ impl Response {
         ResponseBuilder::new()
     }
     /// Get the [HttpVersion] of your Response
-    pub const fn get_version(&self) -> &HttpVersion {
+    pub fn get_version(&self) -> &HttpVersion {
         &self.version
     }
     /// Get the Headers of your Response
-    pub const fn get_headers(&self) -> &BTreeMap<String, String> {
+    pub fn get_headers(&self) -> &BTreeMap<String, String> {
         &self.headers
     }
     /// Get the [HttpStatus] of your Response
-    pub const fn get_status(&self) -> &HttpStatus {
+    pub fn get_status(&self) -> &HttpStatus {
         &self.status
     }
     /// Get the body of your Response
-    pub const fn get_body(&self) -> &String {
+    pub fn get_body(&self) -> &String {
         &self.body
     }
+    /// Looks if the Response has a body that isn't empty or only whitespace
+    pub fn has_body(&self) -> bool {
+        has_body(&self.body)
+    }
+    /// Looks if the body is uncompressed text that can be transformed. <br>
+    /// The Content-Type has to be text/*, application/json, application/*+json, application/xml,
+    /// application/*+xml or application/x-www-form-urlencoded and a Content-Encoding mustn't be present
+    pub fn is_textual(&self) -> bool {
+        is_textual(&self.headers)
+    }
+    /// Get the parsed Content-Type header ([None] if it is absent or malformed). <br>
+    /// This is the media type the body parsers (like forms and multipart) consult
+    pub fn get_content_type(&self) -> Option<MediaType> {
+        content_type(&self.headers)
+    }
+    /// Set the Content-Type header to the media type (like `application/json`),
+    /// a Content-Type header in any casing is replaced
+    pub fn set_content_type(&mut self, mime: &str) -> &mut Response {
+        self.replace_header(CONTENT_TYPE, Some(String::from(mime)))
+    }
+    /// Set the Content-Type header to the media type with `; charset=utf-8` appended
+    pub fn set_content_type_utf8(&mut self, mime: &str) -> &mut Response {
+        self.replace_header(CONTENT_TYPE, Some(format!("{}{}", mime, CHARSET_UTF8)))
+    }
+    /// Get the charset parameter of the Content-Type header
+    pub fn charset(&self) -> Option<String> {
+        charset(&self.headers)
+    }
+    /// Get the protocols of the Upgrade header in order of preference
+    /// (for example the ones required by a Response with Status Upgrade Required)
+    /// or [None] if the header is absent
+    pub fn required_upgrades(&self) -> Option<Vec<String>> {
+        self.get_header_ignore_case(UPGRADE).map(|value| parse_token_list(value))
+    }
+    /// Compares the bodies of both Responses after decoding them according to their Content-Encoding. <br>
+    /// Returns an [Unsupported](crate::ParseErrorKind::Unsupported) error for a coding that can't be decoded
+    /// (there are no built-in decoders, so only identity is supported)
+    pub fn body_equivalent(&self, other: &Response) -> Result<bool, HttpParseError> {
+        Ok(decode_body(&self.headers, &self.body)? == decode_body(&other.headers, &other.body)?)
+    }
+    /// Get a stable 64-bit [FNV-1a](https://datatracker.ietf.org/doc/html/draft-eastlake-fnv) hash
+    /// of the decoded body (for example for cache validators). <br>
+    /// Returns an [Unsupported](crate::ParseErrorKind::Unsupported) error for a coding that can't be decoded
+    /// (like [body_equivalent](Response::body_equivalent))
+    pub fn body_digest(&self) -> Result<u64, HttpParseError> {
+        decode_body(&self.headers, &self.body).map(fnv1a)
+    }
+    /// Looks if the body is empty (a body of only whitespace isn't empty, see [has_body](Response::has_body))
+    pub fn is_empty_body(&self) -> bool {
+        self.body.is_empty()
+    }
+    /// Get the body parsed to the Parameter T. <br>
+    /// Returns a [Body](crate::ParseErrorKind::Body) error if the body is empty or malformed
+    pub fn get_parsed_body<T: Deserialize>(&self) -> Result<T, HttpParseError> {
+        parse_body_to(&self.body)
+    }
     /// Get the body parsed to the Parameter T
-    pub fn get_parsed_body<T: Deserialize>(&self) -> Result<T, ParseError> {
-        T::deserialize_str(self.body.as_str())
+    /// or [None] if the body is empty or only whitespace
+    pub fn get_parsed_body_opt<T: Deserialize>(&self) -> Result<Option<T>, HttpParseError> {
+        parse_body_opt(&self.body)
+    }
+    /// Get the body parsed to a [Problem] if the Content-Type is `application/problem+json`
+    /// or [None] for every other Content-Type
+    pub fn get_problem(&self) -> Option<Result<Problem, HttpParseError>> {
+        has_media_type(&self.headers, PROBLEM_JSON).then(|| parse_body_to(&self.body))
+    }
+    /// Returns the Response if its status is [Successful](HttpStatusGroup::Successful)
+    /// or otherwise a [StatusError] with the status, the body and some of the headers
+    pub fn success(self) -> Result<Response, StatusError> {
+        self.success_with(false)
+    }
+    /// Returns the Response like [success](Response::success),
+    /// but a [Redirection](HttpStatusGroup::Redirection) counts as success if allowed
+    /// (for a client that doesn't follow redirects itself)
+    pub fn success_with(self, allow_redirection: bool) -> Result<Response, StatusError> {
+        if self.is_success(allow_redirection) {
+            return Ok(self);
+        }
+        Err(StatusError::new(self.status, &self.headers, self.body))
+    }
+    /// Checks the status like [success](Response::success) without consuming the Response
+    pub fn error_for_status(&self) -> Result<(), StatusError> {
+        self.error_for_status_with(false)
+    }
+    /// Checks the status like [success_with](Response::success_with) without consuming the Response
+    pub fn error_for_status_with(&self, allow_redirection: bool) -> Result<(), StatusError> {
+        if self.is_success(allow_redirection) {
+            return Ok(());
+        }
+        Err(StatusError::new(self.status.clone(), &self.headers, self.body.clone()))
+    }
+    fn is_success(&self, allow_redirection: bool) -> bool {
+        match self.status.get_group() {
+            HttpStatusGroup::Successful => true,
+            HttpStatusGroup::Redirection => allow_redirection,
+            _ => false,
+        }
+    }
+    /// Get the [Request] embedded in the body if the Content-Type is `message/http`
+    /// (like the echo of a TRACE Request) or [None] for every other Content-Type. <br>
+    /// The body of the embedded Request is framed by its own Content-Length
+    /// (or chunked Transfer-Encoding), anything behind it is ignored.
+    /// An embedded body that is declared longer than the outer body is a [Length](crate::ParseErrorKind::Length) error
+    pub fn get_embedded_request(&self) -> Option<Result<Request, HttpParseError>> {
+        has_media_type(&self.headers, MESSAGE_HTTP).then(|| {
+            Request::parse_with_remainder_within(self.body.as_bytes(), Some(self.body.len())).map(|(req, _)| req)
+        })
     }
     /// Set the body to a specific String
+    /// (a Content-Length that matched the previous body, like the one of a preset, is updated to the new length)
     pub fn set_body(&mut self, body: &str) -> &mut Response {
+        sync_content_length(&mut self.headers, &mut self.journal, self.body.len(), body.len());
         self.body = String::from(body);
         self
     }
+    /// Empties the body and removes the Content-Length and Transfer-Encoding headers (case-insensitive),
+    /// for example for a Response with Status Not Modified or to a HEAD Request
+    pub fn clear_body(&mut self) -> &mut Response {
+        self.body.clear();
+        self.retain_headers(|key, _| {
+            !key.eq_ignore_ascii_case(CONTENT_LENGTH) && !key.eq_ignore_ascii_case(TRANSFER_ENCODING)
+        })
+    }
     /// Set the version to as specific [HttpVersion]
     pub fn set_version(&mut self, version: HttpVersion) -> &mut Response {
         self.version = version;
@@ -63,14 +221,145 @@ impl Response {
         self.status = status;
         self
     }
+    /// Set the code of the status and refresh the reason phrase to the canonical one
+    /// (`Custom HttpStatus` for codes that aren't registered). <br>
+    /// Returns a [Status](crate::ParseErrorKind::Status) error if the code doesn't have three digits
+    pub fn set_status_code(&mut self, code: u16) -> Result<&mut Response, HttpParseError> {
+        self.status = HttpStatus::try_from(usize::from(check_code(code)?))?;
+        Ok(self)
+    }
+    /// Set the reason phrase of the status and keep its code. <br>
+    /// Returns a [Status](crate::ParseErrorKind::Status) error if the reason phrase contains CR or LF
+    pub fn set_reason_phrase(&mut self, msg: &str) -> Result<&mut Response, HttpParseError> {
+        self.status = self.status.clone().with_message(msg)?;
+        Ok(self)
+    }
     /// Add a single header to your Response
     pub fn add_header(&mut self, kv: (String, String)) -> &mut Response {
-        self.headers.insert(kv.0, kv.1);
+        self.insert_header(kv.0, kv.1, None);
+        self
+    }
+    /// Add a single header to your Response and record the tag in the header journal
+    /// (see [enable_header_journal](Response::enable_header_journal))
+    pub fn add_header_tagged(&mut self, kv: (String, String), tag: &str) -> &mut Response {
+        self.insert_header(kv.0, kv.1, Some(tag));
         self
     }
+    /// Adds a header line that is written verbatim (with its casing and whitespace) after the normal headers,
+    /// for example to experiment with structured fields. <br>
+    /// **This bypasses the header canonicalization, the validation and the header-injection protections:**
+    /// only a name in front of exactly one colon is required and CR, LF and NUL are rejected, anything else in the line is sent as it is.
+    /// The line isn't part of [get_headers](Response::get_headers) and parsing never fills it
+    pub fn add_raw_header_line(&mut self, line: &str) -> Result<(), HttpParseError> {
+        self.raw_header_lines.push(check_raw_header_line(line)?);
+        Ok(())
+    }
+    /// Get the header lines added with [add_raw_header_line](Response::add_raw_header_line)
+    pub fn get_raw_header_lines(&self) -> &[String] {
+        &self.raw_header_lines
+    }
     /// Remove a specific Header from the Response (idempotent)
     pub fn remove_header(&mut self, key: &str) -> &mut Response {
-        self.headers.remove(key);
+        self.delete_header(key, None);
+        self
+    }
+    /// Remove a specific Header from the Response and record the tag in the header journal
+    /// (see [enable_header_journal](Response::enable_header_journal))
+    pub fn remove_header_tagged(&mut self, key: &str, tag: &str) -> &mut Response {
+        self.delete_header(key, Some(tag));
+        self
+    }
+    /// Retains only the headers for which the predicate returns true
+    pub fn retain_headers<F: FnMut(&str, &str) -> bool>(&mut self, mut f: F) -> &mut Response {
+        if !self.journal.is_enabled() {
+            self.headers.retain(|key, value| f(key, value));
+            return self;
+        }
+        let mut removed = Vec::new();
+        self.headers.retain(|key, value| {
+            let retain = f(key, value);
+            if !retain {
+                removed.push((key.clone(), value.clone()));
+            }
+            retain
+        });
+        for (key, value) in removed {
+            self.journal.record(&key, Some(value), None, None);
+        }
+        self
+    }
+    /// Removes all hop-by-hop headers (the standard ones, the ones named in the Connection header
+    /// and the Connection header itself) and returns the removed headers
+    pub fn strip_hop_by_hop(&mut self) -> Vec<(String, String)> {
+        let removed = strip_hop_by_hop(&mut self.headers);
+        if self.journal.is_enabled() {
+            for (key, value) in &removed {
+                self.journal.record(key, Some(value.clone()), None, None);
+            }
+        }
+        removed
+    }
+    /// Starts recording every header mutation (with the name, the old and the new value and the tag)
+    /// for debugging which middleware changed a header. <br>
+    /// The journal is disabled by default and ignored by the equality and the serialization.
+    /// Changes through [get_headers_mut](Response::get_headers_mut) aren't recorded
+    pub fn enable_header_journal(&mut self) -> &mut Response {
+        self.journal.enable();
+        self
+    }
+    /// Get the recorded header mutations in order (empty if the journal isn't enabled)
+    pub fn header_journal(&self) -> &[HeaderChange] {
+        self.journal.entries()
+    }
+    fn insert_header(&mut self, key: String, value: String, tag: Option<&str>) {
+        if !self.journal.is_enabled() {
+            self.headers.insert(key, value);
+            return;
+        }
+        let old = self.headers.insert(key.clone(), value.clone());
+        self.journal.record(&key, old, Some(value), tag);
+    }
+    fn delete_header(&mut self, key: &str, tag: Option<&str>) {
+        let old = self.headers.remove(key);
+        self.journal.record(key, old, None, tag);
+    }
+    /// removes every header with the name (case-insensitive) and adds the value under the canonical name
+    fn replace_header(&mut self, name: &str, value: Option<String>) -> &mut Response {
+        let existing: Vec<String> = self.headers.keys()
+            .filter(|key| key.eq_ignore_ascii_case(name))
+            .cloned()
+            .collect();
+        for key in existing {
+            self.delete_header(&key, None);
+        }
+        if let Some(value) = value {
+            self.insert_header(String::from(name), value, None);
+        }
+        self
+    }
+    /// Returns the Response with the [HttpStatus] replaced
+    pub fn with_status(mut self, status: HttpStatus) -> Self {
+        self.status = status;
+        self
+    }
+    /// Returns the Response with the [HttpVersion] replaced
+    pub fn with_version(mut self, version: HttpVersion) -> Self {
+        self.version = version;
+        self
+    }
+    /// Returns the Response with the body replaced (the Content-Length is kept in sync like in [set_body](Response::set_body))
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.set_body(body);
+        self
+    }
+    /// Returns the Response with a single header added (or replaced)
+    pub fn with_added_header(mut self, key: &str, value: &str) -> Self {
+        self.insert_header(String::from(key), String::from(value), None);
+        self
+    }
+    /// Returns the Response without the specific header
+    pub fn with_removed_header(mut self, key: &str) -> Self {
+        self.delete_header(key, None);
         self
     }
     /// If the specified Header doesn't exist inserts it else does nothing 
@@ -80,9 +369,127 @@ impl Response {
         }
         self.add_header(kv)
     }
-    /// Get the header value to a specific key
+    /// Get the header value to a specific key (the key is matched case-insensitive like [Request::get_header])
     pub fn get_header(&self, key: &str) -> Option<&String> {
-        self.headers.get(key)
+        get_header_ignore_case(&self.headers, key)
+    }
+    /// Get the header value to a specific key (the key is matched case-insensitive, the same as [get_header](Response::get_header))
+    pub fn get_header_ignore_case(&self, key: &str) -> Option<&String> {
+        get_header_ignore_case(&self.headers, key)
+    }
+    /// Get the ETag header parsed to an [ETag]
+    pub fn get_etag(&self) -> Option<ETag> {
+        get_header_ignore_case(&self.headers, ETAG).and_then(|etag| ETag::from_str(etag).ok())
+    }
+    /// Set the ETag header to the given [ETag]
+    pub fn set_etag(&mut self, etag: ETag) -> &mut Response {
+        self.add_header((String::from(ETAG), etag.to_string()))
+    }
+    /// Get the Date header parsed to a [SystemTime]
+    pub fn get_date(&self) -> Option<SystemTime> {
+        get_header_ignore_case(&self.headers, DATE).and_then(|date| parse_http_date(date))
+    }
+    /// Set the Date header to the given [SystemTime]
+    pub fn set_date(&mut self, time: SystemTime) -> &mut Response {
+        self.add_header((String::from(DATE), format_http_date(time)))
+    }
+    /// Get the Expires header parsed to [Expires]
+    /// (an invalid date like `0` is [Expired](Expires::Expired) instead of [None])
+    pub fn get_expires(&self) -> Option<Expires> {
+        get_header_ignore_case(&self.headers, EXPIRES).map(|date| Expires::parse(date))
+    }
+    /// Set the Expires header to the given [SystemTime]
+    pub fn set_expires(&mut self, time: SystemTime) -> &mut Response {
+        self.add_header((String::from(EXPIRES), format_http_date(time)))
+    }
+    /// Get the Last-Modified header parsed to a [SystemTime]
+    pub fn get_last_modified(&self) -> Option<SystemTime> {
+        get_header_ignore_case(&self.headers, LAST_MODIFIED).and_then(|date| parse_http_date(date))
+    }
+    /// Set the Last-Modified header to the given [SystemTime]
+    pub fn set_last_modified(&mut self, time: SystemTime) -> &mut Response {
+        self.add_header((String::from(LAST_MODIFIED), format_http_date(time)))
+    }
+    /// Get the Retry-After header parsed to a [RetryAfter]
+    pub fn get_retry_after(&self) -> Option<RetryAfter> {
+        get_header_ignore_case(&self.headers, RETRY_AFTER).and_then(|value| RetryAfter::from_str(value).ok())
+    }
+    /// Set the Retry-After header to a number of seconds
+    pub fn set_retry_after_secs(&mut self, seconds: u64) -> &mut Response {
+        self.add_header((String::from(RETRY_AFTER), RetryAfter::Seconds(seconds).to_string()))
+    }
+    /// Set the Retry-After header to the given [SystemTime]
+    pub fn set_retry_after_date(&mut self, time: SystemTime) -> &mut Response {
+        self.add_header((String::from(RETRY_AFTER), RetryAfter::Date(time).to_string()))
+    }
+    /// Get the directives of the Cache-Control header (empty if it is absent)
+    pub fn cache_control(&self) -> CacheControl {
+        get_header_ignore_case(&self.headers, CACHE_CONTROL).map_or(CacheControl::default(), |value| CacheControl::parse(value))
+    }
+    /// Looks if a shared cache may store this Response to the Request
+    /// ([RFC 7234](https://www.rfc-editor.org/rfc/rfc7234#section-3)):
+    /// - the method of the Request is [GET](HttpMethod::Get) or [HEAD](HttpMethod::Head)
+    /// - the status is [cacheable by default](HttpStatus::is_cacheable_by_default)
+    ///   or the Response has explicit freshness (`max-age`, `s-maxage`, an Expires header or `public`)
+    /// - neither the Request nor the Response has the `no-store` directive and the Response isn't `private`
+    /// - a Request with an Authorization header is only cacheable if the Response is `public`,
+    ///   `must-revalidate` or has an `s-maxage`
+    pub fn is_cacheable(&self, req: &Request) -> bool {
+        let cache_control = self.cache_control();
+        let authorized = req.get_header(AUTHORIZATION).is_none()
+            || cache_control.is_public()
+            || cache_control.is_must_revalidate()
+            || cache_control.get_s_maxage().is_some();
+        let explicit = cache_control.get_max_age().is_some()
+            || cache_control.get_s_maxage().is_some()
+            || cache_control.is_public()
+            || get_header_ignore_case(&self.headers, EXPIRES).is_some();
+        matches!(req.get_method(), HttpMethod::Get | HttpMethod::Head)
+            && (self.status.is_cacheable_by_default() || explicit)
+            && !req.cache_control().is_no_store()
+            && !cache_control.is_no_store()
+            && !cache_control.is_private()
+            && authorized
+    }
+    /// Set the Cache-Control header to the directives (an empty [CacheControl] removes the header),
+    /// a Cache-Control header in any casing is replaced
+    pub fn set_cache_control(&mut self, cc: CacheControl) -> &mut Response {
+        let value = (!cc.is_empty()).then(|| cc.to_string());
+        self.replace_header(CACHE_CONTROL, value)
+    }
+    /// Get the Content-Disposition header parsed to a [ContentDisposition]
+    pub fn get_content_disposition(&self) -> Option<ContentDisposition> {
+        get_header_ignore_case(&self.headers, CONTENT_DISPOSITION).and_then(|value| parse_content_disposition(value))
+    }
+    /// Set the Content-Disposition header (for example to name a download)
+    pub fn set_content_disposition(&mut self, disposition: &ContentDisposition) -> &mut Response {
+        self.add_header((String::from(CONTENT_DISPOSITION), disposition.to_string()))
+    }
+    /// Get the links of the Link header (empty if it is absent)
+    pub fn get_links(&self) -> Vec<Link> {
+        get_header_ignore_case(&self.headers, LINK).map(|value| parse_links(value)).unwrap_or_default()
+    }
+    /// Get the first link with the relation type (case-insensitive)
+    pub fn find_link(&self, rel: &str) -> Option<Link> {
+        self.get_links().into_iter().find(|link| link.has_rel(rel))
+    }
+    /// Append the link to the Link header (or add the header if it is absent)
+    pub fn add_link(&mut self, link: Link) -> &mut Response {
+        let existing = self.headers.iter().find(|(key, _)| key.eq_ignore_ascii_case(LINK));
+        let (key, value) = match existing {
+            Some((key, value)) => (key.clone(), format!("{}, {}", value, link)),
+            None => (String::from(LINK), link.to_string()),
+        };
+        self.insert_header(key, value, None);
+        self
+    }
+    /// Set the security headers of the [SecurityHeaders] that aren't [None]
+    /// (existing headers with the same name are replaced)
+    pub fn set_security_headers(&mut self, config: SecurityHeaders) -> &mut Response {
+        for header in config.headers() {
+            self.add_header(header);
+        }
+        self
     }
     /// Get the Headers as a mutable reference to manipulate it yourself
     pub fn get_headers_mut(&mut self) -> &mut BTreeMap<String, String> {
@@ -90,16 +497,237 @@ impl Response {
     }
     /// Append the body by a String
     pub fn append_body(&mut self, str: &str) -> &mut Response {
+        sync_content_length(&mut self.headers, &mut self.journal, self.body.len(), self.body.len() + str.len());
         self.body.push_str(str);
         self
     }
-    fn parse_meta_line(str: Option<&str>) -> Result<(HttpVersion, HttpStatus), HttpParseError> {
+    /// Reads everything from the reader and appends it to the body
+    /// (for buffering upstream content before forwarding it). <br>
+    /// Returns the number of appended bytes.
+    /// Fails with [InvalidData](std::io::ErrorKind::InvalidData) if the bytes aren't valid UTF-8,
+    /// in that case the body stays unchanged
+    pub fn append_body_from<R: Read>(&mut self, r: &mut R) -> std::io::Result<usize> {
+        let mut bytes = Vec::new();
+        r.read_to_end(&mut bytes)?;
+        let str = String::from_utf8(bytes).map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        self.append_body(&str);
+        Ok(str.len())
+    }
+    /// Get a [Write] adapter that appends directly to the body. <br>
+    /// Every written chunk has to be valid UTF-8
+    pub fn body_writer(&mut self) -> impl Write + '_ {
+        BodyWriter::with_headers(&mut self.body, &mut self.headers, &mut self.journal)
+    }
+    /// Reads the Response from the stream with the given [ParseOptions]. <br>
+    /// The body is framed by the Content-Length header, a chunked Transfer-Encoding
+    /// or otherwise read until the connection is closed (see [is_close_delimited](Response::is_close_delimited)).
+    /// Responses with a status that forbids a body (1xx, 204 and 304) never have a body. <br>
+    /// The [header timeout](ParseOptions::with_header_timeout) and the [idle timeout](ParseOptions::with_idle_timeout)
+    /// are enforced like in [serve_connection](crate::serve_connection), hitting one is a [Timeout](crate::ParseErrorKind::Timeout) error
+    pub fn from_stream<R: Connection>(stream: &mut R, options: &ParseOptions) -> Result<Response, HttpParseError> {
+        Self::read_timed(&mut BufReader::new(TimedReader::new(stream, options)), options)
+    }
+    /// Reads the final Response from the stream like [from_stream](Response::from_stream)
+    /// and returns it with the interim (1xx) Responses that came before it in order,
+    /// for example to act on the Link headers of Early Hints. <br>
+    /// Switching Protocols is a final Response. The header timeout applies to every head
+    pub fn from_stream_with_interim<R: Connection>(
+        stream: &mut R,
+        options: &ParseOptions,
+    ) -> Result<(Response, Vec<Response>), HttpParseError> {
+        let mut reader = BufReader::new(TimedReader::new(stream, options));
+        let mut interim = Vec::new();
+        loop {
+            let resp = Self::read_timed(&mut reader, options)?;
+            if !resp.is_interim() {
+                return Ok((resp, interim));
+            }
+            interim.push(resp);
+        }
+    }
+    fn is_interim(&self) -> bool {
+        self.status.has_group(&HttpStatusGroup::Informational) && self.status.get_code() != &SWITCHING_PROTOCOLS
+    }
+    fn read_timed<R: Connection>(
+        reader: &mut BufReader<TimedReader<R>>,
+        options: &ParseOptions,
+    ) -> Result<Response, HttpParseError> {
+        reader.get_mut().start_head();
+        let resp = Self::parse_with(&read_head(reader, options.get_max_head_size(), Resp)?, options)?;
+        reader.get_mut().head_read();
+        resp.read_body_from(reader, options)
+    }
+    /// reads the Response (without any timeouts) from the reader, like an embedded Response from a body
+    pub(crate) fn read_from<R: BufRead>(reader: &mut R, options: &ParseOptions) -> Result<Response, HttpParseError> {
+        Self::parse_with(&read_head(reader, options.get_max_head_size(), Resp)?, options)?.read_body_from(reader, options)
+    }
+    fn read_body_from<R: BufRead>(self, reader: &mut R, options: &ParseOptions) -> Result<Response, HttpParseError> {
+        let mut resp = self;
+        let limit = options.get_max_body_size();
+        if resp.body_allowed() {
+            check_transfer_codings(&resp.headers)?;
+        }
+        resp.body = match resp.body_framing() {
+            BodyFraming::None => String::new(),
+            BodyFraming::ContentLength(length) => {
+                let length = usize::try_from(length).map_err(|err| HttpParseError::length(err.to_string()))?;
+                read_body(reader, length, limit, Resp)?
+            }
+            BodyFraming::Chunked => read_chunked_body(reader, limit, Resp)?,
+            BodyFraming::Invalid => return Err(HttpParseError::length(INVALID_CONTENT_LENGTH)),
+            BodyFraming::CloseDelimited => {
+                resp.close_delimited = true;
+                read_body_to_end(reader, limit, Resp)?
+            }
+        };
+        resp.check_body_soft_limit(options);
+        Ok(resp)
+    }
+    /// Decides how the body is delimited on the wire from the status and the headers
+    /// ([RFC 7230](https://www.rfc-editor.org/rfc/rfc7230#section-3.3.3)):
+    /// 1. a status that doesn't [allow a body](Response::body_allowed) has [None](BodyFraming::None)
+    /// 2. a Transfer-Encoding wins over the Content-Length: [Chunked](BodyFraming::Chunked) if chunked is the last coding,
+    ///    otherwise [CloseDelimited](BodyFraming::CloseDelimited)
+    /// 3. a valid Content-Length is the [ContentLength](BodyFraming::ContentLength),
+    ///    an invalid one is [Invalid](BodyFraming::Invalid), which has to be treated as an error
+    /// 4. without both headers the body is [CloseDelimited](BodyFraming::CloseDelimited)
+    ///
+    /// The Response doesn't know the Request, so the answer to a HEAD Request has to be treated
+    /// as [None](BodyFraming::None) by the caller
+    pub fn body_framing(&self) -> BodyFraming {
+        if !self.body_allowed() {
+            return BodyFraming::None;
+        }
+        if get_header_ignore_case(&self.headers, TRANSFER_ENCODING).is_some() {
+            return if is_chunked(&self.headers) { BodyFraming::Chunked } else { BodyFraming::CloseDelimited };
+        }
+        match parse_content_length(&self.headers) {
+            Ok(Some(length)) => BodyFraming::ContentLength(length as u64),
+            Ok(None) => BodyFraming::CloseDelimited,
+            Err(_) => BodyFraming::Invalid,
+        }
+    }
+    /// Looks if the body was delimited by closing the connection,
+    /// which means the connection can't be used anymore
+    pub fn is_close_delimited(&self) -> bool {
+        self.close_delimited
+    }
+    /// Looks if the status allows a body, which 1xx, 204 (No Content) and 304 (Not Modified)
+    /// Responses never have regardless of their headers
+    /// ([RFC 7230](https://www.rfc-editor.org/rfc/rfc7230#section-3.3))
+    pub fn body_allowed(&self) -> bool {
+        self.status.allows_body()
+    }
+    /// Parses the Response with the given [ParseOptions]
+    pub fn parse_with(s: &str, options: &ParseOptions) -> Result<Self, HttpParseError> {
+        let mut value = strip_bom(s).lines();
+        let line = value.next();
+        let (version, status) = Self::parse_meta_line(line, options.is_strict()).map_err(|err| note_bom(err, line))?;
+        let header_lines = measure_header_lines(options.get_soft_limits(), value.clone());
+        let (headers, duplicates) = parse_header(&mut value, options)?;
+        let warnings = head_warnings(options.get_soft_limits(), header_lines, None);
+        let body = parse_body(&mut value);
+        let mut resp = Self {
+            version,
+            status,
+            headers,
+            body,
+            duplicates,
+            warnings,
+            raw_header_lines: Vec::new(),
+            force_http1: false,
+            skip_validation: false,
+            close_delimited: false,
+            journal: HeaderJournal::default(),
+        };
+        resp.check_body_soft_limit(options);
+        Ok(resp)
+    }
+    /// Get the warnings for the [soft limits](crate::SoftLimits) of the [ParseOptions]
+    /// the Response reached while parsing (always empty without soft limits)
+    pub fn get_warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+    pub(crate) fn check_body_soft_limit(&mut self, options: &ParseOptions) {
+        update_body_warning(&mut self.warnings, options.get_soft_limits(), &self.body);
+    }
+    /// Looks if the header was repeated while parsing (case-insensitive). <br>
+    /// How the values were combined depends on the [DuplicatePolicy](crate::DuplicatePolicy)
+    pub fn had_duplicate(&self, key: &str) -> bool {
+        self.duplicates.contains(&key.to_ascii_lowercase())
+    }
+    /// Writes the Response in the HTTP/1.x wire format to the [Write]. <br>
+    /// Returns a [Version](crate::ParseErrorKind::Version) error for HTTP/2 and HTTP/3
+    /// unless [force_http1_framing](Response::force_http1_framing) was set.
+    /// The Response is checked with [validate_for_send](Response::validate_for_send) first:
+    /// an [Error](crate::Severity::Error) aborts with a [Resp](crate::ParseErrorKind::Resp) error, warnings are ignored
+    pub fn write_to<W: Write>(&self, w: &mut W) -> Result<(), HttpParseError> {
+        self.write_to_with(w, LineEnding::Crlf)
+    }
+    /// Checks the Response for common mistakes before it is sent
+    /// (like a body on a 204 or a Content-Length that doesn't match the body)
+    /// and returns every [ValidationIssue] found
+    pub fn validate_for_send(&self) -> Result<(), Vec<ValidationIssue>> {
+        into_result(response_issues(&self.status, &self.version, &self.headers, &self.body))
+    }
+    /// Disables the [validate_for_send](Response::validate_for_send) check of [write_to](Response::write_to)
+    /// (for example to send a deliberately malformed Response in a test)
+    pub fn skip_send_validation(&mut self) -> &mut Response {
+        self.skip_validation = true;
+        self
+    }
+    /// Writes the Response like [write_to](Response::write_to) but with the given [LineEnding]
+    pub fn write_to_with<W: Write>(&self, w: &mut W, line_ending: LineEnding) -> Result<(), HttpParseError> {
+        if !self.skip_validation {
+            check_issues(response_issues(&self.status, &self.version, &self.headers, &self.body), Resp)?;
+        }
+        w.write_all(&self.to_bytes_with(line_ending)?)
+            .map_err(|err| HttpParseError::response(err.to_string()))
+    }
+    /// Writes an interim (1xx) Response with its headers but without a body to the [Write],
+    /// so the final Response can follow on the same connection. <br>
+    /// Returns a [Resp](crate::ParseErrorKind::Resp) error if the status isn't informational
+    pub fn write_interim<W: Write>(&self, w: &mut W) -> Result<(), HttpParseError> {
+        if !self.status.has_group(&HttpStatusGroup::Informational) {
+            return Err(HttpParseError::response(format!("{} {}", NOT_INTERIM, self.status)));
+        }
+        check_http1_framing(&self.version, self.force_http1)?;
+        w.write_all(to_wire_string(&self.start_line(), &self.headers, &self.raw_header_lines, "", true, LineEnding::Crlf).as_bytes())
+            .map_err(|err| HttpParseError::response(err.to_string()))
+    }
+    /// Converts the Response into the bytes of the HTTP/1.x wire format. <br>
+    /// Returns a [Version](crate::ParseErrorKind::Version) error for HTTP/2 and HTTP/3
+    /// unless [force_http1_framing](Response::force_http1_framing) was set
+    pub fn to_bytes(&self) -> Result<Vec<u8>, HttpParseError> {
+        self.to_bytes_with(LineEnding::Crlf)
+    }
+    /// Converts the Response like [to_bytes](Response::to_bytes) but with the given [LineEnding]
+    /// (the body is kept as it is)
+    pub fn to_bytes_with(&self, line_ending: LineEnding) -> Result<Vec<u8>, HttpParseError> {
+        check_http1_framing(&self.version, self.force_http1)?;
+        Ok(to_wire_string(&self.start_line(), &self.headers, &self.raw_header_lines, &self.body, true, line_ending).into_bytes())
+    }
+    /// Allows writing HTTP/2 and HTTP/3 Responses in the HTTP/1.x wire format (for example for logging)
+    pub fn force_http1_framing(&mut self) -> &mut Response {
+        self.force_http1 = true;
+        self
+    }
+    /// Converts the Response into its raw CRLF wire format. <br>
+    /// Sensitive headers (like Set-Cookie) are redacted unless `include_secrets` is true
+    pub fn to_raw_string(&self, include_secrets: bool) -> String {
+        to_wire_string(&self.start_line(), &self.headers, &self.raw_header_lines, &self.body, include_secrets, LineEnding::Crlf)
+    }
+    fn start_line(&self) -> String {
+        format!("{} {}", self.version, self.status)
+    }
+    fn parse_meta_line(str: Option<&str>, strict: bool) -> Result<(HttpVersion, HttpStatus), HttpParseError> {
+        // the reason phrase may contain spaces or be missing
         let mut split = str.ok_or(error_option_empty(Req))?
-            .split(EMPTY_CHAR);
+            .splitn(3, |char| is_meta_delimiter(char, strict));
         let version = HttpVersion::try_from(split.next())?;
         let status = HttpStatus::try_from((
             split.next().ok_or(error_option_empty(Req))?,
-            split.next().ok_or(error_option_empty(Req))?,
+            split.next().unwrap_or(""),
         ))?;
         Ok((version, status))
     }
@@ -118,6 +746,14 @@ impl Display for Response {
     }
 }
 
+impl PartialEq for Response {
+    fn eq(&self, other: &Self) -> bool {
+        (&self.version, &self.status, &self.headers, &self.body) == (&other.version, &other.status, &other.headers, &other.body)
+    }
+}
+
+impl Eq for Response {}
+
 impl Debug for Response {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         Display::fmt(self, f)
@@ -134,29 +770,14 @@ impl TryFrom<String> for Response {
 impl FromStr for Response {
     type Err = HttpParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut value = s.lines();
-        let (version, status) = Self::parse_meta_line(value.next())?;
-        let headers = parse_header(&mut value)?;
-        let body = parse_body(&mut value);
-        Ok(Self {
-            version,
-            status,
-            headers,
-            body,
-        })
+        Self::parse_with(s, &ParseOptions::default())
     }
 }
 
 impl TryFrom<&mut TcpStream> for Response {
     type Error = HttpParseError;
     fn try_from(value: &mut TcpStream) -> Result<Self, Self::Error> {
-        let mut reader = BufReader::new(value);
-        let received: Vec<u8> = reader
-            .fill_buf()
-            .map_err(|err| HttpParseError::from((Req, err.to_string())))?
-            .to_vec();
-        reader.consume(received.len());
-        Self::try_from(received)
+        Self::from_stream(value, &ParseOptions::default())
     }
 }
 
@@ -164,7 +785,7 @@ impl TryFrom<Vec<u8>> for Response {
     type Error = HttpParseError;
     fn try_from(value: Vec<u8>) -> Result<Self, Self::Error> {
         let string = String::from_utf8(value)
-            .map_err(|err| HttpParseError::from((Req, err.to_string())))?;
+            .map_err(|err| HttpParseError::request(err.to_string()))?;
         Self::try_from(string)
     }
 }
@@ -177,6 +798,13 @@ impl Default for Response {
             status: ok(),
             version: HttpVersion::OnePointOne,
             body: String::from("Hello, World"),
+            duplicates: BTreeSet::new(),
+            warnings: Vec::new(),
+            raw_header_lines: Vec::new(),
+            force_http1: false,
+            skip_validation: false,
+            close_delimited: false,
+            journal: HeaderJournal::default(),
         }
     }
 }
@@ -189,22 +817,58 @@ impl Destruct for Response {
 }
 
 /// Builder impl for [Response]
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct ResponseBuilder {
     version: Option<HttpVersion>,
     status: Option<HttpStatus>,
     headers: Option<BTreeMap<String, String>>,
     body: Option<String>,
+    error: Option<HttpParseError>,
 }
 
 impl ResponseBuilder {
     /// validates if all the items are present
-    pub const fn validate(&self) -> bool {
+    pub fn validate(&self) -> bool {
         self.body.is_some()
             && self.status.is_some()
             && self.headers.is_some()
             && self.version.is_some()
     }
+    /// validates if all the items are present
+    /// and otherwise returns every [MissingField]
+    pub fn validate_detailed(&self) -> Result<(), Vec<MissingField>> {
+        let missing = self.missing_fields();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(missing)
+        }
+    }
+    /// looks if all the items are present (same as [validate](ResponseBuilder::validate))
+    pub fn is_complete(&self) -> bool {
+        self.validate()
+    }
+    /// returns the differences of the version, status, headers (per header) and body
+    /// from this builder to the other one
+    pub fn diff(&self, other: &ResponseBuilder) -> Vec<FieldDiff> {
+        let mut diffs = Vec::new();
+        diff_field(&mut diffs, "version", &self.version, &other.version);
+        diff_field(&mut diffs, "status", &self.status, &other.status);
+        diff_headers(&mut diffs, "headers", &self.headers, &other.headers);
+        diff_field(&mut diffs, "body", &self.body, &other.body);
+        diffs
+    }
+    fn missing_fields(&self) -> Vec<MissingField> {
+        [
+            (self.version.is_none(), MissingField::Version),
+            (self.status.is_none(), MissingField::Status),
+            (self.headers.is_none(), MissingField::Headers),
+            (self.body.is_none(), MissingField::Body),
+        ]
+        .into_iter()
+        .filter_map(|(missing, field)| missing.then_some(field))
+        .collect()
+    }
     /// creates a new instance of ResponseBuilder with [None] values
     pub const fn new() -> Self {
         Self {
@@ -212,18 +876,29 @@ impl ResponseBuilder {
             status: None,
             headers: None,
             version: None,
+            error: None,
         }
     }
     /// trys to make it to a [Response] otherwise returns a [HttpParseError]
     pub fn build(self) -> Result<Response, HttpParseError> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
         if !self.validate() {
-            return Err(HttpParseError::from((Req, VALIDATE)));
+            return Err(missing_fields_error(Req, &self.missing_fields()));
         }
         Ok(Response {
             version: self.version.unwrap(),
             headers: self.headers.unwrap(),
             status: self.status.unwrap(),
             body: self.body.unwrap(),
+            duplicates: BTreeSet::new(),
+            warnings: Vec::new(),
+            raw_header_lines: Vec::new(),
+            force_http1: false,
+            skip_validation: false,
+            close_delimited: false,
+            journal: HeaderJournal::default(),
         })
     }
     /// replaces the current value with the header parameter
@@ -243,6 +918,26 @@ impl ResponseBuilder {
         self.with_body(&body.json())
     }
 
+    /// replaces the current body with the output written by the given function. <br>
+    /// A write error is returned by [build](ResponseBuilder::build)
+    pub fn with_body_from_writer<F: FnOnce(&mut dyn Write) -> std::io::Result<()>>(mut self, f: F) -> Self {
+        let mut body = String::new();
+        match f(&mut BodyWriter::new(&mut body)) {
+            Ok(()) => self.body = Some(body),
+            Err(err) => self.error = Some(HttpParseError::body(err.to_string())),
+        }
+        self
+    }
+
+    /// replaces the current body with the content of the file and sets the Content-Length
+    /// and the Content-Type (guessed from the extension) headers. <br>
+    /// Returns a [Body](crate::ParseErrorKind::Body) error if the file can't be read or has a binary Content-Type (like an image)
+    pub fn with_body_from_file(mut self, path: &Path) -> Result<Self, HttpParseError> {
+        let mut headers = self.headers.take().unwrap_or_default();
+        self.body = Some(read_body_file(path, &mut headers)?);
+        Ok(self.with_headers(headers))
+    }
+
     /// replaces the current value with the version parameter
     pub fn with_version(mut self, version: HttpVersion) -> Self {
         self.version = Some(version);
@@ -261,6 +956,30 @@ impl ResponseBuilder {
     pub fn with_empty_body(self) -> Self {
         self.with_body("")
     }
+    /// adds the Content-Type header with the media type (like `application/json`)
+    pub fn with_content_type(mut self, mime: &str) -> Self {
+        let mut headers = self.headers.take().unwrap_or_default();
+        replace_header_ignore_case(&mut headers, CONTENT_TYPE, Some(String::from(mime)));
+        self.with_headers(headers)
+    }
+    /// adds the Cache-Control header with the directives (an empty [CacheControl] removes the header)
+    pub fn with_cache_control(mut self, cc: CacheControl) -> Self {
+        let mut headers = self.headers.take().unwrap_or_default();
+        replace_header_ignore_case(&mut headers, CACHE_CONTROL, (!cc.is_empty()).then(|| cc.to_string()));
+        self.with_headers(headers)
+    }
+}
+
+impl Debug for ResponseBuilder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseBuilder")
+            .field("version", &field_state(&self.version))
+            .field("status", &field_state(&self.status))
+            .field("headers", &field_state(&self.headers))
+            .field("body", &field_state(&self.body))
+            .field("error", &field_state(&self.error))
+            .finish()
+    }
 }
 
 impl Default for ResponseBuilder {
@@ -269,6 +988,25 @@ impl Default for ResponseBuilder {
     }
 }
 
+impl From<(HttpVersion, HttpStatus, BTreeMap<String, String>, String)> for Response {
+    fn from(value: (HttpVersion, HttpStatus, BTreeMap<String, String>, String)) -> Self {
+        let (version, status, headers, body) = value;
+        Self {
+            version,
+            status,
+            headers,
+            body,
+            duplicates: BTreeSet::new(),
+            warnings: Vec::new(),
+            raw_header_lines: Vec::new(),
+            force_http1: false,
+            skip_validation: false,
+            close_delimited: false,
+            journal: HeaderJournal::default(),
+        }
+    }
+}
+
 impl TryFrom<Values> for Response {
     type Error = ParseError;
     fn try_from(value: Values) -> Result<Self, Self::Error> {
@@ -277,7 +1015,19 @@ impl TryFrom<Values> for Response {
         let headers = struc.map_val("headers", BTreeMap::try_from)?;
         let status = struc.map_val("status", HttpStatus::try_from)?;
         let version = struc.map_val("version", HttpVersion::try_from)?;
-        Ok(Self { body, headers, status, version })
+        Ok(Self {
+            body,
+            headers,
+            status,
+            version,
+            duplicates: BTreeSet::new(),
+            warnings: Vec::new(),
+            raw_header_lines: Vec::new(),
+            force_http1: false,
+            skip_validation: false,
+            close_delimited: false,
+            journal: HeaderJournal::default(),
+        })
     }
 }
 
@@ -292,6 +1042,26 @@ impl Serialize for Response {
     }
 }
 
+impl Serialize for ResponseBuilder {
+    /// serializes the fields that are set
+    fn serialize(&self) -> Values {
+        let mut struc = BTreeMap::new();
+        if let Some(body) = &self.body {
+            struc.insert(String::from("body"), body.serialize());
+        }
+        if let Some(headers) = &self.headers {
+            struc.insert(String::from("headers"), headers.serialize());
+        }
+        if let Some(status) = &self.status {
+            struc.insert(String::from("status"), status.serialize());
+        }
+        if let Some(version) = &self.version {
+            struc.insert(String::from("version"), version.serialize());
+        }
+        Values::Struct(struc.into_iter().collect())
+    }
+}
+
 impl Destruct for ResponseBuilder {
     type Item = (Option<HttpVersion>, Option<HttpStatus>, Option<BTreeMap<String, String>>, Option<String>);
     fn destruct(self) -> Self::Item {
@@ -299,10 +1069,47 @@ impl Destruct for ResponseBuilder {
     }
 }
 
+impl From<(Option<HttpVersion>, Option<HttpStatus>, Option<BTreeMap<String, String>>, Option<String>)> for ResponseBuilder {
+    fn from(value: (Option<HttpVersion>, Option<HttpStatus>, Option<BTreeMap<String, String>>, Option<String>)) -> Self {
+        let (version, status, headers, body) = value;
+        Self {
+            version,
+            status,
+            headers,
+            body,
+            error: None,
+        }
+    }
+}
+
 /// Several presets for standard Responses
+///
+/// **Note:** the presets that take a body set the Content-Length and
+/// (for a non-empty body) the Content-Type header, which is `text/plain; charset=utf-8` by default. <br>
+/// Use [from_status_and_body_with_content_type](resp_presets::from_status_and_body_with_content_type) for other Content-Types
+/// and [from_status](resp_presets::from_status) plus [set_body](Response::set_body) for a Response without these headers
 pub mod resp_presets {
-    use crate::{HttpStatus, Response, ResponseBuilder, status_presets};
+    use std::sync::{PoisonError, RwLock};
+
+    use wjp::{map, Serialize, Values};
+
+    use crate::{HttpMethod, HttpParseError, HttpStatus, Link, ParseErrorKind, Problem, Request, Response, ResponseBuilder, status_presets};
     use crate::HttpVersion::OnePointOne;
+    use crate::problem::PROBLEM_JSON;
+    use crate::util::{escape_html, ALLOW, CONNECTION, CONTENT_LENGTH, CONTENT_TYPE, MESSAGE_HTTP, UPGRADE};
+
+    const TEXT_PLAIN: &str = "text/plain; charset=utf-8";
+    const TEXT_HTML: &str = "text/html; charset=utf-8";
+    const PLAIN: &str = "text/plain";
+    const HTML: &str = "text/html";
+    const APPLICATION_JSON: &str = "application/json";
+    const UPGRADE_REQUIRED: &str = "This service requires switching to one of the protocols:";
+
+    /// Type of the function that renders the HTML document of an error page
+    /// from the status and the detail (which isn't escaped yet, see [escape_html](crate::escape_html))
+    pub type ErrorPageTemplate = fn(&HttpStatus, Option<&str>) -> String;
+
+    static ERROR_PAGE_TEMPLATE: RwLock<ErrorPageTemplate> = RwLock::new(default_error_page);
 
     /// creates an empty [Response] with version 1.1 and the given [HttpStatus]
     pub fn from_status(status: HttpStatus) -> Response {
@@ -314,10 +1121,24 @@ pub mod resp_presets {
             .build().unwrap()
     }
 
-    /// creates a [Response] with version 1.1, empty headers, the given [HttpStatus] and a given body
+    /// creates a [Response] with version 1.1, the given [HttpStatus] and a given `text/plain` body
+    /// (see [from_status_and_body_with_content_type])
     pub fn from_status_and_body(status: HttpStatus, body: &str) -> Response {
+        from_status_and_body_with_content_type(status, body, TEXT_PLAIN)
+    }
+
+    /// creates a [Response] with version 1.1, the given [HttpStatus] and a given body. <br>
+    /// The Content-Length is set to the length of the body and the Content-Type is set if the body isn't empty.
+    /// A [HttpStatus] that doesn't allow a body (1xx, 204 and 304) gets neither
+    pub fn from_status_and_body_with_content_type(status: HttpStatus, body: &str, content_type: &str) -> Response {
         let mut resp = from_status(status);
         resp.set_body(body);
+        if resp.body_allowed() {
+            resp.add_header((String::from(CONTENT_LENGTH), body.len().to_string()));
+            if !body.is_empty() {
+                resp.add_header((String::from(CONTENT_TYPE), String::from(content_type)));
+            }
+        }
         resp
     }
 
@@ -361,6 +1182,146 @@ pub mod resp_presets {
         from_status_and_body(status_presets::not_implemented(), str)
     }
 
+    /// creates a [Response] for a [HttpParseError] that happened while reading a Request
+    /// with the message of the error as body. <br>
+    /// A [Timeout](ParseErrorKind::Timeout) is answered with Request Timeout,
+    /// [HeaderFieldsTooLarge](ParseErrorKind::HeaderFieldsTooLarge) with Request Header Fields Too Large,
+    /// [VersionNotSupported](ParseErrorKind::VersionNotSupported) with HTTP Version Not Supported,
+    /// an [Unsupported](ParseErrorKind::Unsupported) feature with Not Implemented,
+    /// [HostNotAllowed](ParseErrorKind::HostNotAllowed) with Misdirected Request
+    /// and everything else with Bad Request
+    pub fn from_error(err: &HttpParseError) -> Response {
+        from_status_and_body(error_status(err), err.get_msg().unwrap_or(""))
+    }
+
+    /// creates a [Response] for a [HttpParseError] like [from_error],
+    /// but as an [HTML error page](html_error_page) if the Request prefers `text/html` over `text/plain`
+    /// (like the Accept header of a browser)
+    pub fn from_error_for(err: &HttpParseError, req: &Request) -> Response {
+        match req.negotiate(&[PLAIN, HTML]).as_deref() {
+            Some(HTML) => html_error_page(error_status(err), err.get_msg()),
+            _ => from_error(err),
+        }
+    }
+
+    fn error_status(err: &HttpParseError) -> HttpStatus {
+        match err.get_kind() {
+            ParseErrorKind::Timeout => status_presets::request_timeout(),
+            ParseErrorKind::HeaderFieldsTooLarge => status_presets::request_header_fields_too_large(),
+            ParseErrorKind::VersionNotSupported => status_presets::http_version_not_supported(),
+            ParseErrorKind::Unsupported => status_presets::not_implemented(),
+            ParseErrorKind::HostNotAllowed => status_presets::misdirected_request(),
+            _ => status_presets::bad_request(),
+        }
+    }
+
+    /// creates a [Response] with a small self-contained HTML document for a human-facing error:
+    /// the code and the reason phrase are the title and the heading, the detail is the paragraph below. <br>
+    /// Everything is HTML escaped, the Content-Type is `text/html` and the Content-Length is set.
+    /// The document can be branded with [set_error_page_template]
+    pub fn html_error_page(status: HttpStatus, detail: Option<&str>) -> Response {
+        let template = *ERROR_PAGE_TEMPLATE.read().unwrap_or_else(PoisonError::into_inner);
+        let body = template(&status, detail);
+        from_status_and_body_with_content_type(status, &body, TEXT_HTML)
+    }
+
+    /// replaces the template of [html_error_page] for the whole application
+    /// (pass [default_error_page] to restore the default). <br>
+    /// The template has to escape the detail itself, for example with [escape_html](crate::escape_html)
+    pub fn set_error_page_template(template: ErrorPageTemplate) {
+        *ERROR_PAGE_TEMPLATE.write().unwrap_or_else(PoisonError::into_inner) = template;
+    }
+
+    /// the default template of [html_error_page]
+    pub fn default_error_page(status: &HttpStatus, detail: Option<&str>) -> String {
+        let title = escape_html(&status.to_string());
+        let detail = detail.map_or(String::new(), |detail| format!("<p>{}</p>\n", escape_html(detail)));
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>\n{}</body>\n</html>\n",
+            title, title, detail
+        )
+    }
+
+    /// creates an interim [Response] with Status Early Hints and a Link header
+    /// containing the given targets with their relation types
+    /// (for example `("/style.css", "preload")` becomes `</style.css>; rel=preload`). <br>
+    /// Send it with [write_interim](Response::write_interim) before the final Response
+    pub fn early_hints(links: &[(&str, &str)]) -> Response {
+        let mut resp = from_status(status_presets::early_hints());
+        for (target, rel) in links {
+            resp.add_link(Link::new(target, rel));
+        }
+        resp
+    }
+
+    /// creates an empty [Response] with Status Method Not Allowed
+    /// and the Allow header listing the given methods
+    pub fn method_not_allowed(allowed: &[HttpMethod]) -> Response {
+        let mut resp = from_status(status_presets::method_not_allowed());
+        resp.add_header((String::from(ALLOW), join_methods(allowed)));
+        resp
+    }
+
+    pub(crate) fn join_methods(methods: &[HttpMethod]) -> String {
+        methods
+            .iter()
+            .map(HttpMethod::to_string)
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    /// creates a [Response] echoing the Request as `message/http` body (the answer to a TRACE Request). <br>
+    /// Sensitive headers (like Authorization or Cookie) are redacted
+    /// (see [get_embedded_request](Response::get_embedded_request))
+    pub fn trace_echo(req: &Request) -> Response {
+        from_status_and_body_with_content_type(status_presets::ok(), &req.to_raw_string(false), MESSAGE_HTTP)
+    }
+
+    /// creates a [Response] with the given [HttpStatus] and a [Problem] as `application/problem+json` body
+    pub fn problem(status: HttpStatus, title: &str, detail: Option<&str>) -> Response {
+        let mut problem = Problem::new(&status, title);
+        if let Some(detail) = detail {
+            problem = problem.with_detail(detail);
+        }
+        from_status_and_body_with_content_type(status, &problem.json(), PROBLEM_JSON)
+    }
+
+    /// creates a [Response] with the given [HttpStatus] and a JSON body like
+    /// `{"error":"message","status":404}` (with the Content-Type `application/json`)
+    pub fn json_error(status: HttpStatus, message: &str) -> Response {
+        let body = Values::Struct(map!(("error", &message), ("status", status.get_code())));
+        from_status_and_body_with_content_type(status, &body.json(), APPLICATION_JSON)
+    }
+
+    /// creates a [Response] with Status Upgrade Required, which names the required protocols
+    /// in the Upgrade header (and in the body) and sets `Connection: Upgrade`
+    pub fn upgrade_required(protocols: &[&str]) -> Response {
+        let protocols = protocols.join(", ");
+        let body = format!("{} {}", UPGRADE_REQUIRED, protocols);
+        let mut resp = from_status_and_body(status_presets::upgrade_required(), &body);
+        resp.add_header((String::from(UPGRADE), protocols));
+        resp.add_header((String::from(CONNECTION), String::from(UPGRADE)));
+        resp
+    }
+
+    /// uses the [from_status_and_body] method to create a Response with Status Misdirected Request
+    pub fn misdirected_request(str: &str) -> Response {
+        from_status_and_body(status_presets::misdirected_request(), str)
+    }
+
+    /// uses the [from_status_and_body] method to create a Response with Status Too Many Requests
+    /// and sets the Retry-After header to the number of seconds
+    pub fn too_many_requests(str: &str, retry_after_secs: u64) -> Response {
+        let mut resp = from_status_and_body(status_presets::too_many_requests(), str);
+        resp.set_retry_after_secs(retry_after_secs);
+        resp
+    }
+
+    /// uses the [from_status_and_body] method to create a Response with Status Service Unavailable
+    pub fn service_unavailable(str: &str) -> Response {
+        from_status_and_body(status_presets::service_unavailable(), str)
+    }
+
     /// uses the [from_status_and_body] method to create a Response with Status Unsupported Media Type
     pub fn unsupported_media_type(str: &str) -> Response {
         from_status_and_body(status_presets::unsupported_media_type(), str)
@@ -369,11 +1330,210 @@ pub mod resp_presets {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::fs::read_to_string;
+    use std::io::Write;
+    use std::sync::{Mutex, PoisonError};
+    use std::time::{Duration, UNIX_EPOCH};
 
-    use wjp::Serialize;
+    use wjp::{Deserialize, ParseError, Serialize, SerializeHelper, Values};
 
+    use crate::{BodyFraming, Destruct, ETag, Expires, RetryAfter, HttpParseError, HttpStatus, Request, HttpVersion, LineEnding, MissingField, MockStream, ParseErrorKind, ParseOptions, resp_presets, ResponseBuilder, status_presets};
     use crate::response::Response;
+    use crate::status_presets::ok;
+
+    /// held by the tests that render the application-wide error page template
+    static ERROR_PAGE_TEMPLATE_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn json_error() {
+        let resp = resp_presets::json_error(status_presets::not_found(), "no user \"42\"");
+        assert_eq!(resp.get_status().get_code(), &404);
+        assert_eq!(resp.get_header("Content-Type").unwrap(), "application/json");
+        assert_eq!(resp.get_header("Content-Length").unwrap(), &resp.get_body().len().to_string());
+        let body = ErrorBody::deserialize_str(resp.get_body()).unwrap();
+        assert_eq!(body.error, "no user \"42\"");
+        assert_eq!(body.status, 404);
+    }
+
+    struct ErrorBody {
+        error: String,
+        status: usize,
+    }
+
+    impl TryFrom<Values> for ErrorBody {
+        type Error = ParseError;
+        fn try_from(value: Values) -> Result<Self, Self::Error> {
+            let mut struc = value.get_struct().ok_or(ParseError::new())?;
+            Ok(Self {
+                error: struc.map_val("error", String::try_from)?,
+                status: struc.map_val("status", usize::try_from)?,
+            })
+        }
+    }
+
+    #[test]
+    fn upgrade_required() {
+        let resp = resp_presets::upgrade_required(&["HTTP/2.0", "websocket"]);
+        assert_eq!(resp.get_status().get_code(), &426);
+        assert_eq!(resp.get_header("Upgrade").unwrap(), "HTTP/2.0, websocket");
+        assert_eq!(resp.get_header("Connection").unwrap(), "Upgrade");
+        assert!(resp.get_body().ends_with("HTTP/2.0, websocket"));
+        let parsed = Response::try_from(resp.to_raw_string(true)).unwrap();
+        assert_eq!(parsed.required_upgrades(), Some(vec![String::from("HTTP/2.0"), String::from("websocket")]));
+
+        let resp = resp_presets::ok("").with_added_header("upgrade", " h2c ,, TLS/1.3");
+        assert_eq!(resp.required_upgrades(), Some(vec![String::from("h2c"), String::from("TLS/1.3")]));
+        assert_eq!(resp_presets::ok("").required_upgrades(), None);
+    }
+
+    #[test]
+    fn tab_delimiter() {
+        let resp = Response::parse_with("HTTP/1.1 404\tNot Found\n\n", &ParseOptions::default()).unwrap();
+        assert_eq!(resp.get_status().get_code(), &404);
+        assert_eq!(resp.get_status().get_message(), "Not Found");
+        let resp = Response::parse_with("HTTP/1.1\t200\tOK\n\n", &ParseOptions::default()).unwrap();
+        assert_eq!(resp.get_status().get_code(), &200);
+        assert!(Response::parse_with("HTTP/1.1 404\tNot Found\n\n", &ParseOptions::strict()).is_err());
+    }
+
+    #[test]
+    fn builder_diff() {
+        let before = ResponseBuilder::new()
+            .with_status(ok())
+            .with_headers(BTreeMap::from([
+                (String::from("Content-Type"), String::from("text/plain")),
+                (String::from("Server"), String::from("whdp")),
+            ]))
+            .with_body("Hello");
+        let after = before
+            .clone()
+            .with_status(status_presets::not_found())
+            .with_headers(BTreeMap::from([
+                (String::from("content-type"), String::from("text/html")),
+                (String::from("Server"), String::from("whdp")),
+            ]));
+        let diffs = after.diff(&after.clone());
+        assert!(diffs.is_empty());
+        let diffs = before.diff(&after);
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].get_field(), "status");
+        assert_eq!(diffs[0].get_before(), Some("200 OK"));
+        assert_eq!(diffs[0].get_after(), Some("404 Not Found"));
+        assert_eq!(diffs[1].get_field(), "headers");
+        assert_eq!(diffs[1].get_header(), Some("Content-Type"));
+        assert_eq!(diffs[1].to_string(), "headers Content-Type: text/plain -> text/html");
+
+        let diffs = before.diff(&before.clone().with_version(HttpVersion::One));
+        assert_eq!(diffs[0].to_string(), "version: (unset) -> HTTP/1.0");
+    }
+
+    #[test]
+    fn builder_serialize() {
+        let builder = ResponseBuilder::new().with_status(ok()).with_body("Hello");
+        let struc = builder.serialize().get_struct().unwrap();
+        assert_eq!(struc.len(), 2);
+        assert_eq!(struc.get("status").and_then(Values::get_number).unwrap(), 200.0);
+        assert_eq!(struc.get("body").and_then(Values::get_string).unwrap(), "Hello");
+        assert!(ResponseBuilder::new().serialize().get_struct().unwrap().is_empty());
+    }
+
+    #[test]
+    fn clear_body() {
+        let mut resp = resp_presets::ok("Hello").with_added_header("transfer-encoding", "chunked");
+        resp.clear_body();
+        assert!(resp.is_empty_body());
+        assert_eq!(resp.get_header_ignore_case("Content-Length"), None);
+        assert_eq!(resp.get_header_ignore_case("Transfer-Encoding"), None);
+        assert_eq!(resp.get_header("Content-Type").unwrap(), "text/plain; charset=utf-8");
+    }
+
+    #[test]
+    fn body_framing() {
+        let framing = |resp: &str| Response::try_from(String::from(resp)).unwrap().body_framing();
+        assert_eq!(framing("HTTP/1.1 200 OK\nContent-Length: 5\n\nHello"), BodyFraming::ContentLength(5));
+        assert_eq!(framing("HTTP/1.1 200 OK\nTransfer-Encoding: chunked\n\n"), BodyFraming::Chunked);
+        assert_eq!(framing("HTTP/1.1 200 OK\nTransfer-Encoding: chunked\nContent-Length: 5\n\n"), BodyFraming::Chunked);
+        assert_eq!(framing("HTTP/1.1 200 OK\nTransfer-Encoding: chunked, gzip\n\n"), BodyFraming::CloseDelimited);
+        assert_eq!(framing("HTTP/1.1 200 OK\nContent-Length: five\n\n"), BodyFraming::Invalid);
+        assert_eq!(framing("HTTP/1.1 200 OK\n\n"), BodyFraming::CloseDelimited);
+        for code in [100, 204, 304] {
+            let resp = format!("HTTP/1.1 {} Whatever\nContent-Length: 5\n\n", code);
+            assert_eq!(framing(&resp), BodyFraming::None);
+        }
+
+        let mut stream = MockStream::new().with_read(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nContent-Length: 100\r\n\r\n2\r\nok\r\n0\r\n\r\n");
+        assert_eq!(Response::from_stream(&mut stream, &ParseOptions::new()).unwrap().get_body(), "ok");
+        let mut stream = MockStream::new().with_read(b"HTTP/1.1 200 OK\r\nContent-Length: five\r\n\r\nHello");
+        let err = Response::from_stream(&mut stream, &ParseOptions::new()).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Length);
+    }
+
+    #[test]
+    fn equality() {
+        let repeated = Response::try_from(String::from("HTTP/1.1 200 OK\nVary: a\nVary: b\n\n")).unwrap();
+        let joined = Response::try_from(String::from("HTTP/1.1 200 OK\nVary: a, b\n\n")).unwrap();
+        assert!(repeated.had_duplicate("Vary"));
+        assert_eq!(repeated, joined);
+
+        let mut unchecked = joined.clone();
+        unchecked.skip_send_validation();
+        assert_eq!(unchecked, joined);
+        assert_ne!(joined, Response::try_from(String::from("HTTP/1.1 404 Not Found\nVary: a, b\n\n")).unwrap());
+    }
+
+    #[test]
+    fn body_allowed() {
+        for (code, allowed) in [(100, false), (101, false), (103, false), (200, true), (204, false), (205, true), (304, false), (404, true)] {
+            let resp = Response::try_from(format!("HTTP/1.1 {} Whatever\n\n", code)).unwrap();
+            assert_eq!(resp.body_allowed(), allowed, "{}", code);
+        }
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        let plain = Response::try_from(read_to_string("src/resources/response.txt").unwrap()).unwrap();
+        let bytes = std::fs::read("src/resources/response_bom.txt").unwrap();
+        assert_eq!(Response::try_from(bytes.clone()).unwrap(), plain);
+        let mut stream = MockStream::new().with_read("\u{feff}HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok".as_bytes());
+        let resp = Response::from_stream(&mut stream, &ParseOptions::default()).unwrap();
+        assert_eq!(resp.get_status(), plain.get_status());
+        assert_eq!(resp.get_body(), "ok");
+
+        let err = Response::try_from(String::from("HTTP/1.1\u{feff} 200 OK\n\n")).unwrap_err();
+        assert!(err.get_msg().unwrap().contains("byte order mark"));
+    }
+
+    #[test]
+    fn body_equivalent() {
+        let plain = resp_presets::ok("Hello");
+        let identity = resp_presets::ok("Hello").with_added_header("Content-Encoding", "identity");
+        assert_ne!(plain, identity);
+        assert!(plain.body_equivalent(&identity).unwrap());
+        assert_eq!(plain.body_digest().unwrap(), identity.body_digest().unwrap());
+        assert!(!plain.body_equivalent(&resp_presets::ok("World")).unwrap());
+        assert_ne!(plain.body_digest().unwrap(), resp_presets::ok("World").body_digest().unwrap());
+
+        let gzip = resp_presets::ok("Hello").with_added_header("Content-Encoding", "gzip");
+        let err = plain.body_equivalent(&gzip).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Unsupported);
+        assert_eq!(err.get_msg(), Some("The content coding is not supported: gzip"));
+    }
+
+    #[test]
+    fn body_digest() {
+        assert_eq!(resp_presets::ok("").body_digest(), Ok(0xcbf2_9ce4_8422_2325));
+        assert_eq!(resp_presets::ok("a").body_digest(), Ok(0xaf63_dc4c_8601_ec8c));
+        assert_eq!(resp_presets::ok("foobar").body_digest(), Ok(0x8594_4171_f739_67e8));
+    }
+
+    #[test]
+    fn body_digest_undecodable() {
+        let gzip = resp_presets::ok("Hello").with_added_header("Content-Encoding", "gzip");
+        let err = gzip.body_digest().unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Unsupported);
+        assert_eq!(err.get_msg(), Some("The content coding is not supported: gzip"));
+    }
 
     #[test]
     fn test() {
@@ -383,4 +1543,578 @@ mod tests {
         println!();
         println!("{}", resp.json());
     }
+
+    #[test]
+    fn is_empty_body() {
+        let mut resp = Response::default();
+        resp.set_body("");
+        assert!(resp.is_empty_body());
+        resp.set_body(" ");
+        assert!(!resp.is_empty_body());
+    }
+
+    #[test]
+    fn parsed_body() {
+        let mut resp = Response::default();
+        resp.set_body(" ");
+        assert!(!resp.has_body());
+        assert!(resp.get_parsed_body::<String>().is_err());
+        assert_eq!(resp.get_parsed_body_opt::<String>(), Ok(None));
+        resp.set_body("\"value\"");
+        assert_eq!(resp.get_parsed_body::<String>(), Ok(String::from("value")));
+    }
+
+    #[test]
+    fn keep_all_duplicates() {
+        let raw = "HTTP/1.1 200 OK\nSet-Cookie: a=1\nSet-Cookie: b=2\n\n";
+        let resp = Response::try_from(String::from(raw)).unwrap();
+        assert!(resp.had_duplicate("set-cookie"));
+        assert_eq!(
+            resp.to_raw_string(true),
+            "HTTP/1.1 200 OK\r\nSet-Cookie: a=1\r\nSet-Cookie: b=2\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn joined_duplicates() {
+        let raw = "HTTP/1.1 401 Unauthorized\nWWW-Authenticate: Basic\nwww-authenticate: Bearer\nSet-Cookie: a=1\nSet-Cookie: b=2\n\n";
+        let options = ParseOptions::new().with_joined_headers(&["www-authenticate"]);
+        let resp = Response::parse_with(raw, &options).unwrap();
+        assert!(resp.had_duplicate("WWW-Authenticate"));
+        assert_eq!(resp.get_header("WWW-Authenticate").unwrap(), "Basic, Bearer");
+        assert_eq!(resp.get_header("Set-Cookie").unwrap(), "a=1\nb=2");
+    }
+
+    #[test]
+    fn dates() {
+        let string = read_to_string("src/resources/response.txt").unwrap();
+        let mut resp = Response::try_from(string).unwrap();
+        assert_eq!(resp.get_date(), Some(UNIX_EPOCH + Duration::from_secs(1_700_052_171)));
+        assert_eq!(resp.get_expires(), Some(Expires::Expired));
+        resp.set_expires(UNIX_EPOCH);
+        assert_eq!(resp.get_header("Expires").unwrap(), "Thu, 01 Jan 1970 00:00:00 GMT");
+        assert_eq!(resp.get_expires(), Some(Expires::At(UNIX_EPOCH)));
+    }
+
+    #[test]
+    fn last_modified() {
+        let mut resp = Response::default();
+        assert_eq!(resp.get_last_modified(), None);
+        resp.set_last_modified(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+        assert_eq!(resp.get_header("Last-Modified").unwrap(), "Tue, 14 Nov 2023 22:13:20 GMT");
+        assert_eq!(resp.get_last_modified(), Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)));
+        resp.add_header((String::from("Last-Modified"), String::from("Tuesday, 14-Nov-23 22:13:20 GMT")));
+        assert_eq!(resp.get_last_modified(), Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)));
+    }
+
+    #[test]
+    fn is_cacheable() {
+        let get = Request::try_from("GET / HTTP/1.1\nHost: a\n\n").unwrap();
+        assert!(resp_presets::ok("Hello").is_cacheable(&get));
+        assert!(resp_presets::ok("Hello").is_cacheable(&Request::try_from("HEAD / HTTP/1.1\n\n").unwrap()));
+        assert!(!resp_presets::ok("Hello").is_cacheable(&Request::try_from("POST / HTTP/1.1\n\n").unwrap()));
+        assert!(resp_presets::not_found("").is_cacheable(&get));
+        assert!(!resp_presets::created("").is_cacheable(&get));
+        assert!(!resp_presets::internal_server_error("").is_cacheable(&get));
+        for directives in ["public, max-age=60", "max-age=60", "s-maxage=60", "public"] {
+            assert!(resp_presets::created("").with_added_header("Cache-Control", directives).is_cacheable(&get), "{}", directives);
+        }
+        assert!(resp_presets::created("").with_added_header("Expires", "Thu, 01 Dec 2033 16:00:00 GMT").is_cacheable(&get));
+        assert!(!resp_presets::created("").with_added_header("Cache-Control", "public, no-store").is_cacheable(&get));
+
+        for (directives, cacheable) in [("max-age=60", true), ("no-store", false), ("private", false), ("Private=\"Set-Cookie\"", false), ("no-cache", true)] {
+            let resp = resp_presets::ok("Hello").with_added_header("Cache-Control", directives);
+            assert_eq!(resp.is_cacheable(&get), cacheable, "{}", directives);
+        }
+        let no_store = Request::try_from("GET / HTTP/1.1\nCache-Control: no-store\n\n").unwrap();
+        assert!(!resp_presets::ok("Hello").is_cacheable(&no_store));
+
+        let authorized = Request::try_from("GET / HTTP/1.1\nAuthorization: Bearer 1\n\n").unwrap();
+        assert!(!resp_presets::ok("Hello").is_cacheable(&authorized));
+        for directives in ["public", "must-revalidate", "s-maxage=10"] {
+            assert!(resp_presets::ok("Hello").with_added_header("Cache-Control", directives).is_cacheable(&authorized), "{}", directives);
+        }
+    }
+
+    #[test]
+    fn html_error_page() {
+        let _lock = ERROR_PAGE_TEMPLATE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        let resp = resp_presets::html_error_page(status_presets::not_found(), Some("<script>alert('x')</script> & \"quotes\""));
+        assert_eq!(resp.get_header("Content-Type").unwrap(), "text/html; charset=utf-8");
+        assert_eq!(resp.get_header("Content-Length").unwrap(), &resp.get_body().len().to_string());
+        assert!(resp.get_body().contains("<title>404 Not Found</title>"));
+        assert!(resp.get_body().contains("<h1>404 Not Found</h1>"));
+        assert!(resp.get_body().contains("<p>&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt; &amp; &quot;quotes&quot;</p>"));
+        assert!(!resp.get_body().contains("<script>"));
+        let status = HttpStatus::from((400, "<b>Bad</b>"));
+        assert!(resp_presets::html_error_page(status, None).get_body().contains("<h1>400 &lt;b&gt;Bad&lt;/b&gt;</h1>\n</body>"));
+
+        let err = HttpParseError::request("The <uri> is too long");
+        let browser = Request::try_from("GET / HTTP/1.1\nAccept: text/html,application/xhtml+xml,*/*;q=0.8\n\n").unwrap();
+        let resp = resp_presets::from_error_for(&err, &browser);
+        assert_eq!(resp.get_status().get_code(), &400);
+        assert!(resp.get_body().contains("<p>The &lt;uri&gt; is too long</p>"));
+        for accept in ["", "Accept: */*\n", "Accept: application/json\n"] {
+            let req = Request::try_from(format!("GET / HTTP/1.1\n{}\n", accept).as_str()).unwrap();
+            assert_eq!(resp_presets::from_error_for(&err, &req), resp_presets::from_error(&err), "{}", accept);
+        }
+    }
+
+    #[test]
+    fn error_page_template() {
+        let _lock = ERROR_PAGE_TEMPLATE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        resp_presets::set_error_page_template(|status, detail| format!("<h1>ACME {}</h1>{}", status.get_code(), detail.is_some()));
+        let resp = resp_presets::html_error_page(status_presets::internal_server_error(), None);
+        resp_presets::set_error_page_template(resp_presets::default_error_page);
+        assert_eq!(resp.get_body(), "<h1>ACME 500</h1>false");
+        assert!(resp_presets::html_error_page(status_presets::not_found(), None).get_body().starts_with("<!DOCTYPE html>"));
+    }
+
+    #[test]
+    fn embedded_request() {
+        let req = Request::try_from("TRACE /diag HTTP/1.1\r\nHost: example.com\r\nAuthorization: Bearer 123\r\n\r\n").unwrap();
+        let resp = resp_presets::trace_echo(&req);
+        assert_eq!(resp.get_header("Content-Type").unwrap(), "message/http");
+        let embedded = resp.get_embedded_request().unwrap().unwrap();
+        assert_eq!(embedded.get_uri(), "/diag");
+        assert_eq!(embedded.get_header("Host").unwrap(), "example.com");
+        assert_eq!(embedded.get_header("Authorization").unwrap(), "[REDACTED]");
+
+        let mut resp = resp_presets::ok("POST / HTTP/1.1\r\nContent-Length: 2\r\n\r\nHiTRAILING");
+        resp.add_header((String::from("Content-Type"), String::from("Message/HTTP")));
+        assert_eq!(resp.get_embedded_request().unwrap().unwrap().get_body(), "Hi");
+        resp.set_body("not http");
+        assert!(resp.get_embedded_request().unwrap().is_err());
+        resp.set_body("POST / HTTP/1.1\r\nContent-Length: 99999999999999999\r\n\r\nHi");
+        assert_eq!(resp.get_embedded_request().unwrap().unwrap_err().get_kind(), &ParseErrorKind::Length);
+        resp.set_body("POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nffffffffffffff\r\nHi");
+        assert_eq!(resp.get_embedded_request().unwrap().unwrap_err().get_kind(), &ParseErrorKind::Length);
+        assert!(resp_presets::ok("GET / HTTP/1.1\r\n\r\n").get_embedded_request().is_none());
+    }
+
+    #[test]
+    fn content_type() {
+        let mut resp = resp_presets::ok("{}");
+        resp.set_content_type("application/json");
+        assert_eq!(resp.get_content_type().unwrap().get_essence(), "application/json");
+        assert_eq!(resp.charset(), None);
+        resp.set_content_type_utf8("text/html");
+        assert_eq!(resp.get_header("Content-Type").unwrap(), "text/html; charset=utf-8");
+        assert_eq!(resp.charset().unwrap(), "utf-8");
+
+        let resp = Response::builder()
+            .with_status(ok())
+            .with_version(HttpVersion::OnePointOne)
+            .with_content_type("image/png")
+            .with_empty_body()
+            .build()
+            .unwrap();
+        assert_eq!(resp.get_header("Content-Type").unwrap(), "image/png");
+    }
+
+    #[test]
+    fn content_type_replaces_any_casing() {
+        let mut resp = Response::try_from(String::from("HTTP/1.1 200 OK\r\ncontent-type: text/plain\r\nContent-Length: 2\r\n\r\n{}")).unwrap();
+        resp.set_content_type("application/json");
+        assert_eq!(resp.get_headers().get("Content-Type").unwrap(), "application/json");
+        assert!(!resp.get_headers().contains_key("content-type"));
+        resp.set_content_type_utf8("text/html");
+        assert_eq!(resp.to_raw_string(true).to_ascii_lowercase().matches("content-type").count(), 1);
+    }
+
+    #[test]
+    fn append_body_from() {
+        let mut resp = resp_presets::ok("Hello ");
+        assert_eq!(resp.append_body_from(&mut "upstream".as_bytes()).unwrap(), 8);
+        assert_eq!(resp.get_body(), "Hello upstream");
+        let err = resp.append_body_from(&mut [b'!', 0xff].as_slice()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(resp.get_body(), "Hello upstream");
+    }
+
+    #[test]
+    fn body_writer() {
+        let mut resp = Response::default();
+        resp.set_body("<ul>");
+        {
+            let mut writer = resp.body_writer();
+            for item in 0..2 {
+                write!(writer, "<li>{}</li>", item).unwrap();
+            }
+            assert!(writer.write(&[0xff]).is_err());
+        }
+        assert_eq!(resp.get_body(), "<ul><li>0</li><li>1</li>");
+
+        let resp = Response::builder()
+            .with_body_from_writer(|writer| write!(writer, "Hello {}", 42))
+            .with_status(ok())
+            .with_empty_headers()
+            .with_version(HttpVersion::OnePointOne)
+            .build()
+            .unwrap();
+        assert_eq!(resp.get_body(), "Hello 42");
+
+        let err = Response::builder()
+            .with_body_from_writer(|writer| writer.write_all(&[0xff]))
+            .with_status(ok())
+            .with_empty_headers()
+            .with_version(HttpVersion::OnePointOne)
+            .build()
+            .unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Body);
+    }
+
+    #[test]
+    fn version_aware_wire_format() {
+        let mut resp = Response::default();
+        resp.set_version(HttpVersion::Three);
+        assert_eq!(resp.to_bytes().unwrap_err().get_kind(), &ParseErrorKind::Version);
+        let mut bytes = Vec::new();
+        resp.force_http1_framing().write_to(&mut bytes).unwrap();
+        assert_eq!(bytes, b"HTTP/3 200 OK\r\n\r\nHello, World");
+    }
+
+    #[test]
+    fn etag() {
+        let mut resp = Response::default();
+        assert_eq!(resp.get_etag(), None);
+        resp.set_etag(ETag::weak("v1"));
+        assert_eq!(resp.get_header("ETag").unwrap(), "W/\"v1\"");
+        assert_eq!(resp.get_etag(), Some(ETag::weak("v1")));
+    }
+
+    fn read_from_server(answer: &'static [u8], options: ParseOptions) -> Result<Response, HttpParseError> {
+        Response::from_stream(&mut MockStream::new().with_read(answer), &options)
+    }
+
+    #[test]
+    fn read_until_close() {
+        let resp = read_from_server(b"HTTP/1.0 200 OK\r\nServer: old\r\n\r\nline 1\r\nline 2", ParseOptions::new()).unwrap();
+        assert_eq!(resp.get_version(), &HttpVersion::One);
+        assert_eq!(resp.get_body(), "line 1\r\nline 2");
+        assert!(resp.is_close_delimited());
+
+        let resp = read_from_server(b"HTTP/1.1 200 OK\r\n\r\n", ParseOptions::new()).unwrap();
+        assert_eq!(resp.get_body(), "");
+        assert!(resp.is_close_delimited());
+
+        let options = ParseOptions::new().with_max_body_size(4);
+        let err = read_from_server(b"HTTP/1.1 200 OK\r\n\r\nHello", options).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Length);
+    }
+
+    #[test]
+    fn no_body_status() {
+        let resp = read_from_server(b"HTTP/1.1 204 No Content\r\n\r\nignored", ParseOptions::new()).unwrap();
+        assert_eq!(resp.get_body(), "");
+        assert!(!resp.is_close_delimited());
+        let resp = read_from_server(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nHi", ParseOptions::new()).unwrap();
+        assert_eq!(resp.get_body(), "Hi");
+        assert!(!resp.is_close_delimited());
+    }
+
+    #[test]
+    fn read_chunked() {
+        // the connection stays open, so reading past the last chunk would fail
+        let mut stream = MockStream::new()
+            .with_read(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nHel")
+            .with_read(b"lo\r\n7;ext=1\r\n, World\r\n")
+            .with_read(b"0\r\n\r\n")
+            .with_would_block();
+        let resp = Response::from_stream(&mut stream, &ParseOptions::default()).unwrap();
+        assert_eq!(resp.get_body(), "Hello, World");
+        assert!(!stream.is_exhausted());
+    }
+
+    #[test]
+    fn to_raw_string() {
+        let mut resp = Response::default();
+        resp.add_header((String::from("Set-Cookie"), String::from("id=1")));
+        assert_eq!(
+            resp.to_raw_string(false),
+            "HTTP/1.1 200 OK\r\nSet-Cookie: [REDACTED]\r\n\r\nHello, World"
+        );
+        assert!(resp.to_raw_string(true).contains("Set-Cookie: id=1\r\n"));
+    }
+
+    #[test]
+    fn raw_header_lines() {
+        let mut resp = Response::default();
+        resp.add_raw_header_line("x-Dict:  a=1,   b=?0").unwrap();
+        resp.add_raw_header_line("set-cookie:id=1").unwrap();
+        assert!(resp.get_header("x-dict").is_none());
+        assert_eq!(
+            String::from_utf8(resp.to_bytes().unwrap()).unwrap(),
+            "HTTP/1.1 200 OK\r\nx-Dict:  a=1,   b=?0\r\nset-cookie:id=1\r\n\r\nHello, World"
+        );
+        assert!(resp.to_raw_string(false).contains("\r\nset-cookie: [REDACTED]\r\n"));
+
+        for line in ["X-Evil: a\r\nSet-Cookie: b=2", "X-Evil: a\nb", "X-Evil: a\0", "no colon", ": no name", "X-Bytes: :aGVsbG8=:", "a:b:c"] {
+            let err = resp.add_raw_header_line(line).unwrap_err();
+            assert_eq!(err.get_kind(), &ParseErrorKind::Util, "{}", line);
+        }
+        assert_eq!(resp.get_raw_header_lines().len(), 2);
+    }
+
+    #[test]
+    fn owned_modification() {
+        let base = resp_presets::ok("Hello").with_added_header("Server", "whdp");
+        let created = base
+            .clone()
+            .with_status(status_presets::created())
+            .with_added_header("Location", "/items/1")
+            .with_removed_header("Server")
+            .with_body("");
+        assert_eq!(base.get_status().get_code(), &200);
+        assert_eq!(base.get_header("Server").unwrap(), "whdp");
+        assert_eq!(created.get_status().get_code(), &201);
+        assert_eq!(created.get_header("Location").unwrap(), "/items/1");
+        assert!(created.get_header("Server").is_none());
+        assert_eq!(created.get_body(), "");
+        assert_ne!(base, created);
+        assert_eq!(base.clone().with_version(HttpVersion::One).get_version(), &HttpVersion::One);
+    }
+
+    #[test]
+    fn builder_missing_fields() {
+        let builder = Response::builder().with_status(ok()).with_empty_headers();
+        assert_eq!(builder.validate_detailed(), Err(vec![MissingField::Version, MissingField::Body]));
+        assert!(!builder.is_complete());
+        assert_eq!(
+            format!("{:?}", builder),
+            "ResponseBuilder { version: None, status: set, headers: set, body: None, error: None }"
+        );
+        let builder = Response::builder().with_version(HttpVersion::OnePointOne).with_empty_body();
+        assert_eq!(builder.validate_detailed(), Err(vec![MissingField::Status, MissingField::Headers]));
+        let builder = builder.with_status(ok()).with_headers(BTreeMap::new());
+        assert_eq!(builder.validate_detailed(), Ok(()));
+        assert!(builder.is_complete());
+    }
+
+    #[test]
+    fn preset_headers() {
+        let presets = [
+            resp_presets::ok("Hello"),
+            resp_presets::bad_request("Hello"),
+            resp_presets::not_found("Hello"),
+            resp_presets::created("Hello"),
+            resp_presets::internal_server_error("Hello"),
+            resp_presets::not_implemented("Hello"),
+            resp_presets::unsupported_media_type("Hello"),
+        ];
+        for resp in presets {
+            assert_eq!(resp.get_header("Content-Length").unwrap(), "5");
+            assert_eq!(resp.get_header("Content-Type").unwrap(), "text/plain; charset=utf-8");
+        }
+        let resp = resp_presets::ok("äö");
+        assert_eq!(resp.get_header("Content-Length").unwrap(), "4");
+        let resp = resp_presets::ok("");
+        assert_eq!(resp.get_header("Content-Length").unwrap(), "0");
+        assert!(resp.get_header("Content-Type").is_none());
+        for resp in [resp_presets::no_content(""), resp_presets::r#continue("")] {
+            assert!(resp.get_headers().is_empty());
+        }
+        let resp = resp_presets::from_status_and_body_with_content_type(ok(), "{}", "application/json");
+        assert_eq!(resp.get_header("Content-Type").unwrap(), "application/json");
+        assert_eq!(resp.get_header("Content-Length").unwrap(), "2");
+    }
+
+    #[test]
+    fn get_header_ignores_case() {
+        let resp = Response::try_from(String::from("HTTP/1.1 200 OK\r\ncontent-length: 0\r\nX-Trace-Id: 7\r\n\r\n")).unwrap();
+        assert_eq!(resp.get_header("Content-Length").unwrap(), "0");
+        assert_eq!(resp.get_header("x-trace-id"), Request::try_from("GET / HTTP/1.1\nX-Trace-Id: 7\n\n").unwrap().get_header("x-trace-id"));
+        assert!(resp.get_header("X-Missing").is_none());
+    }
+
+    #[test]
+    fn destruct_round_trip() {
+        let resp = resp_presets::ok("Hello");
+        let (version, status, headers, body) = resp.clone().destruct();
+        assert_eq!((version, status.get_code(), body.as_str()), (HttpVersion::OnePointOne, &200, "Hello"));
+        assert_eq!(headers.len(), 2);
+        assert_eq!(Response::from(resp.clone().destruct()), resp);
+
+        let status = status_presets::not_found();
+        assert_eq!(HttpStatus::from(status.clone().destruct()), status);
+
+        let builder = Response::builder().with_status(ok()).with_body("");
+        assert_eq!(ResponseBuilder::from(builder.clone().destruct()), builder);
+    }
+
+    #[test]
+    fn destruct_drops_metadata() {
+        let resp = Response::try_from(String::from("HTTP/1.1 200 OK\r\nContent-Length: 0\r\nX-A: 1\r\nX-A: 2\r\n\r\n")).unwrap();
+        let rebuilt = Response::from(resp.clone().destruct());
+        assert_eq!(rebuilt, resp);
+        assert!(resp.had_duplicate("X-A"));
+        assert!(!rebuilt.had_duplicate("X-A"));
+
+        let builder = Response::builder()
+            .with_version(HttpVersion::OnePointOne)
+            .with_status(ok())
+            .with_headers(BTreeMap::new())
+            .with_body_from_writer(|_| Err(std::io::Error::other("broken")));
+        assert!(builder.clone().build().is_err());
+        assert!(ResponseBuilder::from(builder.destruct()).with_body("").build().is_ok());
+    }
+
+    #[test]
+    fn empty_reason_phrase() {
+        let resp = Response::try_from(String::from("HTTP/1.1 200\r\nContent-Length: 0\r\n\r\n")).unwrap();
+        assert_eq!(resp.get_status().get_message(), "");
+        assert_eq!(resp.to_raw_string(true), "HTTP/1.1 200\r\nContent-Length: 0\r\n\r\n");
+        let resp = Response::try_from(String::from("HTTP/1.1 404 Not Found\r\n\r\n")).unwrap();
+        assert_eq!(resp.get_status().get_message(), "Not Found");
+        assert!(resp.to_string().starts_with("HTTP/1.1 404 Not Found\n"));
+    }
+
+    #[test]
+    fn const_constructors() {
+        const RESPONSE_BUILDER: ResponseBuilder = Response::builder();
+        assert_eq!(RESPONSE_BUILDER, ResponseBuilder::new());
+    }
+
+    #[test]
+    fn textual() {
+        let textual = [
+            "text/html; charset=utf-8",
+            "TEXT/CSV",
+            "application/json",
+            "application/problem+json",
+            "application/xml",
+            "application/atom+xml",
+            "application/x-www-form-urlencoded",
+        ];
+        for content_type in textual {
+            let resp = resp_presets::from_status_and_body_with_content_type(ok(), "x", content_type);
+            assert!(resp.is_textual(), "{}", content_type);
+        }
+        for content_type in ["image/png", "application/octet-stream", "application/jsonx", "multipart/form-data"] {
+            let resp = resp_presets::from_status_and_body_with_content_type(ok(), "x", content_type);
+            assert!(!resp.is_textual(), "{}", content_type);
+        }
+        let resp = resp_presets::ok("x").with_added_header("Content-Encoding", "gzip");
+        assert!(!resp.is_textual());
+        let resp = resp_presets::ok("x").with_added_header("Content-Encoding", "identity");
+        assert!(resp.is_textual());
+        assert!(!resp.clone().with_removed_header("Content-Type").is_textual());
+    }
+
+    #[test]
+    fn charset() {
+        assert_eq!(resp_presets::ok("x").charset(), Some(String::from("utf-8")));
+        let resp = resp_presets::from_status_and_body_with_content_type(ok(), "x", "text/html; Charset=\"ISO-8859-1\"");
+        assert_eq!(resp.charset(), Some(String::from("ISO-8859-1")));
+        let resp = resp_presets::from_status_and_body_with_content_type(ok(), "x", "application/json");
+        assert_eq!(resp.charset(), None);
+    }
+
+    #[test]
+    fn interim() {
+        let mut out = Vec::new();
+        let hints = resp_presets::early_hints(&[("/style.css", "preload"), ("/app.js", "preload")]);
+        hints.write_interim(&mut out).unwrap();
+        resp_presets::ok("Hi").write_to(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "HTTP/1.1 103 Early Hints\r\nLink: </style.css>; rel=preload, </app.js>; rel=preload\r\n\r\n\
+            HTTP/1.1 200 OK\r\nContent-Length: 2\r\nContent-Type: text/plain; charset=utf-8\r\n\r\nHi"
+        );
+        let err = resp_presets::ok("Hi").write_interim(&mut Vec::new()).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Resp);
+    }
+
+    #[test]
+    fn early_hints_exchange() {
+        let mut wire = Vec::new();
+        resp_presets::early_hints(&[("/style.css", "preload")]).write_interim(&mut wire).unwrap();
+        resp_presets::early_hints(&[("/app.js", "preload"), ("/font.woff2", "preconnect")]).write_interim(&mut wire).unwrap();
+        resp_presets::ok("Hi").write_to(&mut wire).unwrap();
+        wire.extend_from_slice(b"HTTP/1.1 204 No Content\r\n\r\n");
+
+        let mut stream = MockStream::new().with_read(&wire);
+        let (resp, interim) = Response::from_stream_with_interim(&mut stream, &ParseOptions::default()).unwrap();
+        assert_eq!(resp.get_body(), "Hi");
+        assert_eq!(interim.len(), 2);
+        assert_eq!(interim[0].get_status().get_code(), &103);
+        assert_eq!(interim[0].get_header("Link").unwrap(), "</style.css>; rel=preload");
+        assert_eq!(interim[1].get_header("Link").unwrap(), "</app.js>; rel=preload, </font.woff2>; rel=preconnect");
+
+        let mut stream = MockStream::new().with_read(b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\r\n");
+        let (resp, interim) = Response::from_stream_with_interim(&mut stream, &ParseOptions::default()).unwrap();
+        assert_eq!(resp.get_status().get_code(), &101);
+        assert!(interim.is_empty());
+    }
+
+    #[test]
+    fn line_endings() {
+        let resp = resp_presets::ok("a\r\nb");
+        assert_eq!(
+            resp.to_bytes_with(LineEnding::Lf).unwrap(),
+            b"HTTP/1.1 200 OK\nContent-Length: 4\nContent-Type: text/plain; charset=utf-8\n\na\r\nb"
+        );
+        assert_eq!(resp.to_bytes_with(LineEnding::default()).unwrap(), resp.to_bytes().unwrap());
+        let mut out = Vec::new();
+        resp.write_to_with(&mut out, LineEnding::Lf).unwrap();
+        assert_eq!(out, resp.to_bytes_with(LineEnding::Lf).unwrap());
+        let req = Request::try_from("GET / HTTP/1.1\nHost: example.com\n\n").unwrap();
+        assert_eq!(req.to_bytes_with(LineEnding::Lf).unwrap(), b"GET / HTTP/1.1\nHost: example.com\n\n");
+    }
+
+    #[test]
+    fn status_overrides() {
+        let mut resp = resp_presets::ok("Hi");
+        resp.set_reason_phrase("Fine").unwrap();
+        assert_eq!(resp.get_status().to_string(), "200 Fine");
+        assert_ne!(resp.get_status(), &status_presets::ok());
+        resp.set_status_code(404).unwrap();
+        assert_eq!(resp.get_status(), &status_presets::not_found());
+        resp.set_status_code(299).unwrap();
+        assert_eq!(resp.get_status().to_string(), "299 Custom HttpStatus");
+        assert!(resp.set_status_code(42).is_err());
+        assert!(resp.set_reason_phrase("a\nb").is_err());
+        assert_eq!(resp.get_status().get_code(), &299);
+        assert!(resp.to_raw_string(true).starts_with("HTTP/1.1 299 Custom HttpStatus\r\n"));
+    }
+
+    #[test]
+    fn set_status_code_reason_phrases() {
+        let mut resp = resp_presets::ok("");
+        let presets = [
+            status_presets::r#continue(), status_presets::early_hints(), status_presets::ok(),
+            status_presets::created(), status_presets::no_content(), status_presets::bad_request(),
+            status_presets::not_found(), status_presets::method_not_allowed(), status_presets::request_timeout(),
+            status_presets::unsupported_media_type(), status_presets::misdirected_request(),
+            status_presets::request_header_fields_too_large(), status_presets::upgrade_required(),
+            status_presets::too_many_requests(), status_presets::internal_server_error(),
+            status_presets::not_implemented(), status_presets::service_unavailable(),
+            status_presets::http_version_not_supported(),
+        ];
+        for preset in presets {
+            resp.set_status_code(*preset.get_code()).unwrap();
+            assert_eq!(resp.get_status(), &preset);
+        }
+        resp.set_status_code(304).unwrap();
+        assert_eq!(resp.get_status().to_string(), "304 Not Modified");
+    }
+
+    #[test]
+    fn retry_after() {
+        let mut resp = resp_presets::too_many_requests("slow down", 30);
+        assert_eq!(resp.get_status().get_code(), &429);
+        assert_eq!(resp.get_header("Retry-After").unwrap(), "30");
+        assert_eq!(resp.get_retry_after(), Some(RetryAfter::Seconds(30)));
+
+        let mut resp2 = resp_presets::service_unavailable("maintenance");
+        assert_eq!(resp2.get_retry_after(), None);
+        let date = UNIX_EPOCH + Duration::from_secs(1_445_412_480);
+        resp2.set_retry_after_date(date);
+        assert_eq!(resp2.get_header("Retry-After").unwrap(), "Wed, 21 Oct 2015 07:28:00 GMT");
+        assert_eq!(resp2.get_retry_after(), Some(RetryAfter::Date(date)));
+
+        resp.add_header((String::from("retry-after"), String::from("later")));
+        resp.remove_header("Retry-After");
+        assert_eq!(resp.get_retry_after(), None);
+    }
 }
@@ -0,0 +1,82 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use crate::date::{format_http_date, parse_http_date};
+use crate::error::HttpParseError;
+
+const INVALID_RETRY_AFTER: &str = "The Retry-After header has to be a number of seconds or a HTTP date";
+
+/// Enum for the two forms of the Retry-After header
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum RetryAfter {
+    /// the number of seconds to wait (for example `120`)
+    Seconds(u64),
+    /// the time after which to retry (for example `Wed, 21 Oct 2015 07:28:00 GMT`)
+    Date(SystemTime),
+}
+
+impl RetryAfter {
+    /// returns the time to wait from the given point in time
+    /// (zero if the date already passed)
+    pub fn delay_from(&self, now: SystemTime) -> Duration {
+        match self {
+            RetryAfter::Seconds(seconds) => Duration::from_secs(*seconds),
+            RetryAfter::Date(date) => date.duration_since(now).unwrap_or(Duration::ZERO),
+        }
+    }
+}
+
+impl FromStr for RetryAfter {
+    type Err = HttpParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(seconds) = u64::from_str(s) {
+            return Ok(RetryAfter::Seconds(seconds));
+        }
+        parse_http_date(s)
+            .map(RetryAfter::Date)
+            .ok_or(HttpParseError::util(INVALID_RETRY_AFTER))
+    }
+}
+
+impl Display for RetryAfter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RetryAfter::Seconds(seconds) => write!(f, "{}", seconds),
+            RetryAfter::Date(date) => write!(f, "{}", format_http_date(*date)),
+        }
+    }
+}
+
+impl Debug for RetryAfter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::RetryAfter;
+
+    #[test]
+    fn parse() {
+        assert_eq!(RetryAfter::from_str(" 120 "), Ok(RetryAfter::Seconds(120)));
+        let date = RetryAfter::from_str("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+        assert_eq!(date, RetryAfter::Date(UNIX_EPOCH + Duration::from_secs(1_445_412_480)));
+        assert_eq!(date.to_string(), "Wed, 21 Oct 2015 07:28:00 GMT");
+        assert!(RetryAfter::from_str("-1").is_err());
+        assert!(RetryAfter::from_str("soon").is_err());
+    }
+
+    #[test]
+    fn delay() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        assert_eq!(RetryAfter::Seconds(5).delay_from(now), Duration::from_secs(5));
+        assert_eq!(RetryAfter::Date(now + Duration::from_secs(60)).delay_from(now), Duration::from_secs(60));
+        assert_eq!(RetryAfter::Date(UNIX_EPOCH).delay_from(now), Duration::ZERO);
+    }
+}
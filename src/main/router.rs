@@ -0,0 +1,324 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::method::HttpMethod;
+use crate::request::Request;
+use crate::response::{resp_presets, Response};
+use crate::status::status_presets::no_content;
+use crate::uri::Target;
+use crate::util::ALLOW;
+
+const ASTERISK: &str = "*";
+const PATH_DELIMITER: char = '/';
+const PARAM_START: char = '{';
+const PARAM_END: char = '}';
+
+/// A small map from [HttpMethod] to a handler (or any other value) for routing by method
+///
+/// Example:
+/// ```
+/// use whdp::{HttpMethod, MethodMap};
+///
+/// let map = MethodMap::new()
+///     .get("read")
+///     .post("create");
+///
+/// assert_eq!(map.lookup(&HttpMethod::Head), Some(&"read"));
+/// assert_eq!(map.lookup(&HttpMethod::Put), None);
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct MethodMap<T> {
+    handlers: BTreeMap<HttpMethod, T>,
+}
+
+impl<T> MethodMap<T> {
+    /// creates a new and empty MethodMap
+    pub const fn new() -> Self {
+        Self {
+            handlers: BTreeMap::new(),
+        }
+    }
+    /// registers the handler for the given [HttpMethod] (replaces an existing one)
+    pub fn on(mut self, method: HttpMethod, handler: T) -> Self {
+        self.handlers.insert(method, handler);
+        self
+    }
+    /// registers the handler for [GET](HttpMethod::Get)
+    pub fn get(self, handler: T) -> Self {
+        self.on(HttpMethod::Get, handler)
+    }
+    /// registers the handler for [POST](HttpMethod::Post)
+    pub fn post(self, handler: T) -> Self {
+        self.on(HttpMethod::Post, handler)
+    }
+    /// registers the handler for [PUT](HttpMethod::Put)
+    pub fn put(self, handler: T) -> Self {
+        self.on(HttpMethod::Put, handler)
+    }
+    /// registers the handler for [DELETE](HttpMethod::Delete)
+    pub fn delete(self, handler: T) -> Self {
+        self.on(HttpMethod::Delete, handler)
+    }
+    /// registers the handler for [PATCH](HttpMethod::Patch)
+    pub fn patch(self, handler: T) -> Self {
+        self.on(HttpMethod::Patch, handler)
+    }
+    /// registers the handler for [HEAD](HttpMethod::Head)
+    pub fn head(self, handler: T) -> Self {
+        self.on(HttpMethod::Head, handler)
+    }
+    /// registers the handler for [CONNECT](HttpMethod::Connect)
+    pub fn connect(self, handler: T) -> Self {
+        self.on(HttpMethod::Connect, handler)
+    }
+    /// registers the handler for [OPTIONS](HttpMethod::Options)
+    pub fn options(self, handler: T) -> Self {
+        self.on(HttpMethod::Options, handler)
+    }
+    /// registers the handler for [TRACE](HttpMethod::Trace)
+    pub fn trace(self, handler: T) -> Self {
+        self.on(HttpMethod::Trace, handler)
+    }
+    /// looks up the handler for the given [HttpMethod]. <br>
+    /// A [HEAD](HttpMethod::Head) without an own handler falls back to the [GET](HttpMethod::Get) handler
+    pub fn lookup(&self, method: &HttpMethod) -> Option<&T> {
+        self.handlers.get(method).or_else(|| match method {
+            HttpMethod::Head => self.handlers.get(&HttpMethod::Get),
+            _ => None,
+        })
+    }
+    /// looks if the [HttpMethod] is answered automatically
+    /// because there is no handler for [OPTIONS](HttpMethod::Options)
+    pub fn is_implicit_options(&self, method: &HttpMethod) -> bool {
+        method == &HttpMethod::Options && !self.handlers.contains_key(method)
+    }
+    /// returns all the supported [HttpMethod]s including the implicitly supported
+    /// [HEAD](HttpMethod::Head) (if there is a [GET](HttpMethod::Get)) and [OPTIONS](HttpMethod::Options)
+    pub fn allowed(&self) -> Vec<HttpMethod> {
+        let mut allowed: Vec<HttpMethod> = self.handlers.keys().copied().collect();
+        if self.handlers.contains_key(&HttpMethod::Get) {
+            allowed.push(HttpMethod::Head);
+        }
+        allowed.push(HttpMethod::Options);
+        allowed.sort();
+        allowed.dedup();
+        allowed
+    }
+    /// creates the [Response] for an implicit [OPTIONS](HttpMethod::Options) request
+    /// with Status No Content and the Allow header
+    pub fn options_response(&self) -> Response {
+        let mut resp = resp_presets::from_status(no_content());
+        resp.add_header((String::from(ALLOW), resp_presets::join_methods(&self.allowed())));
+        resp
+    }
+    /// creates the Method Not Allowed [Response] listing the [allowed](MethodMap::allowed) methods
+    pub fn method_not_allowed(&self) -> Response {
+        resp_presets::method_not_allowed(&self.allowed())
+    }
+}
+
+impl<T> Default for MethodMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A list of path patterns with a [MethodMap] each for routing by path and method. <br>
+/// A pattern segment `{name}` matches any single segment and a trailing `*` matches the rest of the path.
+/// The first matching pattern wins
+///
+/// Example:
+/// ```
+/// use whdp::{HttpMethod, MethodMap, PathMap};
+///
+/// let routes = PathMap::new()
+///     .route("/users", MethodMap::new().get("list").post("create"))
+///     .route("/users/{id}", MethodMap::new().get("read").delete("delete"));
+///
+/// let methods = routes.find("/users/42").unwrap();
+/// assert_eq!(methods.lookup(&HttpMethod::Delete), Some(&"delete"));
+/// assert!(routes.find("/groups").is_none());
+/// ```
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub struct PathMap<T> {
+    routes: Vec<(String, MethodMap<T>)>,
+}
+
+impl<T> PathMap<T> {
+    /// creates a new and empty PathMap
+    pub const fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+    /// registers the [MethodMap] for the path pattern
+    pub fn route(mut self, pattern: &str, methods: MethodMap<T>) -> Self {
+        self.routes.push((String::from(pattern), methods));
+        self
+    }
+    /// finds the [MethodMap] of the first pattern matching the path (the query is ignored)
+    pub fn find(&self, path: &str) -> Option<&MethodMap<T>> {
+        self.routes
+            .iter()
+            .find(|(pattern, _)| matches_pattern(pattern, path))
+            .map(|(_, methods)| methods)
+    }
+    /// returns the [allowed](MethodMap::allowed) methods of every pattern
+    pub fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            resources: self
+                .routes
+                .iter()
+                .map(|(pattern, methods)| (pattern.clone(), methods.allowed()))
+                .collect(),
+        }
+    }
+}
+
+impl<T> Default for PathMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The methods supported per path pattern and by the whole server (see [PathMap::capabilities])
+#[derive(Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub struct Capabilities {
+    resources: Vec<(String, Vec<HttpMethod>)>,
+}
+
+impl Capabilities {
+    /// returns the path patterns with their methods
+    pub fn get_resources(&self) -> &[(String, Vec<HttpMethod>)] {
+        &self.resources
+    }
+    /// returns the methods of the first pattern matching the path
+    /// or [None] if no pattern matches
+    pub fn allowed_for(&self, path: &str) -> Option<&[HttpMethod]> {
+        self.resources
+            .iter()
+            .find(|(pattern, _)| matches_pattern(pattern, path))
+            .map(|(_, methods)| methods.as_slice())
+    }
+    /// returns the union of the methods of all patterns (always including [OPTIONS](HttpMethod::Options))
+    pub fn allowed(&self) -> Vec<HttpMethod> {
+        let mut allowed: BTreeSet<HttpMethod> = self
+            .resources
+            .iter()
+            .flat_map(|(_, methods)| methods.iter().copied())
+            .collect();
+        allowed.insert(HttpMethod::Options);
+        allowed.into_iter().collect()
+    }
+}
+
+/// answers an [OPTIONS](HttpMethod::Options) Request with the [Capabilities]. <br>
+/// `OPTIONS *` gets the methods of the whole server, a path the methods of the matching pattern
+/// (both with Status No Content and the Allow header) and a path without pattern Not Found.
+/// Returns [None] for every other [HttpMethod]
+pub fn handle_options(req: &Request, caps: &Capabilities) -> Option<Response> {
+    if req.get_method() != &HttpMethod::Options {
+        return None;
+    }
+    let allowed = if req.get_uri() == ASTERISK {
+        caps.allowed()
+    } else {
+        match caps.allowed_for(req.get_uri()) {
+            Some(methods) => {
+                let mut methods = methods.to_vec();
+                if !methods.contains(&HttpMethod::Options) {
+                    methods.push(HttpMethod::Options);
+                }
+                methods
+            }
+            None => return Some(resp_presets::not_found("")),
+        }
+    };
+    let mut resp = resp_presets::from_status(no_content());
+    resp.add_header((String::from(ALLOW), resp_presets::join_methods(&allowed)));
+    Some(resp)
+}
+
+fn matches_pattern(pattern: &str, path: &str) -> bool {
+    let target = Target::split(path);
+    let mut patterns = pattern.split(PATH_DELIMITER);
+    let mut segments = target.get_path().split(PATH_DELIMITER);
+    loop {
+        match (patterns.next(), segments.next()) {
+            (Some(ASTERISK), _) => return patterns.next().is_none(),
+            (Some(expected), Some(segment)) => {
+                let is_param = expected.starts_with(PARAM_START) && expected.ends_with(PARAM_END);
+                if !(is_param && !segment.is_empty() || expected == segment) {
+                    return false;
+                }
+            }
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{handle_options, HttpMethod, MethodMap, PathMap, Request};
+    use crate::router::matches_pattern;
+
+    #[test]
+    fn head_fallback() {
+        let map = MethodMap::new().get(1).post(2);
+        assert_eq!(map.lookup(&HttpMethod::Head), Some(&1));
+        assert_eq!(map.lookup(&HttpMethod::Post), Some(&2));
+        assert_eq!(map.lookup(&HttpMethod::Delete), None);
+        let map = map.head(3);
+        assert_eq!(map.lookup(&HttpMethod::Head), Some(&3));
+    }
+
+    #[test]
+    fn allowed() {
+        let map = MethodMap::new().get(()).put(());
+        assert_eq!(
+            map.allowed(),
+            vec![HttpMethod::Get, HttpMethod::Put, HttpMethod::Head, HttpMethod::Options]
+        );
+        assert!(map.is_implicit_options(&HttpMethod::Options));
+        let resp = map.options_response();
+        assert_eq!(resp.get_header("Allow").unwrap(), "GET, PUT, HEAD, OPTIONS");
+        assert_eq!(map.method_not_allowed().get_status().get_code(), &405);
+    }
+
+    #[test]
+    fn patterns() {
+        assert!(matches_pattern("/users/{id}", "/users/42?full=true"));
+        assert!(!matches_pattern("/users/{id}", "/users/"));
+        assert!(!matches_pattern("/users/{id}", "/users/42/posts"));
+        assert!(matches_pattern("/static/*", "/static/css/app.css"));
+        assert!(matches_pattern("/", "/"));
+        assert!(!matches_pattern("/", "/a"));
+    }
+
+    fn capabilities() -> crate::Capabilities {
+        PathMap::new()
+            .route("/users", MethodMap::new().get(()).post(()))
+            .route("/users/{id}", MethodMap::new().put(()).delete(()))
+            .capabilities()
+    }
+
+    #[test]
+    fn options_star() {
+        let req = Request::try_from("OPTIONS * HTTP/1.1\nHost: a\n\n").unwrap();
+        let resp = handle_options(&req, &capabilities()).unwrap();
+        assert_eq!(resp.get_status().get_code(), &204);
+        assert_eq!(resp.get_header("Allow").unwrap(), "POST, GET, PUT, DELETE, HEAD, OPTIONS");
+    }
+
+    #[test]
+    fn options_path() {
+        let req = Request::try_from("OPTIONS /users/7 HTTP/1.1\nHost: a\n\n").unwrap();
+        let resp = handle_options(&req, &capabilities()).unwrap();
+        assert_eq!(resp.get_status().get_code(), &204);
+        assert_eq!(resp.get_header("Allow").unwrap(), "PUT, DELETE, OPTIONS");
+
+        let req = Request::try_from("OPTIONS /groups HTTP/1.1\nHost: a\n\n").unwrap();
+        assert_eq!(handle_options(&req, &capabilities()).unwrap().get_status().get_code(), &404);
+
+        let req = Request::try_from("GET /users HTTP/1.1\nHost: a\n\n").unwrap();
+        assert!(handle_options(&req, &capabilities()).is_none());
+    }
+}
@@ -0,0 +1,114 @@
+const X_CONTENT_TYPE_OPTIONS: &str = "X-Content-Type-Options";
+const X_FRAME_OPTIONS: &str = "X-Frame-Options";
+const STRICT_TRANSPORT_SECURITY: &str = "Strict-Transport-Security";
+const CONTENT_SECURITY_POLICY: &str = "Content-Security-Policy";
+const REFERRER_POLICY: &str = "Referrer-Policy";
+
+/// Struct for the standard bundle of security headers set by
+/// [set_security_headers](crate::Response::set_security_headers). <br>
+/// The [Default] is opinionated and strict, headers left [None] aren't set:
+///
+/// | Header | Default |
+/// |---|---|
+/// | X-Content-Type-Options | `nosniff` |
+/// | X-Frame-Options | `DENY` |
+/// | Strict-Transport-Security | `max-age=31536000; includeSubDomains` |
+/// | Content-Security-Policy | `default-src 'self'` |
+/// | Referrer-Policy | `no-referrer` |
+///
+/// Example:
+/// ```
+/// use whdp::{resp_presets, SecurityHeaders};
+///
+/// let mut resp = resp_presets::ok("Hello");
+/// resp.set_security_headers(SecurityHeaders {
+///     frame_options: Some(String::from("SAMEORIGIN")),
+///     strict_transport_security: None,
+///     ..SecurityHeaders::default()
+/// });
+/// assert_eq!(resp.get_header("X-Frame-Options").unwrap(), "SAMEORIGIN");
+/// assert!(resp.get_header("Strict-Transport-Security").is_none());
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct SecurityHeaders {
+    /// the value of the X-Content-Type-Options header
+    pub content_type_options: Option<String>,
+    /// the value of the X-Frame-Options header
+    pub frame_options: Option<String>,
+    /// the value of the Strict-Transport-Security header
+    pub strict_transport_security: Option<String>,
+    /// the value of the Content-Security-Policy header
+    pub content_security_policy: Option<String>,
+    /// the value of the Referrer-Policy header
+    pub referrer_policy: Option<String>,
+}
+
+impl SecurityHeaders {
+    /// creates new SecurityHeaders without any header
+    pub const fn none() -> Self {
+        Self {
+            content_type_options: None,
+            frame_options: None,
+            strict_transport_security: None,
+            content_security_policy: None,
+            referrer_policy: None,
+        }
+    }
+    /// returns the names and values of the headers that are set
+    pub(crate) fn headers(self) -> impl Iterator<Item = (String, String)> {
+        [
+            (X_CONTENT_TYPE_OPTIONS, self.content_type_options),
+            (X_FRAME_OPTIONS, self.frame_options),
+            (STRICT_TRANSPORT_SECURITY, self.strict_transport_security),
+            (CONTENT_SECURITY_POLICY, self.content_security_policy),
+            (REFERRER_POLICY, self.referrer_policy),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|value| (String::from(key), value)))
+    }
+}
+
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        Self {
+            content_type_options: Some(String::from("nosniff")),
+            frame_options: Some(String::from("DENY")),
+            strict_transport_security: Some(String::from("max-age=31536000; includeSubDomains")),
+            content_security_policy: Some(String::from("default-src 'self'")),
+            referrer_policy: Some(String::from("no-referrer")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{resp_presets, SecurityHeaders};
+
+    #[test]
+    fn defaults() {
+        let mut resp = resp_presets::ok("Hello");
+        resp.set_security_headers(SecurityHeaders::default());
+        assert_eq!(resp.get_header("X-Content-Type-Options").unwrap(), "nosniff");
+        assert_eq!(resp.get_header("X-Frame-Options").unwrap(), "DENY");
+        assert_eq!(resp.get_header("Strict-Transport-Security").unwrap(), "max-age=31536000; includeSubDomains");
+        assert_eq!(resp.get_header("Content-Security-Policy").unwrap(), "default-src 'self'");
+        assert_eq!(resp.get_header("Referrer-Policy").unwrap(), "no-referrer");
+    }
+
+    #[test]
+    fn customized() {
+        let mut resp = resp_presets::ok("Hello");
+        let before = resp.get_headers().len();
+        resp.set_security_headers(SecurityHeaders::none());
+        assert_eq!(resp.get_headers().len(), before);
+        resp.add_header((String::from("X-Frame-Options"), String::from("DENY")));
+        resp.set_security_headers(SecurityHeaders {
+            frame_options: Some(String::from("SAMEORIGIN")),
+            content_security_policy: Some(String::from("default-src 'none'")),
+            ..SecurityHeaders::none()
+        });
+        assert_eq!(resp.get_headers().len(), before + 2);
+        assert_eq!(resp.get_header("X-Frame-Options").unwrap(), "SAMEORIGIN");
+        assert_eq!(resp.get_header("Content-Security-Policy").unwrap(), "default-src 'none'");
+    }
+}
@@ -0,0 +1,515 @@
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use crate::error::HttpParseError;
+use crate::error::ParseErrorKind::Req;
+use crate::host_guard::HostGuard;
+use crate::options::ParseOptions;
+use crate::request::{BODY_FORBIDDEN, Request};
+use crate::response::{resp_presets, Response};
+use crate::method::BodyExpectation;
+use crate::stream::{BodyFraming, check_body_size, check_head_size, chunked_length, read_chunked_body, sniff_http};
+use crate::util::{check_transfer_codings, CONNECTION};
+
+const CHUNK_SIZE: usize = 4096;
+const DEFAULT_BUFFER_CAP: usize = 64 * 1024;
+const DEFAULT_MAX_HEAD_SIZE: usize = 64 * 1024;
+const CLOSE: &str = "close";
+const CONNECTION_CLOSED: &str = "The connection was closed before the message was complete";
+const HEADER_TIMEOUT: &str = "The header block wasn't received in time";
+const IDLE_TIMEOUT: &str = "The connection was idle for too long";
+
+/// Trait for a connection [serve_connection] reads the Requests from and writes the Responses to
+/// (and the stream readers like [from_reader_with](Request::from_reader_with) read from). <br>
+/// The timeouts of the [ParseOptions] are enforced with [set_read_timeout](Connection::set_read_timeout),
+/// which does nothing by default, so any other [Read] + [Write] type (like an in-memory stream)
+/// only needs an empty impl. A header deadline that has already passed is still detected before every read
+pub trait Connection: Read + Write {
+    /// sets the timeout of the following reads ([None] blocks indefinitely)
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> std::io::Result<()> {
+        let _ = timeout;
+        Ok(())
+    }
+}
+
+impl Connection for TcpStream {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> std::io::Result<()> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+}
+
+impl<C: Connection + ?Sized> Connection for &mut C {
+    fn set_read_timeout(&mut self, timeout: Option<Duration>) -> std::io::Result<()> {
+        (**self).set_read_timeout(timeout)
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl Connection for crate::MockStream {}
+
+/// Serves all the Requests of a (keep-alive) connection with the handler. <br>
+/// The header and idle timeouts of the [ParseOptions] protect against slow clients:
+/// hitting either one answers with Request Timeout and closes the connection,
+/// other parsing errors are answered according to [from_error](resp_presets::from_error). <br>
+/// A body is read according to its [framing](Request::body_framing), a chunked body
+/// is rejected for methods where a body is [Forbidden](crate::BodyExpectation::Forbidden). <br>
+/// A head larger than the [max head size](ParseOptions::with_max_head_size) (64 KiB by default)
+/// is answered with Request Header Fields Too Large. <br>
+/// A single buffer is reused for all the Requests of the connection and shrunk back to the
+/// [buffer cap](ParseOptions::with_buffer_cap) (64 KiB by default) after an unusually large message. <br>
+/// Returns [Ok] when the connection was closed regularly
+pub fn serve_connection<S: Connection, F: FnMut(Request) -> Response>(
+    stream: S,
+    options: &ParseOptions,
+    handler: F,
+) -> Result<(), HttpParseError> {
+    serve_connection_with_guard(stream, options, None, handler)
+}
+
+/// Serves the connection like [serve_connection], but checks every Request with the [HostGuard] first. <br>
+/// A rejected Request is answered with Misdirected Request (for a host that isn't allowed)
+/// or Bad Request and the connection gets closed
+pub fn serve_connection_with_guard<S: Connection, F: FnMut(Request) -> Response>(
+    mut stream: S,
+    options: &ParseOptions,
+    guard: Option<&HostGuard>,
+    mut handler: F,
+) -> Result<(), HttpParseError> {
+    let cap = options.get_buffer_cap().unwrap_or(DEFAULT_BUFFER_CAP);
+    let mut buffer: Vec<u8> = Vec::with_capacity(CHUNK_SIZE.min(cap));
+    loop {
+        let checked = read_request(&mut stream, &mut buffer, options).and_then(|req| match (req, guard) {
+            (Some(req), Some(guard)) => guard.check(&req).map(|_| Some(req)),
+            (req, _) => Ok(req),
+        });
+        let req = match checked {
+            Ok(Some(req)) => req,
+            Ok(None) => return Ok(()),
+            Err(err) => {
+                let mut resp = resp_presets::from_error(&err);
+                resp.add_header((String::from(CONNECTION), String::from(CLOSE)));
+                let _ = resp.write_to(&mut stream);
+                return Err(err);
+            }
+        };
+        let keep_alive = req.is_keep_alive();
+        let mut resp = handler(req);
+        let close = !keep_alive
+            || resp
+                .get_header_ignore_case(CONNECTION)
+                .is_some_and(|value| value.eq_ignore_ascii_case(CLOSE));
+        if close {
+            resp.add_header((String::from(CONNECTION), String::from(CLOSE)));
+        }
+        resp.write_to(&mut stream)?;
+        if close {
+            return Ok(());
+        }
+        shrink_buffer(&mut buffer, cap);
+    }
+}
+
+/// gives the memory of an unusually large message back,
+/// but keeps the bytes of a pipelined Request that were already read
+fn shrink_buffer(buffer: &mut Vec<u8>, cap: usize) {
+    if buffer.capacity() > cap {
+        buffer.shrink_to(cap.max(buffer.len()));
+    }
+}
+
+fn read_request<S: Connection>(
+    stream: &mut S,
+    buffer: &mut Vec<u8>,
+    options: &ParseOptions,
+) -> Result<Option<Request>, HttpParseError> {
+    let deadline = options.get_header_timeout().map(|timeout| Instant::now() + timeout);
+    let max_head_size = Some(options.get_max_head_size().unwrap_or(DEFAULT_MAX_HEAD_SIZE));
+    let mut scanned = 0;
+    let head_end = loop {
+        sniff_http(buffer)?;
+        if let Some(end) = find_head_end(buffer, &mut scanned) {
+            break end;
+        }
+        check_head_size(buffer.len(), max_head_size)?;
+        if read_chunk(stream, buffer, deadline, options.get_idle_timeout())? == 0 {
+            if buffer.is_empty() {
+                return Ok(None);
+            }
+            // old clients may close the connection without sending the empty line
+            break buffer.len();
+        }
+    };
+    check_head_size(head_end, max_head_size)?;
+    let head = std::str::from_utf8(&buffer[..head_end]).map_err(|err| HttpParseError::request(err.to_string()))?;
+    let mut req = Request::parse_with(head, options)?;
+    buffer.drain(..head_end);
+    check_transfer_codings(req.get_headers())?;
+    let length = match req.body_framing() {
+        BodyFraming::ContentLength(length) => {
+            let length = usize::try_from(length).map_err(|err| HttpParseError::length(err.to_string()))?;
+            check_body_size(length, options.get_max_body_size())?;
+            length
+        }
+        BodyFraming::Chunked => {
+            if req.body_expectation() == BodyExpectation::Forbidden {
+                return Err(HttpParseError::request(format!("{} {}", BODY_FORBIDDEN, req.get_method())));
+            }
+            let body = read_chunked_request_body(stream, buffer, options)?;
+            req.set_body(&body);
+            req.check_body_soft_limit(options);
+            return Ok(Some(req));
+        }
+        BodyFraming::None | BodyFraming::CloseDelimited => 0,
+        BodyFraming::Invalid => return Err(req.invalid_framing()),
+    };
+    while buffer.len() < length {
+        if read_chunk(stream, buffer, None, options.get_idle_timeout())? == 0 {
+            return Err(HttpParseError::request(CONNECTION_CLOSED));
+        }
+    }
+    let body: Vec<u8> = buffer.drain(..length).collect();
+    let body = String::from_utf8(body).map_err(|err| HttpParseError::request(err.to_string()))?;
+    req.set_body(&body);
+    req.check_body_soft_limit(options);
+    Ok(Some(req))
+}
+
+/// reads the chunked body of a Request without a Content-Length from the buffer
+/// (and the stream until it is complete) and decodes it
+fn read_chunked_request_body<S: Connection>(
+    stream: &mut S,
+    buffer: &mut Vec<u8>,
+    options: &ParseOptions,
+) -> Result<String, HttpParseError> {
+    let length = loop {
+        if let Some(length) = chunked_length(buffer, options.get_max_body_size(), Req)? {
+            break length;
+        }
+        check_body_size(buffer.len(), options.get_max_body_size())?;
+        if read_chunk(stream, buffer, None, options.get_idle_timeout())? == 0 {
+            return Err(HttpParseError::request(CONNECTION_CLOSED));
+        }
+    };
+    let chunked: Vec<u8> = buffer.drain(..length).collect();
+    read_chunked_body(&mut chunked.as_slice(), options.get_max_body_size(), Req)
+}
+
+/// returns the end of the empty line that ends the head. <br>
+/// The search starts where the previous one stopped, because the empty line
+/// can only end in a byte that wasn't scanned yet
+fn find_head_end(buffer: &[u8], scanned: &mut usize) -> Option<usize> {
+    let start = *scanned;
+    *scanned = buffer.len();
+    (start..buffer.len())
+        .find(|idx| {
+            let line = &buffer[..=*idx];
+            line.ends_with(b"\n\n") || line.ends_with(b"\r\n\r\n")
+        })
+        .map(|idx| idx + 1)
+}
+
+fn read_chunk<S: Connection>(
+    stream: &mut S,
+    buffer: &mut Vec<u8>,
+    deadline: Option<Instant>,
+    idle: Option<Duration>,
+) -> Result<usize, HttpParseError> {
+    let timeout = next_read_timeout(deadline, idle).map_err(HttpParseError::timeout)?;
+    stream
+        .set_read_timeout(timeout)
+        .map_err(|err| HttpParseError::request(err.to_string()))?;
+    // read directly into the spare capacity instead of copying from a temporary chunk
+    let filled = buffer.len();
+    buffer.resize(filled + CHUNK_SIZE, 0);
+    let result = stream.read(&mut buffer[filled..]);
+    buffer.truncate(filled + *result.as_ref().unwrap_or(&0));
+    match result {
+        Ok(read) => Ok(read),
+        Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+            Err(HttpParseError::timeout(timeout_message(deadline)))
+        }
+        Err(err) => Err(HttpParseError::request(err.to_string())),
+    }
+}
+
+/// returns the timeout of the next read: the idle timeout shortened to the time left until the header deadline
+/// (the message of the header timeout if the deadline has already passed)
+fn next_read_timeout(deadline: Option<Instant>, idle: Option<Duration>) -> Result<Option<Duration>, &'static str> {
+    let Some(deadline) = deadline else {
+        return Ok(idle);
+    };
+    let remaining = deadline
+        .checked_duration_since(Instant::now())
+        .filter(|remaining| !remaining.is_zero())
+        .ok_or(HEADER_TIMEOUT)?;
+    Ok(Some(idle.map_or(remaining, |idle| idle.min(remaining))))
+}
+
+/// returns the message of the timeout a read ran into
+fn timeout_message(deadline: Option<Instant>) -> &'static str {
+    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+        HEADER_TIMEOUT
+    } else {
+        IDLE_TIMEOUT
+    }
+}
+
+/// [Read] adapter that enforces the header and idle timeouts of the [ParseOptions]
+/// on every read from the connection (like [serve_connection] does). <br>
+/// A read that runs into a timeout fails with [TimedOut](ErrorKind::TimedOut) and the message of the timeout.
+/// Without any timeout the read timeout of the connection is left as it is
+pub(crate) struct TimedReader<S: Connection> {
+    stream: S,
+    header_timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    idle: Option<Duration>,
+}
+
+impl<S: Connection> TimedReader<S> {
+    /// creates the reader with a running header deadline
+    pub(crate) fn new(stream: S, options: &ParseOptions) -> Self {
+        let mut reader = Self {
+            stream,
+            header_timeout: options.get_header_timeout(),
+            deadline: None,
+            idle: options.get_idle_timeout(),
+        };
+        reader.start_head();
+        reader
+    }
+    /// starts the header deadline for the next head
+    pub(crate) fn start_head(&mut self) {
+        self.deadline = self.header_timeout.map(|timeout| Instant::now() + timeout);
+    }
+    /// ends the header deadline once the head was read, the body only has the idle timeout
+    pub(crate) fn head_read(&mut self) {
+        self.deadline = None;
+    }
+    pub(crate) fn get_mut(&mut self) -> &mut S {
+        &mut self.stream
+    }
+}
+
+impl<S: Connection> Read for TimedReader<S> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.deadline.is_none() && self.idle.is_none() {
+            return self.stream.read(buf);
+        }
+        let timeout = next_read_timeout(self.deadline, self.idle)
+            .map_err(|msg| std::io::Error::new(ErrorKind::TimedOut, msg))?;
+        self.stream.set_read_timeout(timeout)?;
+        self.stream.read(buf).map_err(|err| match err.kind() {
+            ErrorKind::WouldBlock | ErrorKind::TimedOut => {
+                std::io::Error::new(ErrorKind::TimedOut, timeout_message(self.deadline))
+            }
+            _ => err,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use crate::{HostGuard, HttpParseError, MockStream, ParseErrorKind, ParseOptions, Request, resp_presets, Response, serve_connection, serve_connection_with_guard};
+    use crate::server::{HEADER_TIMEOUT, IDLE_TIMEOUT};
+    use crate::server::{find_head_end, shrink_buffer};
+
+    fn serve_mock(options: ParseOptions, mut stream: MockStream) -> (Result<(), HttpParseError>, String) {
+        let result = serve_connection(&mut stream, &options, |req| resp_presets::ok(req.get_uri()));
+        (result, stream.written_string())
+    }
+
+    fn serve(options: ParseOptions, client: fn(TcpStream) -> String) -> (Result<(), HttpParseError>, String, Duration) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = thread::spawn(move || client(TcpStream::connect(addr).unwrap()));
+        let (stream, _) = listener.accept().unwrap();
+        let start = Instant::now();
+        let result = serve_connection(stream, &options, |req| resp_presets::ok(req.get_uri()));
+        let elapsed = start.elapsed();
+        (result, client.join().unwrap(), elapsed)
+    }
+
+    fn read_all(mut stream: TcpStream) -> String {
+        let mut string = String::new();
+        let _ = stream.read_to_string(&mut string);
+        string
+    }
+
+    #[test]
+    fn chunked_without_content_length() {
+        let stream = MockStream::new()
+            .with_read(b"POST /a HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nbo")
+            .with_read(b"dy\r\n0\r\n\r\nGET /b HTTP/1.1\r\nConnection: close\r\n\r\n");
+        let (result, answer) = serve_mock(ParseOptions::new(), stream);
+        assert!(result.is_ok());
+        assert!(answer.contains("\r\n\r\n/a"));
+        assert!(answer.ends_with("\r\n\r\n/b"));
+
+        let stream = MockStream::new().with_read(b"HEAD / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nbody\r\n0\r\n\r\n");
+        let (result, answer) = serve_mock(ParseOptions::new(), stream);
+        assert!(result.is_err());
+        assert!(answer.starts_with("HTTP/1.1 400"));
+    }
+
+    #[test]
+    fn keep_alive() {
+        let stream = MockStream::new().with_read(b"GET /a HTTP/1.1\r\n\r\nGET /b HTTP/1.1\r\nConnection: close\r\n\r\n");
+        let (result, answer) = serve_mock(ParseOptions::new(), stream);
+        assert!(result.is_ok());
+        assert!(answer.contains("\r\n\r\n/a"));
+        assert!(answer.ends_with("\r\n\r\n/b"));
+    }
+
+    #[test]
+    fn buffer_cap() {
+        let mut buffer = Vec::with_capacity(1 << 20);
+        buffer.extend_from_slice(b"GET /next HTTP/1.1\r\n");
+        shrink_buffer(&mut buffer, 1024);
+        assert!(buffer.capacity() < 1 << 20);
+        assert_eq!(buffer, b"GET /next HTTP/1.1\r\n");
+
+        let body = "a".repeat(100_000);
+        let big = format!("POST /big HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+        let stream = MockStream::new()
+            .with_read(big.as_bytes())
+            .with_read(b"GET /small HTTP/1.1\r\nConnection: close\r\n\r\n");
+        let (result, answer) = serve_mock(ParseOptions::new().with_buffer_cap(1024), stream);
+        assert!(result.is_ok());
+        assert!(answer.contains("\r\n\r\n/big"));
+        assert!(answer.ends_with("\r\n\r\n/small"));
+    }
+
+    #[test]
+    fn http_one_without_host() {
+        let (result, answer) = serve_mock(ParseOptions::new(), MockStream::new().with_read(b"GET /health HTTP/1.0\r\n\r\n"));
+        assert!(result.is_ok());
+        assert!(answer.contains("Connection: close\r\n"));
+        assert!(answer.ends_with("\r\n\r\n/health"));
+
+        // the client closes the connection without sending the empty line
+        let (result, answer) = serve_mock(ParseOptions::new(), MockStream::new().with_read(b"GET /health HTTP/1.0\r\n"));
+        assert!(result.is_ok());
+        assert!(answer.ends_with("/health"));
+
+        // a real socket for the full stream path
+        let (result, answer, _) = serve(ParseOptions::new(), |mut stream| {
+            stream.write_all(b"GET /health HTTP/1.0\r\n\r\n").unwrap();
+            read_all(stream)
+        });
+        assert!(result.is_ok());
+        assert!(answer.ends_with("\r\n\r\n/health"));
+    }
+
+    #[test]
+    fn head_size() {
+        let mut scanned = 0;
+        assert_eq!(find_head_end(b"GET / HTTP/1.1\r\n\r", &mut scanned), None);
+        assert_eq!(scanned, 17);
+        assert_eq!(find_head_end(b"GET / HTTP/1.1\r\n\r\nbody", &mut scanned), Some(18));
+        assert_eq!(find_head_end(b"GET / HTTP/1.1\n\nGET / HTTP/1.1\r\n\r\n", &mut 0), Some(16));
+
+        let stream = MockStream::new().with_read(b"GET / HTTP/1.1\r\n").with_read(format!("X-Big: {}\r\n\r\n", "a".repeat(200)).as_bytes());
+        let (result, answer) = serve_mock(ParseOptions::new().with_max_head_size(128), stream);
+        assert_eq!(result.unwrap_err().get_kind(), &ParseErrorKind::HeaderFieldsTooLarge);
+        assert!(answer.starts_with("HTTP/1.1 431 Request Header Fields Too Large"));
+
+        // without the empty line the client can't grow the buffer beyond the default limit
+        let mut stream = MockStream::new().with_read(b"GET / HTTP/1.1\r\n");
+        for _ in 0..100 {
+            stream = stream.with_read(&[b'a'; 1024]);
+        }
+        let (result, answer) = serve_mock(ParseOptions::new(), stream);
+        assert_eq!(result.unwrap_err().get_kind(), &ParseErrorKind::HeaderFieldsTooLarge);
+        assert!(answer.starts_with("HTTP/1.1 431"));
+    }
+
+    #[test]
+    fn header_timeout() {
+        let options = ParseOptions::new().with_header_timeout(Duration::from_millis(300));
+        let (result, answer, elapsed) = serve(options, |mut stream| {
+            for byte in b"GET / HTTP/1.1\r\nHost: slowloris\r\n\r\n" {
+                if stream.write_all(&[*byte]).is_err() {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            read_all(stream)
+        });
+        assert_eq!(result.unwrap_err().get_kind(), &ParseErrorKind::Timeout);
+        assert!(answer.starts_with("HTTP/1.1 408 Request Timeout"));
+        assert!(elapsed < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn idle_timeout() {
+        let options = ParseOptions::new().with_idle_timeout(Duration::from_millis(200));
+        let (result, answer, elapsed) = serve(options, |mut stream| {
+            stream.write_all(b"GET / HT").unwrap();
+            read_all(stream)
+        });
+        assert_eq!(result.unwrap_err().get_kind(), &ParseErrorKind::Timeout);
+        assert!(answer.starts_with("HTTP/1.1 408 Request Timeout"));
+        assert!(elapsed < Duration::from_secs(2));
+
+        let (result, answer) = serve_mock(options, MockStream::new().with_read(b"GET / HT").with_would_block());
+        assert_eq!(result.unwrap_err().get_kind(), &ParseErrorKind::Timeout);
+        assert!(answer.starts_with("HTTP/1.1 408 Request Timeout"));
+    }
+
+    #[test]
+    fn stream_reader_timeouts() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(b"POST / HTTP/1.1\r\nContent-Length: 4\r\n\r\nbo").unwrap();
+            read_all(stream)
+        });
+        let (mut stream, _) = listener.accept().unwrap();
+        let options = ParseOptions::new().with_idle_timeout(Duration::from_millis(200));
+        let start = Instant::now();
+        let err = Request::from_reader_with(&mut stream, &options).unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(2));
+        drop(stream);
+        client.join().unwrap();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Timeout);
+        assert_eq!(err.get_msg(), Some(IDLE_TIMEOUT));
+
+        let options = ParseOptions::new().with_header_timeout(Duration::from_millis(50));
+        let mut stream = MockStream::new()
+            .with_read(b"HTTP/1.1 200 OK\r\n")
+            .with_delay(Duration::from_millis(100))
+            .with_read(b"Content-Length: 2\r\n")
+            .with_read(b"\r\nok");
+        let err = Response::from_stream(&mut stream, &options).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Timeout);
+        assert_eq!(err.get_msg(), Some(HEADER_TIMEOUT));
+        // the header deadline ends with the head
+        let mut stream = MockStream::new()
+            .with_read(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n")
+            .with_delay(Duration::from_millis(100))
+            .with_read(b"ok");
+        assert_eq!(Response::from_stream(&mut stream, &options).unwrap().get_body(), "ok");
+    }
+
+    #[test]
+    fn host_guard() {
+        let mut stream = MockStream::new()
+            .with_read(b"GET /a HTTP/1.1\r\nHost: example.com\r\n\r\nGET /b HTTP/1.1\r\nHost: evil.com\r\n\r\n");
+        let guard = HostGuard::new().with_allowed("example.com");
+        let result = serve_connection_with_guard(&mut stream, &ParseOptions::new(), Some(&guard), |req| {
+            resp_presets::ok(req.get_uri())
+        });
+        let answer = stream.written_string();
+        assert_eq!(result.unwrap_err().get_kind(), &ParseErrorKind::HostNotAllowed);
+        assert!(answer.starts_with("HTTP/1.1 200 OK"));
+        assert!(answer.contains("HTTP/1.1 421 Misdirected Request"));
+        assert!(!answer.contains("/b"));
+    }
+}
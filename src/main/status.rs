@@ -4,9 +4,78 @@ use std::str::FromStr;
 use wjp::{ParseError, Serialize, Values};
 
 use crate::error::HttpParseError;
-use crate::error::ParseErrorKind::Status;
 use crate::util::{Destruct, EMPTY_CHAR, OPTION_WAS_EMPTY};
 
+const INVALID_CODE: &str = "The status code has to have three digits, but was";
+const INVALID_REASON_PHRASE: &str = "The reason phrase mustn't contain CR or LF:";
+const CACHEABLE_BY_DEFAULT: [u16; 11] = [200, 203, 204, 206, 300, 301, 404, 405, 410, 414, 501];
+const CUSTOM_REASON_PHRASE: &str = "Custom HttpStatus";
+/// the canonical reason phrases of the registered status codes
+/// ([RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-15)), the [status_presets] use the same ones
+const REASON_PHRASES: [(u16, &str); 61] = [
+    (100, "Continue"),
+    (101, "Switching Protocols"),
+    (102, "Processing"),
+    (103, "Early Hints"),
+    (200, "OK"),
+    (201, "Created"),
+    (202, "Accepted"),
+    (203, "Non-Authoritative Information"),
+    (204, "No Content"),
+    (205, "Reset Content"),
+    (206, "Partial Content"),
+    (207, "Multi-Status"),
+    (208, "Already Reported"),
+    (226, "IM Used"),
+    (300, "Multiple Choices"),
+    (301, "Moved Permanently"),
+    (302, "Found"),
+    (303, "See Other"),
+    (304, "Not Modified"),
+    (305, "Use Proxy"),
+    (307, "Temporary Redirect"),
+    (308, "Permanent Redirect"),
+    (400, "Bad Request"),
+    (401, "Unauthorized"),
+    (402, "Payment Required"),
+    (403, "Forbidden"),
+    (404, "Not Found"),
+    (405, "Method Not Allowed"),
+    (406, "Not Acceptable"),
+    (407, "Proxy Authentication Required"),
+    (408, "Request Timeout"),
+    (409, "Conflict"),
+    (410, "Gone"),
+    (411, "Length Required"),
+    (412, "Precondition Failed"),
+    (413, "Content Too Large"),
+    (414, "URI Too Long"),
+    (415, "Unsupported Media Type"),
+    (416, "Range Not Satisfiable"),
+    (417, "Expectation Failed"),
+    (421, "Misdirected Request"),
+    (422, "Unprocessable Content"),
+    (423, "Locked"),
+    (424, "Failed Dependency"),
+    (425, "Too Early"),
+    (426, "Upgrade Required"),
+    (428, "Precondition Required"),
+    (429, "Too Many Requests"),
+    (431, "Request Header Fields Too Large"),
+    (451, "Unavailable For Legal Reasons"),
+    (500, "Internal Server Error"),
+    (501, "Not Implemented"),
+    (502, "Bad Gateway"),
+    (503, "Service Unavailable"),
+    (504, "Gateway Timeout"),
+    (505, "HTTP Version Not Supported"),
+    (506, "Variant Also Negotiates"),
+    (507, "Insufficient Storage"),
+    (508, "Loop Detected"),
+    (510, "Not Extended"),
+    (511, "Network Authentication Required"),
+];
+
 /// Struct for HTTP Status Codes
 #[derive(Ord, PartialOrd, Eq, PartialEq, Clone, Hash,Default)]
 pub struct HttpStatus {
@@ -24,13 +93,51 @@ impl HttpStatus {
         self.get_group().eq(group)
     }
     /// returns the HTTP Status Code
-    pub const fn get_code(&self) -> &u16 {
+    pub fn get_code(&self) -> &u16 {
         &self.code
     }
     /// returns the name associated with its Status Code
-    pub const fn get_message(&self) -> &String {
+    pub fn get_message(&self) -> &String {
         &self.message
     }
+    /// looks if a Response with this status may be cached without explicit freshness information
+    /// ([RFC 7231](https://www.rfc-editor.org/rfc/rfc7231#section-6.1)):
+    /// 200, 203, 204, 206, 300, 301, 404, 405, 410, 414 and 501
+    pub fn is_cacheable_by_default(&self) -> bool {
+        CACHEABLE_BY_DEFAULT.contains(&self.code)
+    }
+    /// looks if a Response with this status may have a body, which 1xx, 204 and 304 don't
+    pub(crate) fn allows_body(&self) -> bool {
+        !matches!(self.code, 100..=199 | 204 | 304)
+    }
+    /// returns the status with the reason phrase replaced (the code is kept). <br>
+    /// Returns a [Status](crate::ParseErrorKind::Status) error if the reason phrase contains CR or LF
+    pub fn with_message(mut self, msg: &str) -> Result<Self, HttpParseError> {
+        self.message = String::from(check_reason_phrase(msg)?);
+        Ok(self)
+    }
+    /// returns the status with the code replaced (the reason phrase is kept). <br>
+    /// Returns a [Status](crate::ParseErrorKind::Status) error if the code doesn't have three digits
+    pub fn with_code(mut self, code: u16) -> Result<Self, HttpParseError> {
+        self.code = check_code(code)?;
+        Ok(self)
+    }
+}
+
+/// checks that the status code has three digits
+pub(crate) fn check_code(code: u16) -> Result<u16, HttpParseError> {
+    if !(100..=999).contains(&code) {
+        return Err(HttpParseError::status(format!("{} {}", INVALID_CODE, code)));
+    }
+    Ok(code)
+}
+
+/// checks that the reason phrase doesn't contain CR or LF
+pub(crate) fn check_reason_phrase(msg: &str) -> Result<&str, HttpParseError> {
+    if msg.contains(['\r', '\n']) {
+        return Err(HttpParseError::status(format!("{} {:?}", INVALID_REASON_PHRASE, msg)));
+    }
+    Ok(msg)
 }
 
 impl Destruct for HttpStatus {
@@ -59,7 +166,7 @@ impl TryFrom<(usize, &str)> for HttpStatus {
     type Error = HttpParseError;
     fn try_from(value: (usize, &str)) -> Result<Self, Self::Error> {
         let size = u16::try_from(value.0)
-            .map_err(|err| HttpParseError::from((Status, err.to_string())))?;
+            .map_err(|err| HttpParseError::status(err.to_string()))?;
         Ok(Self::from((size, value.1)))
     }
 }
@@ -68,7 +175,7 @@ impl TryFrom<(isize, &str)> for HttpStatus {
     type Error = HttpParseError;
     fn try_from(value: (isize, &str)) -> Result<Self, Self::Error> {
         let size = usize::try_from(value.0)
-            .map_err(|err| HttpParseError::from((Status, err.to_string())))?;
+            .map_err(|err| HttpParseError::status(err.to_string()))?;
         Self::try_from((size, value.1))
     }
 }
@@ -77,7 +184,7 @@ impl TryFrom<(isize, String)> for HttpStatus {
     type Error = HttpParseError;
     fn try_from(value: (isize, String)) -> Result<Self, Self::Error> {
         let size = usize::try_from(value.0)
-            .map_err(|err| HttpParseError::from((Status, err.to_string())))?;
+            .map_err(|err| HttpParseError::status(err.to_string()))?;
         Self::try_from((size, value.1))
     }
 }
@@ -86,7 +193,7 @@ impl TryFrom<(usize, String)> for HttpStatus {
     type Error = HttpParseError;
     fn try_from(value: (usize, String)) -> Result<Self, Self::Error> {
         let size = u16::try_from(value.0)
-            .map_err(|err| HttpParseError::from((Status, err.to_string())))?;
+            .map_err(|err| HttpParseError::status(err.to_string()))?;
         Ok(Self::from((size, value.1)))
     }
 }
@@ -95,7 +202,7 @@ impl TryFrom<(&str, &str)> for HttpStatus {
     type Error = HttpParseError;
     fn try_from(value: (&str, &str)) -> Result<Self, Self::Error> {
         let code = u16::from_str(value.0)
-            .map_err(|err| HttpParseError::from((Status, err.to_string())))?;
+            .map_err(|err| HttpParseError::status(err.to_string()))?;
         Ok(Self::from((code, value.1)))
     }
 }
@@ -105,34 +212,34 @@ impl FromStr for HttpStatus {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut split = s.split(EMPTY_CHAR);
         let first = split.next()
-            .ok_or(HttpParseError::from((Status, OPTION_WAS_EMPTY)))?;
+            .ok_or(HttpParseError::status(OPTION_WAS_EMPTY))?;
         let second = split.next()
-            .ok_or(HttpParseError::from((Status, OPTION_WAS_EMPTY)))?;
+            .ok_or(HttpParseError::status(OPTION_WAS_EMPTY))?;
         Self::try_from((first, second))
     }
 }
 
 impl TryFrom<usize> for HttpStatus {
     type Error = HttpParseError;
+    /// creates the status with the canonical reason phrase of the code
+    /// (`Custom HttpStatus` for codes that aren't registered)
     fn try_from(value: usize) -> Result<Self, Self::Error> {
-        Ok(match value {
-            100 => status_presets::r#continue(),
-            200 => status_presets::ok(),
-            201 => status_presets::created(),
-            204 => status_presets::no_content(),
-            400 => status_presets::bad_request(),
-            404 => status_presets::not_found(),
-            415 => status_presets::unsupported_media_type(),
-            500 => status_presets::internal_server_error(),
-            501 => status_presets::not_implemented(),
-            _ => HttpStatus::from((value as u16, "Custom HttpStatus"))
-        })
+        let message = REASON_PHRASES
+            .iter()
+            .find(|(code, _)| usize::from(*code) == value)
+            .map_or(CUSTOM_REASON_PHRASE, |(_, message)| message);
+        Ok(HttpStatus::from((value as u16, message)))
     }
 }
 
 impl Display for HttpStatus {
+    /// writes the code and the reason phrase (only the code if the reason phrase is empty)
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} {}", self.code, self.message)
+        if self.message.is_empty() {
+            write!(f, "{}", self.code)
+        } else {
+            write!(f, "{} {}", self.code, self.message)
+        }
     }
 }
 
@@ -184,7 +291,16 @@ impl TryFrom<isize> for HttpStatusGroup {
     type Error = HttpParseError;
     fn try_from(value: isize) -> Result<Self, Self::Error> {
         let value = usize::try_from(value)
-            .map_err(|err| HttpParseError::from((Status, err.to_string())))?;
+            .map_err(|err| HttpParseError::status(err.to_string()))?;
+        Ok(Self::from(value))
+    }
+}
+
+impl TryFrom<&str> for HttpStatusGroup {
+    type Error = HttpParseError;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let value = usize::from_str(value.trim())
+            .map_err(|err| HttpParseError::status(err.to_string()))?;
         Ok(Self::from(value))
     }
 }
@@ -224,6 +340,13 @@ pub mod status_presets {
         HttpStatus::from((100, "Continue"))
     }
 
+    /// preset for the Status code [103]
+    ///
+    /// [103]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/103
+    pub fn early_hints() -> HttpStatus {
+        HttpStatus::from((103, "Early Hints"))
+    }
+
     /// preset for the Status code [200]
     ///
     /// [200]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/200
@@ -259,6 +382,20 @@ pub mod status_presets {
         HttpStatus::from((404, "Not Found"))
     }
 
+    /// preset for the Status code [405]
+    ///
+    /// [405]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/405
+    pub fn method_not_allowed() -> HttpStatus {
+        HttpStatus::from((405, "Method Not Allowed"))
+    }
+
+    /// preset for the Status code [408]
+    ///
+    /// [408]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/408
+    pub fn request_timeout() -> HttpStatus {
+        HttpStatus::from((408, "Request Timeout"))
+    }
+
     /// preset for the Status code [415]
     ///
     /// [415]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/415
@@ -266,6 +403,34 @@ pub mod status_presets {
         HttpStatus::from((415, "Unsupported Media Type"))
     }
 
+    /// preset for the Status code [421]
+    ///
+    /// [421]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/421
+    pub fn misdirected_request() -> HttpStatus {
+        HttpStatus::from((421, "Misdirected Request"))
+    }
+
+    /// preset for the Status code [431]
+    ///
+    /// [431]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/431
+    pub fn request_header_fields_too_large() -> HttpStatus {
+        HttpStatus::from((431, "Request Header Fields Too Large"))
+    }
+
+    /// preset for the Status code [426]
+    ///
+    /// [426]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/426
+    pub fn upgrade_required() -> HttpStatus {
+        HttpStatus::from((426, "Upgrade Required"))
+    }
+
+    /// preset for the Status code [429]
+    ///
+    /// [429]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/429
+    pub fn too_many_requests() -> HttpStatus {
+        HttpStatus::from((429, "Too Many Requests"))
+    }
+
     /// preset for the Status code [500]
     ///
     /// [500]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/500
@@ -279,4 +444,69 @@ pub mod status_presets {
     pub fn not_implemented() -> HttpStatus {
         HttpStatus::from((501, "Not Implemented"))
     }
+
+    /// preset for the Status code [503]
+    ///
+    /// [503]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/503
+    pub fn service_unavailable() -> HttpStatus {
+        HttpStatus::from((503, "Service Unavailable"))
+    }
+
+    /// preset for the Status code [505]
+    ///
+    /// [505]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Status/505
+    pub fn http_version_not_supported() -> HttpStatus {
+        HttpStatus::from((505, "HTTP Version Not Supported"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HttpStatus, HttpStatusGroup, ParseErrorKind, status_presets};
+
+    #[test]
+    fn empty_reason_phrase() {
+        let status = HttpStatus::from((200, ""));
+        assert_eq!(status.to_string(), "200");
+        assert_eq!(status_presets::ok().to_string(), "200 OK");
+    }
+
+    #[test]
+    fn with_code_and_message() {
+        let status = status_presets::not_found().with_message("Gone Fishing").unwrap();
+        assert_eq!(status.to_string(), "404 Gone Fishing");
+        assert_ne!(status, status_presets::not_found());
+        assert_eq!(status.get_code(), status_presets::not_found().get_code());
+        let status = status.with_code(410).unwrap();
+        assert_eq!(status.to_string(), "410 Gone Fishing");
+        assert_eq!(status.get_group(), HttpStatusGroup::ClientError);
+        assert_eq!(status_presets::ok().with_code(99).unwrap_err().get_kind(), &ParseErrorKind::Status);
+        assert!(status_presets::ok().with_code(1000).is_err());
+        assert!(status_presets::ok().with_message("OK\r\nSet-Cookie: a=b").is_err());
+        assert_eq!(status_presets::ok().with_message("").unwrap().to_string(), "200");
+    }
+
+    #[test]
+    fn cacheable_by_default() {
+        assert!(status_presets::ok().is_cacheable_by_default());
+        assert!(HttpStatus::from((501, "")).is_cacheable_by_default());
+        assert!(!status_presets::created().is_cacheable_by_default());
+        assert!(!HttpStatus::from((302, "Found")).is_cacheable_by_default());
+    }
+
+    #[test]
+    fn canonical_reason_phrases() {
+        assert_eq!(HttpStatus::try_from(304).unwrap().to_string(), "304 Not Modified");
+        assert_eq!(HttpStatus::try_from(401).unwrap().to_string(), "401 Unauthorized");
+        assert_eq!(HttpStatus::try_from(299).unwrap().to_string(), "299 Custom HttpStatus");
+    }
+
+    #[test]
+    fn group_from_str() {
+        assert_eq!(HttpStatusGroup::try_from("404"), Ok(HttpStatusGroup::ClientError));
+        assert_eq!(HttpStatusGroup::try_from("200"), Ok(HttpStatusGroup::Successful));
+        assert_eq!(HttpStatusGroup::try_from("999"), Ok(HttpStatusGroup::Unknown));
+        assert_eq!(HttpStatusGroup::try_from("abc").unwrap_err().get_kind(), &ParseErrorKind::Status);
+        assert!(HttpStatusGroup::try_from("-1").is_err());
+    }
 }
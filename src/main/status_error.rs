@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::retry_after::RetryAfter;
+use crate::status::HttpStatus;
+use crate::util::get_header_ignore_case;
+
+const RETRY_AFTER: &str = "Retry-After";
+/// the headers of the Response that are kept inside a [StatusError]
+const KEPT_HEADERS: [&str; 4] = [RETRY_AFTER, "Location", "WWW-Authenticate", "Content-Type"];
+
+/// Error for a [Response](crate::Response) whose status isn't the expected success
+/// (see [success](crate::Response::success) and [error_for_status](crate::Response::error_for_status)). <br>
+/// Keeps the [HttpStatus], the body and the Retry-After, Location,
+/// WWW-Authenticate and Content-Type headers that were present
+///
+/// Example:
+/// ```
+/// use whdp::resp_presets;
+///
+/// let err = resp_presets::too_many_requests("slow down", 30).success().unwrap_err();
+/// assert_eq!(err.get_status().get_code(), &429);
+/// assert_eq!(err.get_body(), "slow down");
+/// assert_eq!(err.to_string(), "HTTP status 429 Too Many Requests");
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct StatusError {
+    status: HttpStatus,
+    headers: BTreeMap<String, String>,
+    body: String,
+}
+
+impl StatusError {
+    /// creates a new StatusError keeping only the headers of [KEPT_HEADERS]
+    pub(crate) fn new(status: HttpStatus, headers: &BTreeMap<String, String>, body: String) -> Self {
+        let headers = headers
+            .iter()
+            .filter(|(key, _)| KEPT_HEADERS.iter().any(|kept| kept.eq_ignore_ascii_case(key)))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        Self { status, headers, body }
+    }
+    /// returns the [HttpStatus] of the Response
+    pub fn get_status(&self) -> &HttpStatus {
+        &self.status
+    }
+    /// returns the kept headers of the Response
+    pub fn get_headers(&self) -> &BTreeMap<String, String> {
+        &self.headers
+    }
+    /// returns the value of a kept header (case-insensitive)
+    pub fn get_header(&self, key: &str) -> Option<&String> {
+        get_header_ignore_case(&self.headers, key)
+    }
+    /// returns the Retry-After header parsed to a [RetryAfter]
+    pub fn get_retry_after(&self) -> Option<RetryAfter> {
+        self.get_header(RETRY_AFTER).and_then(|value| RetryAfter::from_str(value).ok())
+    }
+    /// returns the body of the Response
+    pub fn get_body(&self) -> &String {
+        &self.body
+    }
+    /// returns the body of the Response and drops the rest
+    pub fn into_body(self) -> String {
+        self.body
+    }
+}
+
+impl Display for StatusError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HTTP status {}", self.status)
+    }
+}
+
+impl Error for StatusError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HttpStatus, HttpStatusGroup, resp_presets, Response, RetryAfter};
+
+    fn response(code: u16) -> Response {
+        resp_presets::ok("body").with_status(HttpStatus::try_from(code as usize).unwrap())
+    }
+
+    #[test]
+    fn groups() {
+        assert!(response(200).success().is_ok());
+        assert!(response(204).error_for_status().is_ok());
+        for code in [100, 301, 404, 503] {
+            let err = response(code).success().unwrap_err();
+            assert_eq!(err.get_status().get_code(), &code);
+            assert!(response(code).error_for_status().is_err());
+        }
+        assert!(response(302).success_with(true).is_ok());
+        assert!(response(302).error_for_status_with(true).is_ok());
+        assert!(response(404).success_with(true).is_err());
+        assert!(response(101).success_with(true).is_err());
+
+        let err = response(500).error_for_status().unwrap_err();
+        assert!(err.get_status().has_group(&HttpStatusGroup::ServerError));
+        assert_eq!(err.to_string(), "HTTP status 500 Internal Server Error");
+    }
+
+    #[test]
+    fn keeps_body_and_headers() {
+        let mut resp = resp_presets::too_many_requests("{\"error\":\"quota\"}", 120);
+        resp.add_header((String::from("X-Request-Id"), String::from("abc")));
+        let err = resp.success().unwrap_err();
+        assert_eq!(err.get_body(), "{\"error\":\"quota\"}");
+        assert_eq!(err.get_retry_after(), Some(RetryAfter::Seconds(120)));
+        assert!(err.get_header("x-request-id").is_none());
+        assert_eq!(err.into_body(), "{\"error\":\"quota\"}");
+    }
+}
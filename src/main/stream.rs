@@ -0,0 +1,332 @@
+use std::io::{BufRead, ErrorKind, Read};
+
+use crate::error::{HttpParseError, ParseErrorKind};
+use crate::util::{BOM_NOTE, OPTION_WAS_EMPTY};
+
+const BODY_TOO_LARGE: &str = "The body exceeds the maximum size of";
+const HEAD_TOO_LARGE: &str = "The start line and the headers exceed the maximum size of";
+pub(crate) const BODY_INCOMPLETE: &str = "The input ends before the end of the body";
+pub(crate) const INVALID_CONTENT_LENGTH: &str = "The Content-Length is invalid, so the end of the body is unknown";
+const INVALID_CHUNK_SIZE: &str = "The chunk size is not a valid hex number";
+const CHUNK_TOO_LARGE: &str = "The chunk size exceeds the addressable memory";
+const TLS_HANDSHAKE: &str = "The bytes look like a TLS ClientHello, is this port expecting HTTPS?";
+const NOT_HTTP: &str = "The bytes don't look like a HTTP/1.x request or status line";
+const TLS_RECORD_HANDSHAKE: u8 = 0x16;
+const TLS_MAJOR_VERSION: u8 = 0x03;
+const MAX_START_TOKEN: usize = 32;
+const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+
+/// Enum for how the body of a message is delimited on the wire
+/// ([RFC 7230](https://www.rfc-editor.org/rfc/rfc7230#section-3.3.3)),
+/// see [Request::body_framing](crate::Request::body_framing) and [Response::body_framing](crate::Response::body_framing)
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum BodyFraming {
+    /// the body has exactly this many bytes
+    ContentLength(u64),
+    /// the body is a sequence of chunks ended by a zero-sized chunk
+    Chunked,
+    /// the body extends until the connection gets closed
+    CloseDelimited,
+    /// the framing headers are invalid (like an invalid Content-Length), which is an unrecoverable error
+    /// because the end of the message can't be determined
+    Invalid,
+    /// there is no body at all
+    None,
+}
+
+/// checks that the first bytes look like a HTTP/1.x request or status line,
+/// which starts with a printable token followed by a space (leading empty lines are allowed). <br>
+/// Incomplete input is accepted as long as it could still become a valid start. <br>
+/// A single byte order mark at the very start is skipped
+pub(crate) fn sniff_http(bytes: &[u8]) -> Result<(), HttpParseError> {
+    if !bytes.is_empty() && UTF8_BOM.starts_with(bytes) {
+        return Ok(());
+    }
+    let bytes = bytes.strip_prefix(&UTF8_BOM).unwrap_or(bytes);
+    if bytes.first() == Some(&TLS_RECORD_HANDSHAKE) && bytes.get(1).is_none_or(|byte| *byte == TLS_MAJOR_VERSION) {
+        return Err(HttpParseError::not_http(TLS_HANDSHAKE));
+    }
+    let start = bytes
+        .iter()
+        .position(|byte| *byte != b'\r' && *byte != b'\n')
+        .unwrap_or(bytes.len());
+    if bytes[start..].starts_with(&UTF8_BOM) {
+        return Err(HttpParseError::not_http(format!("{} {}", NOT_HTTP, BOM_NOTE)));
+    }
+    let token = bytes[start..]
+        .iter()
+        .take(MAX_START_TOKEN + 1)
+        .take_while(|byte| **byte != b' ');
+    let mut length = 0;
+    for byte in token {
+        if !byte.is_ascii_graphic() {
+            return Err(HttpParseError::not_http(NOT_HTTP));
+        }
+        length += 1;
+    }
+    if length == 0 && bytes.len() > start || length > MAX_START_TOKEN {
+        return Err(HttpParseError::not_http(NOT_HTTP));
+    }
+    Ok(())
+}
+
+/// reads the meta data line and the headers until (and including) the empty line,
+/// but not more than the limit (a single byte order mark at the very start is skipped)
+pub(crate) fn read_head<R: BufRead>(reader: &mut R, limit: Option<usize>, kind: ParseErrorKind) -> Result<String, HttpParseError> {
+    let buffer = reader.fill_buf().map_err(|err| io_error(kind, err))?;
+    sniff_http(buffer)?;
+    if buffer.starts_with(&UTF8_BOM) {
+        reader.consume(UTF8_BOM.len());
+    }
+    let mut head = String::new();
+    let max = limit.map_or(u64::MAX, |limit| limit as u64 + 1);
+    loop {
+        let read = reader
+            .take(max - head.len() as u64)
+            .read_line(&mut head)
+            .map_err(|err| io_error(kind, err))?;
+        check_head_size(head.len(), limit)?;
+        if read == 0 {
+            if head.is_empty() {
+                return Err(HttpParseError::from((kind, OPTION_WAS_EMPTY)));
+            }
+            return Ok(head);
+        }
+        if head.ends_with("\n\n") || head.ends_with("\r\n\r\n") || head == "\r\n" || head == "\n" {
+            return Ok(head);
+        }
+    }
+}
+
+/// reads exactly length bytes of the body, but fails before reading anything if the length exceeds the limit. <br>
+/// The body grows with the bytes that actually arrive instead of being allocated for the declared length,
+/// so a huge Content-Length can't exhaust the memory
+pub(crate) fn read_body<R: Read>(
+    reader: &mut R,
+    length: usize,
+    limit: Option<usize>,
+    kind: ParseErrorKind,
+) -> Result<String, HttpParseError> {
+    check_body_size(length, limit)?;
+    let mut body = Vec::new();
+    reader
+        .take(length as u64)
+        .read_to_end(&mut body)
+        .map_err(|err| io_error(kind, err))?;
+    if body.len() < length {
+        return Err(HttpParseError::from((kind, BODY_INCOMPLETE)));
+    }
+    String::from_utf8(body).map_err(|err| HttpParseError::from((kind, err.to_string())))
+}
+
+/// maps a failed read to an error of the kind, a read that timed out
+/// (like one of the [ParseOptions](crate::ParseOptions) timeouts) to a [Timeout](ParseErrorKind::Timeout) error
+fn io_error(kind: ParseErrorKind, err: std::io::Error) -> HttpParseError {
+    match err.kind() {
+        ErrorKind::WouldBlock | ErrorKind::TimedOut => HttpParseError::timeout(err.to_string()),
+        _ => HttpParseError::from((kind, err.to_string())),
+    }
+}
+
+/// checks that the size of the start line and the headers doesn't exceed the limit
+pub(crate) fn check_head_size(size: usize, limit: Option<usize>) -> Result<(), HttpParseError> {
+    match limit {
+        Some(limit) if size > limit => {
+            Err(HttpParseError::header_fields_too_large(format!("{} {} bytes", HEAD_TOO_LARGE, limit)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// checks that the body size doesn't exceed the limit
+pub(crate) fn check_body_size(size: usize, limit: Option<usize>) -> Result<(), HttpParseError> {
+    match limit {
+        Some(limit) if size > limit => Err(HttpParseError::length(format!("{} {} bytes", BODY_TOO_LARGE, limit))),
+        _ => Ok(()),
+    }
+}
+
+/// reads the body until the connection gets closed (but not more than the limit)
+pub(crate) fn read_body_to_end<R: Read>(
+    reader: &mut R,
+    limit: Option<usize>,
+    kind: ParseErrorKind,
+) -> Result<String, HttpParseError> {
+    let mut body = Vec::new();
+    let max = limit.map_or(u64::MAX, |limit| limit as u64 + 1);
+    reader
+        .take(max)
+        .read_to_end(&mut body)
+        .map_err(|err| io_error(kind, err))?;
+    check_body_size(body.len(), limit)?;
+    String::from_utf8(body).map_err(|err| HttpParseError::from((kind, err.to_string())))
+}
+
+/// reads and decodes a chunked body (trailers are skipped). <br>
+/// Every chunk is checked against the limit before it is read and grows the body only with the bytes that arrive
+pub(crate) fn read_chunked_body<R: BufRead>(
+    reader: &mut R,
+    limit: Option<usize>,
+    kind: ParseErrorKind,
+) -> Result<String, HttpParseError> {
+    let mut body = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader
+            .read_line(&mut line)
+            .map_err(|err| io_error(kind, err))?;
+        let size = line.trim().split(';').next().unwrap_or("");
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|err| HttpParseError::from((kind, format!("{}: {}", INVALID_CHUNK_SIZE, err))))?;
+        if size == 0 {
+            break;
+        }
+        let total = body.len().checked_add(size).ok_or(HttpParseError::length(CHUNK_TOO_LARGE))?;
+        check_body_size(total, limit)?;
+        let read = reader
+            .take(size as u64)
+            .read_to_end(&mut body)
+            .map_err(|err| io_error(kind, err))?;
+        if read < size {
+            return Err(HttpParseError::from((kind, BODY_INCOMPLETE)));
+        }
+        let mut line_end = String::new();
+        reader
+            .read_line(&mut line_end)
+            .map_err(|err| io_error(kind, err))?;
+    }
+    // skip the trailers until the empty line
+    loop {
+        let mut line = String::new();
+        let read = reader
+            .read_line(&mut line)
+            .map_err(|err| io_error(kind, err))?;
+        if read == 0 || line.trim().is_empty() {
+            break;
+        }
+    }
+    String::from_utf8(body).map_err(|err| HttpParseError::from((kind, err.to_string())))
+}
+
+/// returns the length of the complete chunked body (with the trailers) at the start of the bytes
+/// or [None] if more bytes are needed. <br>
+/// A chunk that would exceed the limit (or the addressable memory) is an error right away,
+/// so the caller doesn't wait for bytes it would reject anyway
+pub(crate) fn chunked_length(bytes: &[u8], limit: Option<usize>, kind: ParseErrorKind) -> Result<Option<usize>, HttpParseError> {
+    let mut position: usize = 0;
+    let mut body: usize = 0;
+    loop {
+        let Some(line) = next_line(bytes, &mut position) else {
+            return Ok(None);
+        };
+        let size = String::from_utf8_lossy(line);
+        let size = size.trim().split(';').next().unwrap_or("");
+        let size = usize::from_str_radix(size, 16)
+            .map_err(|err| HttpParseError::from((kind, format!("{}: {}", INVALID_CHUNK_SIZE, err))))?;
+        if size == 0 {
+            break;
+        }
+        body = body.checked_add(size).ok_or(HttpParseError::length(CHUNK_TOO_LARGE))?;
+        check_body_size(body, limit)?;
+        position = position.checked_add(size).ok_or(HttpParseError::length(CHUNK_TOO_LARGE))?;
+        if position > bytes.len() || next_line(bytes, &mut position).is_none() {
+            return Ok(None);
+        }
+    }
+    loop {
+        let Some(line) = next_line(bytes, &mut position) else {
+            return Ok(None);
+        };
+        if line.trim_ascii().is_empty() {
+            return Ok(Some(position));
+        }
+    }
+}
+
+/// returns the line (with its line break) starting at the position and moves the position behind it
+fn next_line<'a>(bytes: &'a [u8], position: &mut usize) -> Option<&'a [u8]> {
+    let rest = bytes.get(*position..)?;
+    let end = rest.iter().position(|byte| *byte == b'\n')? + 1;
+    *position += end;
+    Some(&rest[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ParseErrorKind;
+    use crate::stream::{chunked_length, read_body, read_chunked_body, read_head, sniff_http};
+
+    const CLIENT_HELLO: [u8; 16] = [
+        0x16, 0x03, 0x01, 0x02, 0x00, 0x01, 0x00, 0x01, 0xfc, 0x03, 0x03, 0x7a, 0x2c, 0x9e, 0x41, 0x00,
+    ];
+
+    #[test]
+    fn sniff() {
+        let err = sniff_http(&CLIENT_HELLO).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::NotHttp);
+        assert!(err.get_msg().unwrap().contains("TLS ClientHello"));
+        assert!(sniff_http(&[0x16]).is_err());
+        assert!(sniff_http(&[0x00, 0xff, 0x13, 0x37, 0x20]).is_err());
+        assert!(sniff_http(&[0x47, 0x45, 0x54, 0x00]).is_err());
+        assert!(sniff_http(b" GET / HTTP/1.1").is_err());
+        assert!(sniff_http(&[b'A'; 64]).is_err());
+
+        assert!(sniff_http(b"GET / HTTP/1.1\r\n").is_ok());
+        assert!(sniff_http(b"HTTP/1.1 200 OK\r\n").is_ok());
+        assert!(sniff_http(b"\r\nPOST /").is_ok());
+        assert!(sniff_http(b"GE").is_ok());
+        assert!(sniff_http(b"").is_ok());
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        assert!(sniff_http(b"\xEF\xBB\xBFGET / HTTP/1.1").is_ok());
+        assert!(sniff_http(b"\xEF\xBB").is_ok());
+        let err = sniff_http(b"\xEF\xBB\xBF\xEF\xBB\xBFGET / HTTP/1.1").unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::NotHttp);
+        assert!(err.get_msg().unwrap().contains("byte order mark"));
+        assert!(sniff_http(b"\r\n\xEF\xBB\xBFGET / HTTP/1.1").is_err());
+    }
+
+    #[test]
+    fn body_length() {
+        let mut reader = b"Hello World".as_slice();
+        assert_eq!(read_body(&mut reader, 5, None, ParseErrorKind::Req).unwrap(), "Hello");
+        assert_eq!(reader, b" World");
+        let err = read_body(&mut b"Hello".as_slice(), 99_999_999_999_999_999, None, ParseErrorKind::Req).unwrap_err();
+        assert_eq!(err.get_msg(), Some("The input ends before the end of the body"));
+        let err = read_body(&mut b"Hello".as_slice(), 99_999_999_999_999_999, Some(1024), ParseErrorKind::Req).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Length);
+    }
+
+    #[test]
+    fn chunk_size() {
+        let read = |bytes: &[u8], limit| read_chunked_body(&mut bytes.to_vec().as_slice(), limit, ParseErrorKind::Req);
+        assert_eq!(read(b"2\r\nok\r\n3;ext\r\n!!!\r\n0\r\nTrailer: a\r\n\r\n", None).unwrap(), "ok!!!");
+        let err = read(b"1\r\na\r\nffffffffffffffff\r\nb\r\n0\r\n\r\n", None).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Length);
+        assert_eq!(read(b"fffffffffffffff\r\nb\r\n0\r\n\r\n", None).unwrap_err().get_msg(), Some("The input ends before the end of the body"));
+        assert_eq!(read(b"fffffffffffffff\r\nb\r\n0\r\n\r\n", Some(1024)).unwrap_err().get_kind(), &ParseErrorKind::Length);
+    }
+
+    #[test]
+    fn chunked_body_length() {
+        let bytes = b"2\r\nok\r\n0\r\n\r\nGET";
+        assert_eq!(chunked_length(bytes, None, ParseErrorKind::Req).unwrap(), Some(12));
+        assert_eq!(chunked_length(&bytes[..8], None, ParseErrorKind::Req).unwrap(), None);
+        assert_eq!(chunked_length(bytes, Some(1), ParseErrorKind::Req).unwrap_err().get_kind(), &ParseErrorKind::Length);
+        let huge = b"ffffffffffffffff\r\nb\r\n";
+        assert_eq!(chunked_length(huge, None, ParseErrorKind::Req).unwrap_err().get_kind(), &ParseErrorKind::Length);
+        assert_eq!(chunked_length(huge, Some(1024), ParseErrorKind::Req).unwrap_err().get_kind(), &ParseErrorKind::Length);
+    }
+
+    #[test]
+    fn head_size() {
+        let head = b"GET / HTTP/1.1\r\nHost: a\r\n\r\nbody";
+        assert_eq!(read_head(&mut head.as_slice(), Some(27), ParseErrorKind::Req).unwrap().len(), 27);
+        let err = read_head(&mut head.as_slice(), Some(26), ParseErrorKind::Req).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::HeaderFieldsTooLarge);
+        let endless = [b'a'; 4096];
+        assert!(read_head(&mut endless.as_slice(), Some(1024), ParseErrorKind::Req).is_err());
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use wjp::{Deserialize, ParseError, Values};
+
+use crate::response::Response;
+use crate::status::HttpStatus;
+
+const STATUS: &str = "status";
+const HEADER: &str = "header";
+const BODY: &str = "body";
+
+/// Expectation for a single header of a [Response]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum HeaderExpectation {
+    /// The header is present with exactly this value
+    Exact(String),
+    /// The header is present with any value
+    Present,
+    /// The header is absent
+    Absent,
+}
+
+/// Matcher for the body of a [Response]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum BodyMatcher {
+    /// The body is exactly this String
+    Exact(String),
+    /// The body contains this String
+    Contains(String),
+    /// The body is JSON structurally equal to this JSON (ignoring the key order)
+    JsonEq(String),
+}
+
+/// Expectation a [Response] is compared against with [assert_response_matches]. <br>
+/// Only the parts that are specified get compared
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ResponseExpectation {
+    status: Option<HttpStatus>,
+    headers: Vec<(String, HeaderExpectation)>,
+    body: Option<BodyMatcher>,
+}
+
+impl ResponseExpectation {
+    /// creates a new ResponseExpectation that matches every [Response]
+    pub const fn new() -> Self {
+        Self {
+            status: None,
+            headers: Vec::new(),
+            body: None,
+        }
+    }
+    /// replaces the current value with the status parameter
+    pub fn with_status(mut self, status: HttpStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+    /// adds an expectation for the header (the key is matched case-insensitive)
+    pub fn with_header(mut self, key: &str, expectation: HeaderExpectation) -> Self {
+        self.headers.push((String::from(key), expectation));
+        self
+    }
+    /// replaces the current value with the body parameter
+    pub fn with_body(mut self, body: BodyMatcher) -> Self {
+        self.body = Some(body);
+        self
+    }
+    /// compares the [Response] with the expectation and returns a line for every mismatch
+    pub fn mismatches(&self, actual: &Response) -> Vec<String> {
+        let mut mismatches = Vec::new();
+        if let Some(status) = &self.status {
+            if status != actual.get_status() {
+                mismatches.push(format!("{}: expected `{}` but was `{}`", STATUS, status, actual.get_status()));
+            }
+        }
+        for (key, expectation) in &self.headers {
+            let value = actual.get_header_ignore_case(key);
+            let mismatch = match (expectation, value) {
+                (HeaderExpectation::Exact(expected), Some(value)) if expected != value => {
+                    Some(format!("expected `{}` but was `{}`", expected, value))
+                }
+                (HeaderExpectation::Exact(expected), None) => Some(format!("expected `{}` but was absent", expected)),
+                (HeaderExpectation::Present, None) => Some(String::from("expected to be present but was absent")),
+                (HeaderExpectation::Absent, Some(value)) => Some(format!("expected to be absent but was `{}`", value)),
+                _ => None,
+            };
+            if let Some(mismatch) = mismatch {
+                mismatches.push(format!("{} {}: {}", HEADER, key, mismatch));
+            }
+        }
+        if let Some(body) = &self.body {
+            let actual = actual.get_body();
+            let mismatch = match body {
+                BodyMatcher::Exact(expected) if expected != actual => Some(format!("expected `{}`", expected)),
+                BodyMatcher::Contains(expected) if !actual.contains(expected.as_str()) => {
+                    Some(format!("expected to contain `{}`", expected))
+                }
+                BodyMatcher::JsonEq(expected) if !json_eq(expected, actual) => Some(format!("expected JSON equal to `{}`", expected)),
+                _ => None,
+            };
+            if let Some(mismatch) = mismatch {
+                mismatches.push(format!("{}: {} but was `{}`", BODY, mismatch, actual));
+            }
+        }
+        mismatches
+    }
+}
+
+/// Asserts that the [Response] matches the [ResponseExpectation]
+/// and panics with a readable diff naming every mismatching part otherwise
+pub fn assert_response_matches(actual: &Response, expected: &ResponseExpectation) {
+    let mismatches = expected.mismatches(actual);
+    if !mismatches.is_empty() {
+        panic!("response doesn't match the expectation:\n  {}", mismatches.join("\n  "));
+    }
+}
+
+struct Json(Values);
+
+impl TryFrom<Values> for Json {
+    type Error = ParseError;
+    fn try_from(value: Values) -> Result<Self, Self::Error> {
+        Ok(Json(value))
+    }
+}
+
+fn json_eq(expected: &str, actual: &str) -> bool {
+    match (Json::deserialize_str(expected), Json::deserialize_str(actual)) {
+        (Ok(expected), Ok(actual)) => values_eq(&expected.0, &actual.0),
+        _ => false,
+    }
+}
+
+fn values_eq(expected: &Values, actual: &Values) -> bool {
+    match (expected, actual) {
+        (Values::Null, Values::Null) => true,
+        (Values::Boolean(expected), Values::Boolean(actual)) => expected == actual,
+        (Values::Number(expected), Values::Number(actual)) => expected == actual,
+        (Values::String(expected), Values::String(actual)) => expected == actual,
+        (Values::Array(expected), Values::Array(actual)) => {
+            expected.len() == actual.len() && expected.iter().zip(actual).all(|(e, a)| values_eq(e, a))
+        }
+        (Values::Struct(expected), Values::Struct(actual)) => struct_eq(expected, actual),
+        _ => false,
+    }
+}
+
+fn struct_eq(expected: &HashMap<String, Values>, actual: &HashMap<String, Values>) -> bool {
+    expected.len() == actual.len()
+        && expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|actual| values_eq(value, actual)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::resp_presets::ok;
+    use crate::status_presets;
+    use crate::test_util::{assert_response_matches, BodyMatcher, HeaderExpectation, ResponseExpectation};
+
+    #[test]
+    fn matches() {
+        let mut resp = ok("{\"a\":1,\"b\":[true,null]}");
+        resp.add_header((String::from("Content-Type"), String::from("application/json")));
+        let expectation = ResponseExpectation::new()
+            .with_status(status_presets::ok())
+            .with_header("content-type", HeaderExpectation::Exact(String::from("application/json")))
+            .with_header("Content-Type", HeaderExpectation::Present)
+            .with_header("Location", HeaderExpectation::Absent)
+            .with_body(BodyMatcher::JsonEq(String::from("{ \"b\": [true, null], \"a\": 1 }")));
+        assert_response_matches(&resp, &expectation);
+    }
+
+    #[test]
+    fn mismatches() {
+        let resp = ok("{\"a\":1}");
+        let expectation = ResponseExpectation::new()
+            .with_status(status_presets::not_found())
+            .with_header("Location", HeaderExpectation::Present)
+            .with_body(BodyMatcher::JsonEq(String::from("{\"a\":2}")));
+        let mismatches = expectation.mismatches(&resp);
+        assert_eq!(mismatches.len(), 3);
+        assert_eq!(mismatches[0], "status: expected `404 Not Found` but was `200 OK`");
+        assert_eq!(mismatches[1], "header Location: expected to be present but was absent");
+        assert!(mismatches[2].starts_with("body: expected JSON equal to"));
+        assert!(ResponseExpectation::new()
+            .with_body(BodyMatcher::Contains(String::from("\"a\"")))
+            .mismatches(&resp)
+            .is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "header Location")]
+    fn panics() {
+        let expectation = ResponseExpectation::new().with_header("Location", HeaderExpectation::Present);
+        assert_response_matches(&ok(""), &expectation);
+    }
+}
@@ -0,0 +1,425 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+use crate::error::HttpParseError;
+use crate::form::{encode_form_component, query_pairs};
+use crate::util::split_host_port;
+
+const INVALID_TARGET: &str = "The request target mustn't be empty or contain whitespace or control characters:";
+const AUTHORITY_WITHOUT_SCHEME: &str = "An authority needs a scheme in front of the path of the target";
+const SCHEME_DELIMITER: char = ':';
+const AUTHORITY_PREFIX: &str = "//";
+const PATH_DELIMITER: char = '/';
+const QUERY_DELIMITER: char = '?';
+const FRAGMENT_DELIMITER: char = '#';
+const PAIR_DELIMITER: char = '&';
+const ASTERISK_FORM: &str = "*";
+const HTTP: &str = "http";
+const HTTPS: &str = "https";
+
+/// Struct for the parts of a request target ([RFC 3986](https://www.rfc-editor.org/rfc/rfc3986#section-3)):
+/// scheme, authority, path, query and fragment. <br>
+/// It isn't a full URL parser, only the forms of [RFC 7230](https://www.rfc-editor.org/rfc/rfc7230#section-5.3)
+/// are distinguished: the origin form (`/path?query`), the absolute form (`http://host/path`),
+/// the authority form of CONNECT (`host:443`) and the asterisk form of OPTIONS (`*`).
+/// The parts are kept as they are (still percent-encoded), so [to_string](ToString::to_string) reassembles the target
+///
+/// Example:
+/// ```
+/// use whdp::Target;
+///
+/// let mut target = Target::parse("http://example.com/a/b?x=1#top").unwrap();
+/// assert_eq!(target.get_authority(), Some("example.com"));
+/// assert_eq!(target.get_path_segments(), vec!["a", "b"]);
+/// target.set_path("/c").push_query_pair("y", "a b").strip_fragment();
+/// assert_eq!(target.to_string(), "http://example.com/c?x=1&y=a+b");
+/// assert_eq!(target.get_origin_form(), "/c?x=1&y=a+b");
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct Target {
+    scheme: Option<String>,
+    authority: Option<String>,
+    path: String,
+    query: Option<String>,
+    fragment: Option<String>,
+}
+
+impl Target {
+    /// parses the request target. <br>
+    /// Returns a [Util](crate::ParseErrorKind::Util) error if it is empty
+    /// or contains whitespace or control characters
+    pub fn parse(str: &str) -> Result<Self, HttpParseError> {
+        if str.is_empty() || str.chars().any(|char| char.is_whitespace() || char.is_control()) {
+            return Err(HttpParseError::util(format!("{} {:?}", INVALID_TARGET, str)));
+        }
+        Ok(Self::split(str))
+    }
+    /// splits the target into its parts without checking the characters
+    pub(crate) fn split(str: &str) -> Self {
+        let mut target = Self::default();
+        if str == ASTERISK_FORM {
+            target.path = String::from(str);
+            return target;
+        }
+        let (rest, fragment) = split_off(str, FRAGMENT_DELIMITER);
+        let (rest, query) = split_off(rest, QUERY_DELIMITER);
+        target.fragment = fragment.map(String::from);
+        target.query = query.map(String::from);
+        if target.query.is_none() && target.fragment.is_none() && is_authority_form(rest) {
+            target.authority = Some(String::from(rest));
+            return target;
+        }
+        let rest = match rest.split_once(SCHEME_DELIMITER) {
+            Some((scheme, rest)) if is_scheme(scheme) => {
+                target.scheme = Some(String::from(scheme));
+                rest
+            }
+            _ => rest,
+        };
+        match rest.strip_prefix(AUTHORITY_PREFIX).filter(|_| target.scheme.is_some()) {
+            Some(rest) => {
+                let (authority, path) = rest.split_at(rest.find(PATH_DELIMITER).unwrap_or(rest.len()));
+                target.authority = Some(String::from(authority));
+                target.path = String::from(path);
+            }
+            None => target.path = String::from(rest),
+        }
+        target
+    }
+    /// returns the scheme (like `http`) of an absolute target
+    pub fn get_scheme(&self) -> Option<&str> {
+        self.scheme.as_deref()
+    }
+    /// returns the authority (`host:port`) of an absolute or authority form target
+    pub fn get_authority(&self) -> Option<&str> {
+        self.authority.as_deref()
+    }
+    /// returns the host of the authority without the port
+    /// (and without the brackets of an IPv6 literal)
+    pub fn get_host(&self) -> Option<&str> {
+        self.authority.as_deref().and_then(split_host_port).map(|(host, _)| host)
+    }
+    /// returns the port of the authority ([None] if it has no port)
+    pub fn get_port(&self) -> Option<u16> {
+        self.authority.as_deref().and_then(split_host_port).and_then(|(_, port)| port)
+    }
+    /// returns the path (empty for `http://host` or an authority form target)
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+    /// returns the segments of the path without the leading slash
+    /// (`/a/b/` has the segments `a`, `b` and an empty one)
+    pub fn get_path_segments(&self) -> Vec<&str> {
+        match self.path.strip_prefix(PATH_DELIMITER).unwrap_or(&self.path) {
+            "" if !self.path.starts_with(PATH_DELIMITER) => Vec::new(),
+            path => path.split(PATH_DELIMITER).collect(),
+        }
+    }
+    /// returns the query without the `?` (still percent-encoded)
+    pub fn get_query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+    /// returns the decoded pairs of the query in order (like [get_query_pairs](crate::Request::get_query_pairs))
+    pub fn get_query_pairs(&self) -> Vec<(String, String)> {
+        self.query.as_deref().map(query_pairs).unwrap_or_default()
+    }
+    /// returns the fragment without the `#`
+    pub fn get_fragment(&self) -> Option<&str> {
+        self.fragment.as_deref()
+    }
+    /// returns the path with the query as used in the origin form
+    /// (an empty path becomes `/`, for example to forward an absolute form target to an origin server)
+    pub fn get_origin_form(&self) -> String {
+        let mut string = if self.path.is_empty() { String::from(PATH_DELIMITER) } else { self.path.clone() };
+        if let Some(query) = &self.query {
+            string.push(QUERY_DELIMITER);
+            string.push_str(query);
+        }
+        string
+    }
+    /// returns the path as a canonical routing key, see [canonical_path](crate::Request::canonical_path)
+    pub fn canonical_path(&self) -> String {
+        canonical_path(&self.path)
+    }
+    /// looks if the target has a scheme and an authority (like `http://host/path`)
+    pub fn is_absolute(&self) -> bool {
+        self.scheme.is_some() && self.authority.is_some()
+    }
+    /// looks if the target is absolute with the `http` or `https` scheme (case-insensitive)
+    pub fn is_http(&self) -> bool {
+        self.authority.is_some()
+            && self.scheme.as_deref().is_some_and(|scheme| scheme.eq_ignore_ascii_case(HTTP) || scheme.eq_ignore_ascii_case(HTTPS))
+    }
+    /// looks if the target is only an authority (like `host:443` of CONNECT)
+    pub fn is_authority_form(&self) -> bool {
+        self.scheme.is_none() && self.authority.is_some()
+    }
+    /// looks if the target is the asterisk `*` of OPTIONS
+    pub fn is_asterisk(&self) -> bool {
+        self.authority.is_none() && self.path == ASTERISK_FORM
+    }
+    /// replaces the scheme ([None] removes it together with the authority)
+    pub fn set_scheme(&mut self, scheme: Option<&str>) -> &mut Self {
+        self.scheme = scheme.map(String::from);
+        if self.scheme.is_none() {
+            self.authority = None;
+        }
+        self
+    }
+    /// replaces the authority (`host:port`). <br>
+    /// Returns a [Util](crate::ParseErrorKind::Util) error if the target has no scheme but a path, query or fragment,
+    /// because an origin form target with an authority couldn't be told apart from a path
+    /// (set the [scheme](Target::set_scheme) first)
+    pub fn set_authority(&mut self, authority: Option<&str>) -> Result<&mut Self, HttpParseError> {
+        let origin_form = !self.path.is_empty() || self.query.is_some() || self.fragment.is_some();
+        if authority.is_some() && self.scheme.is_none() && origin_form {
+            return Err(HttpParseError::util(format!("{} {:?}", AUTHORITY_WITHOUT_SCHEME, self.to_string())));
+        }
+        self.authority = authority.map(String::from);
+        Ok(self.set_path(&self.path.clone()))
+    }
+    /// replaces the path (a slash is added in front of a relative path if there is an authority)
+    pub fn set_path(&mut self, path: &str) -> &mut Self {
+        self.path = match self.authority {
+            Some(_) if !path.is_empty() && !path.starts_with(PATH_DELIMITER) => format!("{}{}", PATH_DELIMITER, path),
+            _ => String::from(path),
+        };
+        self
+    }
+    /// replaces the query (it is taken as it is, so it has to be percent-encoded already)
+    pub fn set_query(&mut self, query: Option<&str>) -> &mut Self {
+        self.query = query.map(String::from);
+        self
+    }
+    /// appends the key and the value percent-encoded (a space becomes `+`) to the query
+    pub fn push_query_pair(&mut self, key: &str, value: &str) -> &mut Self {
+        let pair = format!("{}={}", encode_form_component(key), encode_form_component(value));
+        match &mut self.query {
+            Some(query) if !query.is_empty() => {
+                query.push(PAIR_DELIMITER);
+                query.push_str(&pair);
+            }
+            query => *query = Some(pair),
+        }
+        self
+    }
+    /// removes the fragment (which isn't sent in a Request)
+    pub fn strip_fragment(&mut self) -> &mut Self {
+        self.fragment = None;
+        self
+    }
+}
+
+/// returns the query of a request target without the `?` (the part in front of the fragment)
+pub(crate) fn raw_query(str: &str) -> Option<&str> {
+    match str {
+        ASTERISK_FORM => None,
+        str => split_off(split_off(str, FRAGMENT_DELIMITER).0, QUERY_DELIMITER).1,
+    }
+}
+
+/// splits the string at the first delimiter
+fn split_off(str: &str, delimiter: char) -> (&str, Option<&str>) {
+    match str.split_once(delimiter) {
+        Some((rest, tail)) => (rest, Some(tail)),
+        None => (str, None),
+    }
+}
+
+/// looks if the string is a scheme: a letter followed by letters, digits, `+`, `-` or `.`
+fn is_scheme(str: &str) -> bool {
+    let mut chars = str.chars();
+    chars.next().is_some_and(|char| char.is_ascii_alphabetic())
+        && chars.all(|char| char.is_ascii_alphanumeric() || matches!(char, '+' | '-' | '.'))
+}
+
+/// looks if the string is only a host with a port (like `example.com:443` or `[::1]:443`)
+fn is_authority_form(str: &str) -> bool {
+    !str.contains(PATH_DELIMITER)
+        && split_host_port(str).is_some_and(|(host, port)| !host.is_empty() && port.is_some())
+}
+
+/// collapses duplicate slashes, removes `.` segments and resolves `..` segments
+/// (which never climb above the root) of a path. <br>
+/// A trailing slash is kept, as well as one for a trailing `.` or `..` segment
+pub(crate) fn canonical_path(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    let mut trailing_slash = false;
+    for segment in path.split(PATH_DELIMITER) {
+        trailing_slash = matches!(segment, "" | "." | "..");
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    let mut canonical = String::with_capacity(path.len() + 1);
+    for segment in &segments {
+        canonical.push(PATH_DELIMITER);
+        canonical.push_str(segment);
+    }
+    if trailing_slash || segments.is_empty() {
+        canonical.push(PATH_DELIMITER);
+    }
+    canonical
+}
+
+impl FromStr for Target {
+    type Err = HttpParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(scheme) = &self.scheme {
+            write!(f, "{}{}", scheme, SCHEME_DELIMITER)?;
+            if self.authority.is_some() {
+                f.write_str(AUTHORITY_PREFIX)?;
+            }
+        }
+        if let Some(authority) = &self.authority {
+            f.write_str(authority)?;
+        }
+        f.write_str(&self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "{}{}", QUERY_DELIMITER, query)?;
+        }
+        if let Some(fragment) = &self.fragment {
+            write!(f, "{}{}", FRAGMENT_DELIMITER, fragment)?;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for Target {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ParseErrorKind, Target};
+
+    #[test]
+    fn origin_form() {
+        let target = Target::parse("/a/b?x=1&y=%20#frag").unwrap();
+        assert_eq!(target.get_scheme(), None);
+        assert_eq!(target.get_authority(), None);
+        assert_eq!(target.get_path(), "/a/b");
+        assert_eq!(target.get_query(), Some("x=1&y=%20"));
+        assert_eq!(target.get_fragment(), Some("frag"));
+        assert_eq!(target.get_query_pairs(), vec![(String::from("x"), String::from("1")), (String::from("y"), String::from(" "))]);
+        assert!(!target.is_absolute());
+        assert_eq!(target.to_string(), "/a/b?x=1&y=%20#frag");
+
+        let target = Target::parse("//double/slash").unwrap();
+        assert_eq!(target.get_authority(), None);
+        assert_eq!(target.get_path(), "//double/slash");
+        assert_eq!(Target::parse("/?").unwrap().get_query(), Some(""));
+        assert_eq!(Target::parse("/?").unwrap().to_string(), "/?");
+    }
+
+    #[test]
+    fn absolute_form() {
+        let target = Target::parse("HTTPS://user@[2001:db8::1]:8443/a?q#f").unwrap();
+        assert_eq!(target.get_scheme(), Some("HTTPS"));
+        assert_eq!(target.get_authority(), Some("user@[2001:db8::1]:8443"));
+        assert_eq!(target.get_path(), "/a");
+        assert!(target.is_http());
+        assert_eq!(target.to_string(), "HTTPS://user@[2001:db8::1]:8443/a?q#f");
+
+        let target = Target::parse("http://example.com:8080").unwrap();
+        assert_eq!(target.get_host(), Some("example.com"));
+        assert_eq!(target.get_port(), Some(8080));
+        assert_eq!(target.get_path(), "");
+        assert!(target.get_path_segments().is_empty());
+        assert_eq!(target.get_origin_form(), "/");
+        assert_eq!(target.to_string(), "http://example.com:8080");
+
+        let target = Target::parse("http://example.com?x=1").unwrap();
+        assert_eq!(target.get_authority(), Some("example.com"));
+        assert_eq!(target.get_origin_form(), "/?x=1");
+        assert_eq!(target.to_string(), "http://example.com?x=1");
+
+        let target = Target::parse("urn:isbn:123").unwrap();
+        assert_eq!(target.get_scheme(), Some("urn"));
+        assert_eq!(target.get_path(), "isbn:123");
+        assert!(!target.is_absolute());
+        assert_eq!(target.to_string(), "urn:isbn:123");
+    }
+
+    #[test]
+    fn authority_and_asterisk_form() {
+        let target = Target::parse("example.com:443").unwrap();
+        assert!(target.is_authority_form());
+        assert_eq!(target.get_host(), Some("example.com"));
+        assert_eq!(target.get_port(), Some(443));
+        assert_eq!(target.to_string(), "example.com:443");
+        assert!(Target::parse("[::1]:443").unwrap().is_authority_form());
+        assert!(!Target::parse("example.com").unwrap().is_authority_form());
+
+        let target = Target::parse("*").unwrap();
+        assert!(target.is_asterisk());
+        assert_eq!(target.to_string(), "*");
+    }
+
+    #[test]
+    fn path_segments() {
+        assert_eq!(Target::parse("/").unwrap().get_path_segments(), vec![""]);
+        assert_eq!(Target::parse("/a/b/").unwrap().get_path_segments(), vec!["a", "b", ""]);
+        assert_eq!(Target::parse("/a//./../b").unwrap().canonical_path(), "/b");
+        assert_eq!(Target::parse("http://h/a/%2e%2e/").unwrap().get_path_segments(), vec!["a", "%2e%2e", ""]);
+    }
+
+    #[test]
+    fn mutators() {
+        let mut target = Target::parse("http://example.com").unwrap();
+        target.set_path("items").push_query_pair("q", "a&b c").push_query_pair("ü", "");
+        assert_eq!(target.to_string(), "http://example.com/items?q=a%26b+c&%C3%BC=");
+        assert_eq!(target.get_query_pairs()[0], (String::from("q"), String::from("a&b c")));
+
+        let mut target = Target::parse("/a?#f").unwrap();
+        target.push_query_pair("x", "1").strip_fragment();
+        assert_eq!(target.to_string(), "/a?x=1");
+        target.set_query(None);
+        assert_eq!(target.to_string(), "/a");
+
+        target.set_scheme(Some("https")).set_authority(Some("example.com")).unwrap();
+        assert_eq!(target.to_string(), "https://example.com/a");
+        target.set_scheme(None);
+        assert_eq!(target.to_string(), "/a");
+        target.set_path("");
+        assert_eq!(target.to_string(), "");
+    }
+
+    #[test]
+    fn set_authority() {
+        let mut target = Target::parse("/a?x=1").unwrap();
+        let err = target.set_authority(Some("example.com")).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Util);
+        assert_eq!(target.to_string(), "/a?x=1");
+
+        target.set_scheme(Some("http")).set_authority(Some("example.com")).unwrap();
+        assert_eq!(Target::parse(&target.to_string()).unwrap(), target);
+        assert_eq!(target.get_authority(), Some("example.com"));
+
+        let mut target = Target::default();
+        target.set_authority(Some("example.com:443")).unwrap();
+        assert!(target.is_authority_form());
+        assert_eq!(Target::parse(&target.to_string()).unwrap(), target);
+    }
+
+    #[test]
+    fn invalid() {
+        for str in ["", "/a b", "/a\tb", "/a\r\n"] {
+            let err = Target::parse(str).unwrap_err();
+            assert_eq!(err.get_kind(), &ParseErrorKind::Util, "{:?}", str);
+        }
+        assert_eq!("/ok".parse::<Target>().unwrap().get_path(), "/ok");
+    }
+}
@@ -1,16 +1,104 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Arguments;
+use std::io::{ErrorKind, Write};
 use std::net::TcpStream;
-use std::str::Lines;
+use std::path::Path;
+use std::str::{FromStr, Lines};
+
+use wjp::Deserialize;
 
 use crate::{ParseErrorKind, Request};
-use crate::error::HttpParseError;
-use crate::error::ParseErrorKind::Util;
+use crate::error::{HttpParseError, MissingField};
+use crate::header::{DuplicatePolicy, HeaderMap};
+use crate::journal::HeaderJournal;
+use crate::media_type::MediaType;
+use crate::options::ParseOptions;
+use crate::error::ParseErrorKind::{Length, Util};
+use crate::version::HttpVersion;
 
 pub(crate) const KEY_VALUE_DELIMITER: &str = ": ";
 pub(crate) const NEW_LINE: char = '\n';
 pub(crate) const EMPTY_CHAR: char = ' ';
+const TAB: char = '\t';
+const BOM: char = '\u{feff}';
+pub(crate) const BOM_NOTE: &str = "(found a byte order mark, which is only skipped at the very start of a message)";
+const IPV6_START: char = '[';
+const IPV6_END: char = ']';
+const PORT_DELIMITER: char = ':';
 pub(crate) const OPTION_WAS_EMPTY: &str = "the Option<?> was empty and couldn't get unwrapped";
 pub(crate) const INDEX_WAS_WRONG: &str = "The provided index didn't match";
+pub(crate) const EMPTY_BODY: &str = "empty body";
+const MALFORMED_HEADER_LINE: &str = "The header line needs a name and a value separated by a colon and a space:";
+const TOO_MANY_HEADERS: &str = "The message has more header lines than the limit of";
+const DOUBLE_QUOTE: char = '"';
+const BACKSLASH: char = '\\';
+const TOKEN_SPECIALS: &str = "!#$%&'*+-.^_`|~";
+const MALFORMED_BODY: &str = "the body couldn't get parsed";
+pub(crate) const MISSING_FIELDS: &str = "min. 1 field was not filled with a value";
+pub(crate) const UNSUPPORTED_VERSION: &str = "The HTTP version is not supported:";
+pub(crate) const UNSUPPORTED_CODING: &str = "The transfer coding is not supported:";
+pub(crate) const UNSUPPORTED_CONTENT_CODING: &str = "The content coding is not supported:";
+pub(crate) const NO_HTTP1_WIRE_FORM: &str = "has no HTTP/1.x wire form";
+pub(crate) const CONTENT_LENGTH: &str = "Content-Length";
+pub(crate) const TRANSFER_ENCODING: &str = "Transfer-Encoding";
+pub(crate) const CHUNKED: &str = "chunked";
+pub(crate) const CONNECTION: &str = "Connection";
+pub(crate) const UPGRADE: &str = "Upgrade";
+pub(crate) const CONTENT_TYPE: &str = "Content-Type";
+pub(crate) const ALLOW: &str = "Allow";
+pub(crate) const MESSAGE_HTTP: &str = "message/http";
+pub(crate) const CRLF: &str = "\r\n";
+const LF: &str = "\n";
+pub(crate) const REDACTED: &str = "[REDACTED]";
+const BINARY_BODY_FILE: &str = "The file has a binary Content-Type and can't be used as a String body:";
+const INVALID_RAW_HEADER_LINE: &str = "A raw header line needs a name before exactly one colon and mustn't contain CR, LF or NUL:";
+pub(crate) const HOP_BY_HOP_HEADERS: [&str; 9] = [
+    "Connection",
+    "Keep-Alive",
+    "Proxy-Authenticate",
+    "Proxy-Authorization",
+    "Proxy-Connection",
+    "TE",
+    "Trailer",
+    "Transfer-Encoding",
+    "Upgrade",
+];
+pub(crate) const SENSITIVE_HEADERS: [&str; 5] = [
+    "Authorization",
+    "Proxy-Authorization",
+    "Cookie",
+    "Set-Cookie",
+    "X-Api-Key",
+];
+
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+const CONTENT_ENCODING: &str = "Content-Encoding";
+const IDENTITY: &str = "identity";
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+const TEXT_PREFIX: &str = "text/";
+const APPLICATION_PREFIX: &str = "application/";
+const TEXTUAL_SUFFIXES: [&str; 2] = ["+json", "+xml"];
+const TEXTUAL_TYPES: [&str; 3] = ["application/json", "application/xml", "application/x-www-form-urlencoded"];
+const CONTENT_TYPES: [(&str, &str); 16] = [
+    ("html", "text/html; charset=utf-8"),
+    ("htm", "text/html; charset=utf-8"),
+    ("css", "text/css; charset=utf-8"),
+    ("js", "text/javascript; charset=utf-8"),
+    ("json", "application/json"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("csv", "text/csv; charset=utf-8"),
+    ("xml", "application/xml"),
+    ("svg", "image/svg+xml"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("ico", "image/x-icon"),
+    ("pdf", "application/pdf"),
+    ("wasm", "application/wasm"),
+];
+const BINARY_CONTENT_TYPES: [&str; 6] = ["image/png", "image/jpeg", "image/gif", "image/x-icon", "application/pdf", "application/wasm"];
 
 pub(crate) trait ParseKeyValue {
     fn parse_key_value(&self) -> String;
@@ -20,10 +108,12 @@ impl ParseKeyValue for BTreeMap<String, String> {
     fn parse_key_value(&self) -> String {
         let mut string = String::new();
         for (key, value) in self {
-            string.push_str(key);
-            string.push_str(KEY_VALUE_DELIMITER);
-            string.push_str(value);
-            string.push(NEW_LINE);
+            for value in value.split(NEW_LINE) {
+                string.push_str(key);
+                string.push_str(KEY_VALUE_DELIMITER);
+                string.push_str(value);
+                string.push(NEW_LINE);
+            }
         }
         string
     }
@@ -32,6 +122,14 @@ impl ParseKeyValue for BTreeMap<String, String> {
 /// Trait for destructing structs with private fields.
 /// It can also be used to run destroy logic <br>
 ///
+/// The order of the returned tuple is stable: fields added later aren't part of it
+/// (they are dropped by `destruct` and get their default value when constructing). <br>
+/// Every type of this crate also implements the inverse `From<Item>`:
+/// `T::from(t.destruct())` rebuilds the message content, so it compares equal to `t`,
+/// but everything outside of the tuple is lost. <br>
+/// That's the metadata of a parsed message (duplicates, warnings, raw header lines)
+/// and the pending error of a builder, which therefore builds afterwards
+///
 /// Example:
 /// ```
 /// use whdp::Destruct;
@@ -56,6 +154,520 @@ pub trait Destruct {
     fn destruct(self) -> Self::Item;
 }
 
+pub(crate) fn get_header_ignore_case<'a>(
+    headers: &'a BTreeMap<String, String>,
+    key: &str,
+) -> Option<&'a String> {
+    headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(key))
+        .map(|(_, value)| value)
+}
+
+/// removes every header with the name (case-insensitive) and inserts the value under the canonical name
+pub(crate) fn replace_header_ignore_case(headers: &mut BTreeMap<String, String>, name: &str, value: Option<String>) {
+    headers.retain(|key, _| !key.eq_ignore_ascii_case(name));
+    if let Some(value) = value {
+        headers.insert(String::from(name), value);
+    }
+}
+
+pub(crate) fn parse_content_length(
+    headers: &BTreeMap<String, String>,
+) -> Result<Option<usize>, HttpParseError> {
+    get_header_ignore_case(headers, CONTENT_LENGTH)
+        .map(|value| {
+            value
+                .trim()
+                .parse::<usize>()
+                .map_err(|err| HttpParseError::length(err.to_string()))
+        })
+        .transpose()
+}
+
+pub(crate) fn has_body(body: &str) -> bool {
+    !body.trim().is_empty()
+}
+
+pub(crate) fn parse_body_opt<T: Deserialize>(body: &str) -> Result<Option<T>, HttpParseError> {
+    if !has_body(body) {
+        return Ok(None);
+    }
+    T::deserialize_str(body)
+        .map(Some)
+        .map_err(|err| match format!("{:?}", err) {
+            detail if detail.is_empty() => HttpParseError::body(MALFORMED_BODY),
+            detail => HttpParseError::body(format!("{}: {}", MALFORMED_BODY, detail)),
+        })
+}
+
+pub(crate) fn parse_body_to<T: Deserialize>(body: &str) -> Result<T, HttpParseError> {
+    parse_body_opt(body)?.ok_or(HttpParseError::body(EMPTY_BODY))
+}
+
+/// Enum for the line ending of the serialized start line and headers
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum LineEnding {
+    /// `\r\n` as required on the wire
+    #[default]
+    Crlf,
+    /// `\n` for example for LF-only fixtures and tools
+    Lf,
+}
+
+impl LineEnding {
+    /// returns the line ending as a str
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Crlf => CRLF,
+            LineEnding::Lf => LF,
+        }
+    }
+}
+
+/// Adapter to write into the body of a message with [Write]. <br>
+/// Every written chunk has to be valid UTF-8 otherwise an [InvalidData] error is returned
+///
+/// [InvalidData]: std::io::ErrorKind::InvalidData
+pub struct BodyWriter<'a> {
+    body: &'a mut String,
+    headers: Option<(&'a mut BTreeMap<String, String>, &'a mut HeaderJournal)>,
+}
+
+impl<'a> BodyWriter<'a> {
+    pub(crate) fn new(body: &'a mut String) -> Self {
+        Self { body, headers: None }
+    }
+    /// keeps a Content-Length of the headers that matches the body up to date while writing
+    /// (and records the changes in the journal)
+    pub(crate) fn with_headers(
+        body: &'a mut String,
+        headers: &'a mut BTreeMap<String, String>,
+        journal: &'a mut HeaderJournal,
+    ) -> Self {
+        Self { body, headers: Some((headers, journal)) }
+    }
+}
+
+impl Write for BodyWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let str = std::str::from_utf8(buf)
+            .map_err(|err| std::io::Error::new(ErrorKind::InvalidData, err))?;
+        let previous = self.body.len();
+        self.body.push_str(str);
+        if let Some((headers, journal)) = &mut self.headers {
+            sync_content_length(headers, journal, previous, self.body.len());
+        }
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// updates a Content-Length that matched the previous length of the body (like the one of a preset)
+/// to the new length and records the change in the journal, any other Content-Length is deliberate and kept
+pub(crate) fn sync_content_length(
+    headers: &mut BTreeMap<String, String>,
+    journal: &mut HeaderJournal,
+    previous: usize,
+    length: usize,
+) {
+    let declared = headers
+        .iter_mut()
+        .find(|(key, _)| key.eq_ignore_ascii_case(CONTENT_LENGTH))
+        .filter(|(_, value)| value.trim().parse::<usize>().ok() == Some(previous));
+    if let Some((key, value)) = declared {
+        let old = std::mem::replace(value, length.to_string());
+        if old != *value {
+            journal.record(key, Some(old), Some(value.clone()), None);
+        }
+    }
+}
+
+/// guesses the Content-Type from the extension of the path
+pub(crate) fn guess_content_type(path: &Path) -> &'static str {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .and_then(|extension| {
+            CONTENT_TYPES
+                .iter()
+                .find(|(known, _)| known.eq_ignore_ascii_case(extension))
+        })
+        .map(|(_, content_type)| *content_type)
+        .unwrap_or(DEFAULT_CONTENT_TYPE)
+}
+
+/// reads the file into a body and adds the Content-Length and Content-Type headers <br>
+/// Files with a binary Content-Type (like images) are rejected, because the body is a String
+pub(crate) fn read_body_file(
+    path: &Path,
+    headers: &mut BTreeMap<String, String>,
+) -> Result<String, HttpParseError> {
+    let content_type = guess_content_type(path);
+    if BINARY_CONTENT_TYPES.contains(&content_type) {
+        return Err(HttpParseError::body(format!("{} {} ({})", BINARY_BODY_FILE, path.display(), content_type)));
+    }
+    let body = std::fs::read_to_string(path)
+        .map_err(|err| HttpParseError::body(format!("{}: {}", path.display(), err)))?;
+    replace_header_ignore_case(headers, CONTENT_LENGTH, Some(body.len().to_string()));
+    replace_header_ignore_case(headers, CONTENT_TYPE, Some(String::from(content_type)));
+    Ok(body)
+}
+
+/// describes if a builder field is filled for the Debug output of the builders
+pub(crate) fn field_state<T>(field: &Option<T>) -> Arguments<'static> {
+    if field.is_some() {
+        format_args!("set")
+    } else {
+        format_args!("None")
+    }
+}
+
+/// creates the error returned by a builder with missing fields
+pub(crate) fn missing_fields_error(kind: ParseErrorKind, missing: &[MissingField]) -> HttpParseError {
+    let fields = missing
+        .iter()
+        .map(MissingField::to_string)
+        .collect::<Vec<String>>()
+        .join(", ");
+    HttpParseError::from((kind, format!("{}: {}", MISSING_FIELDS, fields)))
+}
+
+pub(crate) fn is_chunked(headers: &BTreeMap<String, String>) -> bool {
+    get_header_ignore_case(headers, TRANSFER_ENCODING)
+        .and_then(|value| value.rsplit(',').next())
+        .is_some_and(|coding| coding.trim().eq_ignore_ascii_case(CHUNKED))
+}
+
+/// removes a single byte order mark at the very start of the message
+pub(crate) fn strip_bom(s: &str) -> &str {
+    s.strip_prefix(BOM).unwrap_or(s)
+}
+
+/// adds a note to the message of the error if the line contains a byte order mark
+pub(crate) fn note_bom(mut err: HttpParseError, line: Option<&str>) -> HttpParseError {
+    if line.is_some_and(|line| line.contains(BOM)) {
+        let msg = format!("{} {}", err.get_msg().unwrap_or(""), BOM_NOTE);
+        err.with_msg(msg.trim_start());
+    }
+    err
+}
+
+/// looks if the char delimits the parts of a request or status line,
+/// which is only a space in strict mode and a space or tab otherwise
+pub(crate) fn is_meta_delimiter(char: char, strict: bool) -> bool {
+    char == EMPTY_CHAR || !strict && char == TAB
+}
+
+/// splits a host with an optional port (like the Host header) into the host and the port. <br>
+/// The brackets of an IPv6 literal (`[2001:db8::1]:8080`) are removed,
+/// returns [None] if the port isn't a valid number or the brackets are malformed
+pub(crate) fn split_host_port(authority: &str) -> Option<(&str, Option<u16>)> {
+    let (host, port) = match authority.strip_prefix(IPV6_START) {
+        Some(rest) => {
+            let (host, rest) = rest.split_once(IPV6_END)?;
+            match rest {
+                "" => (host, None),
+                _ => (host, Some(rest.strip_prefix(PORT_DELIMITER)?)),
+            }
+        }
+        None => match authority.split_once(PORT_DELIMITER) {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        },
+    };
+    let port = match port {
+        Some(port) if port.bytes().all(|byte| byte.is_ascii_digit()) => Some(port.parse::<u16>().ok()?),
+        Some(_) => return None,
+        None => None,
+    };
+    Some((host, port))
+}
+
+/// parses the Content-Type header into a [MediaType] ([None] if it is absent or malformed)
+pub(crate) fn content_type(headers: &BTreeMap<String, String>) -> Option<MediaType> {
+    get_header_ignore_case(headers, CONTENT_TYPE).and_then(|value| MediaType::from_str(value).ok())
+}
+
+/// looks if the Content-Type header has the given media type (ignoring parameters)
+pub(crate) fn has_media_type(headers: &BTreeMap<String, String>, media_type: &str) -> bool {
+    content_type(headers).is_some_and(|content_type| content_type.get_essence().eq_ignore_ascii_case(media_type))
+}
+
+/// splits at the delimiter outside of quoted strings
+pub(crate) fn split_quoted(value: &str, delimiter: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (index, char) in value.char_indices() {
+        match char {
+            _ if escaped => escaped = false,
+            BACKSLASH if quoted => escaped = true,
+            DOUBLE_QUOTE => quoted = !quoted,
+            _ if char == delimiter && !quoted => {
+                parts.push(&value[start..index]);
+                start = index + char.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+/// returns the value as it is if it is a token, otherwise as an escaped quoted-string
+pub(crate) fn quote_if_needed(value: &str) -> String {
+    let token = !value.is_empty()
+        && value
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || TOKEN_SPECIALS.contains(char));
+    if token {
+        return String::from(value);
+    }
+    format!("\"{}\"", value.replace(BACKSLASH, "\\\\").replace(DOUBLE_QUOTE, "\\\""))
+}
+
+/// removes the quotes of a quoted-string and unescapes it (other values are returned as they are)
+pub(crate) fn unquote(value: &str) -> String {
+    let Some(inner) = value.strip_prefix(DOUBLE_QUOTE).and_then(|value| value.strip_suffix(DOUBLE_QUOTE)) else {
+        return String::from(value);
+    };
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            BACKSLASH => unquoted.extend(chars.next()),
+            _ => unquoted.push(char),
+        }
+    }
+    unquoted
+}
+
+/// checks that every coding of the Transfer-Encoding header can be decoded
+/// (only chunked is supported) otherwise returns an [Unsupported] error
+pub(crate) fn check_transfer_codings(headers: &BTreeMap<String, String>) -> Result<(), HttpParseError> {
+    check_codings(headers, TRANSFER_ENCODING, CHUNKED, UNSUPPORTED_CODING)
+}
+
+/// checks that the coding header only lists the supported coding
+/// otherwise returns an [Unsupported] error with the message and the first other coding
+fn check_codings(
+    headers: &BTreeMap<String, String>,
+    key: &str,
+    supported: &str,
+    msg: &str,
+) -> Result<(), HttpParseError> {
+    let codings = get_header_ignore_case(headers, key).map_or("", String::as_str);
+    match codings
+        .split(',')
+        .map(str::trim)
+        .find(|coding| !coding.is_empty() && !coding.eq_ignore_ascii_case(supported))
+    {
+        Some(coding) => Err(HttpParseError::unsupported(format!("{} {}", msg, coding))),
+        None => Ok(()),
+    }
+}
+
+/// splits a comma separated list of tokens (like the Upgrade or Connection header)
+/// including the ones of repeated header lines and drops the empty elements
+pub(crate) fn parse_token_list(value: &str) -> Vec<String> {
+    value
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// decodes the body according to the Content-Encoding header. <br>
+/// There are no built-in decoders, so every coding except identity returns an [Unsupported] error
+pub(crate) fn decode_body<'a>(headers: &BTreeMap<String, String>, body: &'a str) -> Result<&'a [u8], HttpParseError> {
+    check_codings(headers, CONTENT_ENCODING, IDENTITY, UNSUPPORTED_CONTENT_CODING).map(|_| body.as_bytes())
+}
+
+/// the 64-bit FNV-1a hash, which is stable across platforms and versions
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME))
+}
+
+/// looks if the body is uncompressed text according to the Content-Type
+/// (text/*, application/json, application/*+json, application/xml, application/*+xml
+/// and application/x-www-form-urlencoded) and the absence of a Content-Encoding
+pub(crate) fn is_textual(headers: &BTreeMap<String, String>) -> bool {
+    let encoded = get_header_ignore_case(headers, CONTENT_ENCODING)
+        .is_some_and(|encoding| !encoding.trim().is_empty() && !encoding.trim().eq_ignore_ascii_case(IDENTITY));
+    let Some(content_type) = content_type(headers) else {
+        return false;
+    };
+    let essence = content_type.get_essence();
+    !encoded
+        && (essence.starts_with(TEXT_PREFIX)
+        || TEXTUAL_TYPES.contains(&essence)
+        || essence.starts_with(APPLICATION_PREFIX) && TEXTUAL_SUFFIXES.iter().any(|suffix| essence.ends_with(suffix)))
+}
+
+/// returns the (unquoted) charset parameter of the Content-Type header
+pub(crate) fn charset(headers: &BTreeMap<String, String>) -> Option<String> {
+    content_type(headers)?.charset().map(String::from)
+}
+
+/// Escapes the characters `&`, `<`, `>`, `"` and `'` of the string for HTML text and attribute values
+/// (for example in a custom [error page template](crate::resp_presets::set_error_page_template))
+///
+/// Example:
+/// ```
+/// use whdp::escape_html;
+///
+/// assert_eq!(escape_html("<a href=\"x\">Tom & Jerry's</a>"), "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;");
+/// ```
+pub fn escape_html(str: &str) -> String {
+    let mut escaped = String::with_capacity(str.len());
+    for char in str.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+/// Looks if every `%` in the string is followed by two hex digits,
+/// without decoding it (for example `/a%20b` is valid, `/a%2` and `/a%zz` aren't)
+///
+/// Example:
+/// ```
+/// use whdp::is_valid_percent_encoding;
+///
+/// assert!(is_valid_percent_encoding("/search?q=a%2Bb"));
+/// assert!(!is_valid_percent_encoding("/100%"));
+/// ```
+pub fn is_valid_percent_encoding(str: &str) -> bool {
+    let bytes = str.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let valid = bytes
+                .get(index + 1..index + 3)
+                .is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit));
+            if !valid {
+                return false;
+            }
+            index += 3;
+        } else {
+            index += 1;
+        }
+    }
+    true
+}
+
+pub(crate) fn is_hop_by_hop(key: &str) -> bool {
+    HOP_BY_HOP_HEADERS.iter().any(|name| name.eq_ignore_ascii_case(key))
+}
+
+/// returns the headers with lowercase names without the ignored ones (case-insensitive)
+pub(crate) fn normalized_headers<'a>(headers: &'a BTreeMap<String, String>, ignore: &[&str]) -> BTreeMap<String, &'a str> {
+    headers
+        .iter()
+        .filter(|(key, _)| !ignore.iter().any(|ignored| ignored.eq_ignore_ascii_case(key)))
+        .map(|(key, value)| (key.to_ascii_lowercase(), value.as_str()))
+        .collect()
+}
+
+pub(crate) fn strip_hop_by_hop(headers: &mut BTreeMap<String, String>) -> Vec<(String, String)> {
+    let nominated: Vec<String> = get_header_ignore_case(headers, CONNECTION)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|token| !token.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+    let keys: Vec<String> = headers
+        .keys()
+        .filter(|key| is_hop_by_hop(key) || nominated.iter().any(|token| token.eq_ignore_ascii_case(key)))
+        .cloned()
+        .collect();
+    keys.into_iter()
+        .filter_map(|key| headers.remove(&key).map(|value| (key, value)))
+        .collect()
+}
+
+pub(crate) fn is_sensitive(key: &str) -> bool {
+    SENSITIVE_HEADERS.iter().any(|name| name.eq_ignore_ascii_case(key))
+}
+
+/// checks only the bare minimum of a raw header line: exactly one colon with a name in front of it and no CR, LF or NUL
+pub(crate) fn check_raw_header_line(line: &str) -> Result<String, HttpParseError> {
+    let has_name = line.split_once(PORT_DELIMITER).is_some_and(|(name, _)| !name.trim().is_empty());
+    if !has_name || line.matches(PORT_DELIMITER).count() != 1 || line.contains(['\r', '\n', '\0']) {
+        return Err(HttpParseError::header(format!("{} {:?}", INVALID_RAW_HEADER_LINE, line)));
+    }
+    Ok(String::from(line))
+}
+
+pub(crate) fn to_wire_string(
+    start_line: &str,
+    headers: &BTreeMap<String, String>,
+    raw_lines: &[String],
+    body: &str,
+    include_secrets: bool,
+    line_ending: LineEnding,
+) -> String {
+    let line_ending = line_ending.as_str();
+    let mut string = String::from(start_line);
+    string.push_str(line_ending);
+    for (key, value) in headers {
+        for value in value.split(NEW_LINE) {
+            string.push_str(key);
+            string.push_str(KEY_VALUE_DELIMITER);
+            if include_secrets || !is_sensitive(key) {
+                string.push_str(value);
+            } else {
+                string.push_str(REDACTED);
+            }
+            string.push_str(line_ending);
+        }
+    }
+    for line in raw_lines {
+        match line.split_once(PORT_DELIMITER) {
+            Some((key, _)) if !include_secrets && is_sensitive(key.trim()) => {
+                string.push_str(key);
+                string.push_str(KEY_VALUE_DELIMITER);
+                string.push_str(REDACTED);
+            }
+            _ => string.push_str(line),
+        }
+        string.push_str(line_ending);
+    }
+    string.push_str(line_ending);
+    string.push_str(body);
+    string
+}
+
+pub(crate) fn check_http1_framing(version: &HttpVersion, force_http1: bool) -> Result<(), HttpParseError> {
+    match version {
+        HttpVersion::Two | HttpVersion::Three if !force_http1 => Err(HttpParseError::version(format!("{} {}", version, NO_HTTP1_WIRE_FORM))),
+        _ => Ok(()),
+    }
+}
+
+pub(crate) fn shell_quote(str: &str) -> String {
+    let mut string = String::from("'");
+    string.push_str(&str.replace('\'', "'\\''"));
+    string.push('\'');
+    string
+}
+
 pub(crate) fn parse_body(lines: &mut Lines) -> String {
     let mut string = String::new();
     let mut first = true;
@@ -69,20 +681,54 @@ pub(crate) fn parse_body(lines: &mut Lines) -> String {
     string
 }
 
-pub(crate) fn parse_header(lines: &mut Lines) -> Result<BTreeMap<String, String>, HttpParseError> {
+pub(crate) fn parse_header(
+    lines: &mut Lines,
+    options: &ParseOptions,
+) -> Result<(BTreeMap<String, String>, BTreeSet<String>), HttpParseError> {
     let mut map: BTreeMap<String, String> = BTreeMap::new();
+    let mut duplicates: BTreeSet<String> = BTreeSet::new();
     let mut opt_line = lines.next();
+    // the headers start after the meta data line
+    let mut line_number = 2;
     while opt_line.is_some() {
         let line = opt_line.unwrap();
         if !line.is_empty() {
-            let (key, val) = parse_key_value(line)?;
-            map.insert(key, val);
+            if let Some(limit) = options.get_max_headers().filter(|limit| line_number - 1 > *limit) {
+                return Err(HttpParseError::builder(Length)
+                    .message(format!("{} {}", TOO_MANY_HEADERS, limit))
+                    .line(line_number)
+                    .build());
+            }
+            let (key, val) = parse_key_value(line).map_err(|err| {
+                HttpParseError::builder(Util)
+                    .message(err.get_msg().unwrap_or(""))
+                    .line(line_number)
+                    .build()
+            })?;
+            let name = key.to_ascii_lowercase();
+            let policy = if options.is_joined_header(&key) {
+                DuplicatePolicy::Merge
+            } else {
+                DuplicatePolicy::of(&key)
+            };
+            let duplicate = map
+                .insert_with(key, val, policy, options.is_strict())
+                .map_err(|err| {
+                    HttpParseError::builder(*err.get_kind())
+                        .message(err.get_msg().unwrap_or(""))
+                        .line(line_number)
+                        .build()
+                })?;
+            if duplicate {
+                duplicates.insert(name);
+            }
             opt_line = lines.next();
+            line_number += 1;
         } else {
             opt_line = None
         }
     }
-    Ok(map)
+    Ok((map, duplicates))
 }
 
 pub(crate) fn parse_uri(str: Option<&str>) -> Result<String, HttpParseError> {
@@ -91,15 +737,10 @@ pub(crate) fn parse_uri(str: Option<&str>) -> Result<String, HttpParseError> {
 
 fn parse_key_value(str: &str) -> Result<(String, String), HttpParseError> {
     let mut key_value = str.split(KEY_VALUE_DELIMITER);
-    let key = key_value
-        .next()
-        .ok_or(error_option_empty(Util))
-        .map(String::from)?;
-    let value = key_value
-        .next()
-        .ok_or(error_option_empty(Util))
-        .map(String::from)?;
-    Ok((key, value))
+    match (key_value.next(), key_value.next()) {
+        (Some(key), Some(value)) => Ok((String::from(key), String::from(value))),
+        _ => Err(HttpParseError::header(format!("{} {:?}", MALFORMED_HEADER_LINE, str))),
+    }
 }
 
 pub(crate) fn error_option_empty(kind: ParseErrorKind) -> HttpParseError {
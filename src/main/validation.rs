@@ -0,0 +1,313 @@
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Display, Formatter};
+
+use crate::error::{HttpParseError, ParseErrorKind};
+use crate::status::HttpStatus;
+use crate::util::{get_header_ignore_case, is_chunked, CONTENT_LENGTH, TRANSFER_ENCODING};
+use crate::version::HttpVersion;
+
+const HOST: &str = "Host";
+const ISSUE_DELIMITER: &str = "; ";
+const PSEUDO_HEADER_PREFIX: char = ':';
+
+/// Enum for how serious a [ValidationIssue] is
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Severity {
+    /// the message is malformed and mustn't be sent
+    Error,
+    /// the message can be sent, but a recipient may handle it unexpectedly
+    Warning,
+}
+
+/// Enum for a mistake in an outgoing message found by
+/// [Request::validate_for_send](crate::Request::validate_for_send) or
+/// [Response::validate_for_send](crate::Response::validate_for_send). <br>
+/// More checks are added over time, so matching on it needs a wildcard arm
+#[non_exhaustive]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ValidationIssue {
+    /// a HTTP/1.1 Request without a Host header
+    MissingHost,
+    /// a Response with a status that forbids a body (1xx, 204 and 304), but has one
+    BodyNotAllowed(u16),
+    /// the Content-Length header doesn't match the body
+    /// (only a [Warning](Severity::Warning) for a Response with an empty body, which may answer a HEAD Request)
+    ContentLengthMismatch {
+        /// the length of the Content-Length header
+        declared: String,
+        /// the actual length of the body
+        actual: usize,
+    },
+    /// the header name isn't a token or the value contains a CR or NUL
+    /// (a LF separates the lines of a repeated header)
+    InvalidHeader(String),
+    /// the uri contains raw whitespace
+    UriWithWhitespace,
+    /// a chunked Transfer-Encoding on a HTTP/1.0 message, which doesn't know it
+    ChunkedOnHttpOne,
+    /// both a Content-Length and a Transfer-Encoding header (the Content-Length gets ignored)
+    ConflictingFraming,
+    /// any other issue with its severity and message
+    Other(Severity, String),
+}
+
+impl ValidationIssue {
+    /// returns how serious the issue is
+    pub fn severity(&self) -> Severity {
+        match self {
+            ValidationIssue::ConflictingFraming => Severity::Warning,
+            ValidationIssue::ContentLengthMismatch { actual: 0, .. } => Severity::Warning,
+            ValidationIssue::Other(severity, _) => *severity,
+            _ => Severity::Error,
+        }
+    }
+    /// looks if the issue is an [Error](Severity::Error)
+    pub fn is_error(&self) -> bool {
+        self.severity() == Severity::Error
+    }
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationIssue::MissingHost => write!(f, "A HTTP/1.1 Request needs a Host header"),
+            ValidationIssue::BodyNotAllowed(code) => write!(f, "A Response with the status {} mustn't have a body", code),
+            ValidationIssue::ContentLengthMismatch { declared, actual } => {
+                write!(f, "The Content-Length is {} but the body has {} bytes", declared, actual)
+            }
+            ValidationIssue::InvalidHeader(key) => write!(f, "The header {:?} has an invalid name or value", key),
+            ValidationIssue::UriWithWhitespace => write!(f, "The uri contains raw whitespace"),
+            ValidationIssue::ChunkedOnHttpOne => write!(f, "HTTP/1.0 doesn't support a chunked Transfer-Encoding"),
+            ValidationIssue::ConflictingFraming => {
+                write!(f, "The Content-Length is ignored because of the Transfer-Encoding")
+            }
+            ValidationIssue::Other(_, msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl Debug for ValidationIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.severity(), self)
+    }
+}
+
+/// collects the issues both Requests and Responses can have
+pub(crate) fn framing_issues(version: &HttpVersion, headers: &BTreeMap<String, String>, body: &str) -> Vec<ValidationIssue> {
+    let http1 = matches!(version, HttpVersion::One | HttpVersion::OnePointOne);
+    let mut issues: Vec<ValidationIssue> = headers
+        .iter()
+        .filter(|(key, _)| http1 || !key.starts_with(PSEUDO_HEADER_PREFIX))
+        .filter(|(key, value)| !is_valid_header(key, value))
+        .map(|(key, _)| ValidationIssue::InvalidHeader(key.clone()))
+        .collect();
+    let content_length = get_header_ignore_case(headers, CONTENT_LENGTH);
+    if let Some(declared) = content_length {
+        if declared.trim().parse::<usize>().ok() != Some(body.len()) {
+            issues.push(ValidationIssue::ContentLengthMismatch {
+                declared: declared.clone(),
+                actual: body.len(),
+            });
+        }
+    }
+    if content_length.is_some() && get_header_ignore_case(headers, TRANSFER_ENCODING).is_some() {
+        issues.push(ValidationIssue::ConflictingFraming);
+    }
+    if *version == HttpVersion::One && is_chunked(headers) {
+        issues.push(ValidationIssue::ChunkedOnHttpOne);
+    }
+    issues
+}
+
+/// collects the issues of a Request
+pub(crate) fn request_issues(
+    uri: &str,
+    version: &HttpVersion,
+    headers: &BTreeMap<String, String>,
+    body: &str,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    if *version == HttpVersion::OnePointOne && get_header_ignore_case(headers, HOST).is_none() {
+        issues.push(ValidationIssue::MissingHost);
+    }
+    if uri.contains(char::is_whitespace) {
+        issues.push(ValidationIssue::UriWithWhitespace);
+    }
+    issues.extend(framing_issues(version, headers, body));
+    issues
+}
+
+/// collects the issues of a Response
+pub(crate) fn response_issues(
+    status: &HttpStatus,
+    version: &HttpVersion,
+    headers: &BTreeMap<String, String>,
+    body: &str,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    if !status.allows_body() && !body.is_empty() {
+        issues.push(ValidationIssue::BodyNotAllowed(*status.get_code()));
+    }
+    issues.extend(framing_issues(version, headers, body));
+    issues
+}
+
+/// returns the issues if there are any
+pub(crate) fn into_result(issues: Vec<ValidationIssue>) -> Result<(), Vec<ValidationIssue>> {
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        Err(issues)
+    }
+}
+
+/// returns an error of the kind with all the [Error](Severity::Error) issues (warnings are ignored)
+pub(crate) fn check_issues(issues: Vec<ValidationIssue>, kind: ParseErrorKind) -> Result<(), HttpParseError> {
+    let errors: Vec<String> = issues
+        .iter()
+        .filter(|issue| issue.is_error())
+        .map(ToString::to_string)
+        .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(HttpParseError::from((kind, errors.join(ISSUE_DELIMITER))))
+}
+
+fn is_valid_header(key: &str, value: &str) -> bool {
+    let token = !key.is_empty() && key.bytes().all(|byte| byte.is_ascii_graphic() && byte != b':');
+    token && !value.contains(['\r', '\0'])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HttpMethod, HttpStatus, HttpVersion, ParseErrorKind, Request, resp_presets, Response, Severity, ValidationIssue};
+
+    fn request_issues(req: &str) -> Vec<ValidationIssue> {
+        Request::try_from(req).unwrap().validate_for_send().err().unwrap_or_default()
+    }
+
+    fn response_issues(resp: &Response) -> Vec<ValidationIssue> {
+        resp.validate_for_send().err().unwrap_or_default()
+    }
+
+    #[test]
+    fn valid() {
+        assert!(request_issues("GET / HTTP/1.1\nHost: example.com\n\n").is_empty());
+        assert!(request_issues("GET / HTTP/1.0\n\n").is_empty());
+        assert!(response_issues(&resp_presets::ok("body")).is_empty());
+    }
+
+    #[test]
+    fn missing_host() {
+        assert_eq!(request_issues("GET / HTTP/1.1\n\n"), [ValidationIssue::MissingHost]);
+    }
+
+    #[test]
+    fn body_not_allowed() {
+        let resp = resp_presets::ok("body").with_status(HttpStatus::try_from(204).unwrap());
+        assert_eq!(response_issues(&resp), [ValidationIssue::BodyNotAllowed(204)]);
+        assert!(response_issues(&resp_presets::ok("").with_status(HttpStatus::try_from(204).unwrap())).is_empty());
+    }
+
+    #[test]
+    fn content_length_mismatch() {
+        let issues = request_issues("POST / HTTP/1.0\nContent-Length: 10\n\nbody");
+        assert_eq!(issues, [ValidationIssue::ContentLengthMismatch { declared: String::from("10"), actual: 4 }]);
+        assert_eq!(issues[0].severity(), Severity::Error);
+        assert_eq!(issues[0].to_string(), "The Content-Length is 10 but the body has 4 bytes");
+
+        let mut resp = resp_presets::ok("");
+        resp.add_header((String::from("Content-Length"), String::from("10")));
+        assert_eq!(response_issues(&resp)[0].severity(), Severity::Warning);
+    }
+
+    #[test]
+    fn invalid_header() {
+        let mut resp = resp_presets::ok("body");
+        resp.add_header((String::from("X-Injected"), String::from("a\r\nSet-Cookie: evil")));
+        assert_eq!(response_issues(&resp), [ValidationIssue::InvalidHeader(String::from("X-Injected"))]);
+
+        let mut resp = resp_presets::ok("body");
+        resp.add_header((String::from("Bad Name"), String::from("value")));
+        assert_eq!(response_issues(&resp), [ValidationIssue::InvalidHeader(String::from("Bad Name"))]);
+    }
+
+    #[test]
+    fn uri_with_whitespace() {
+        let req = Request::builder()
+            .with_method(HttpMethod::Get)
+            .with_uri("/a b")
+            .with_version(HttpVersion::One)
+            .with_empty_headers()
+            .with_body("")
+            .build()
+            .unwrap();
+        assert_eq!(req.validate_for_send().unwrap_err(), [ValidationIssue::UriWithWhitespace]);
+    }
+
+    #[test]
+    fn chunked_on_http_one() {
+        let issues = request_issues("POST / HTTP/1.0\nTransfer-Encoding: chunked\n\n");
+        assert_eq!(issues, [ValidationIssue::ChunkedOnHttpOne]);
+    }
+
+    #[test]
+    fn conflicting_framing() {
+        let issues = request_issues("POST / HTTP/1.1\nHost: a\nContent-Length: 0\nTransfer-Encoding: chunked\n\n");
+        assert_eq!(issues, [ValidationIssue::ConflictingFraming]);
+        assert!(!issues[0].is_error());
+    }
+
+    #[test]
+    fn write_to() {
+        let mut bytes = Vec::new();
+        let mut req = Request::try_from("GET / HTTP/1.1\n\n").unwrap();
+        let err = req.write_to(&mut bytes).unwrap_err();
+        assert_eq!(err.get_kind(), &ParseErrorKind::Req);
+        assert_eq!(err.get_msg(), Some("A HTTP/1.1 Request needs a Host header"));
+        assert!(bytes.is_empty());
+        req.skip_send_validation().write_to(&mut bytes).unwrap();
+        assert_eq!(bytes, b"GET / HTTP/1.1\r\n\r\n");
+
+        let mut resp = resp_presets::ok("");
+        resp.add_header((String::from("Content-Length"), String::from("10")));
+        assert!(resp.write_to(&mut Vec::new()).is_ok());
+        resp.add_header((String::from("X-Bad"), String::from("\r")));
+        assert_eq!(resp.write_to(&mut Vec::new()).unwrap_err().get_kind(), &ParseErrorKind::Resp);
+    }
+
+    #[test]
+    fn preset_content_length() {
+        use std::io::Write;
+
+        let mut resp = resp_presets::ok("a");
+        resp.set_body("abc");
+        assert_eq!(resp.get_header("Content-Length").unwrap(), "3");
+        resp.append_body("de");
+        resp.append_body_from(&mut "fg".as_bytes()).unwrap();
+        write!(resp.body_writer(), "h").unwrap();
+        assert_eq!(resp.get_header("Content-Length").unwrap(), "8");
+        assert!(resp.write_to(&mut Vec::new()).is_ok());
+
+        // a Content-Length that didn't match the body is deliberate and kept
+        let mut resp = resp_presets::ok("a");
+        resp.add_header((String::from("Content-Length"), String::from("10")));
+        resp.set_body("abc");
+        assert_eq!(resp.get_header("Content-Length").unwrap(), "10");
+        assert!(resp.write_to(&mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn with_body_content_length() {
+        let mut resp = resp_presets::ok("a");
+        resp.enable_header_journal();
+        let resp = resp.with_body("abc");
+        assert_eq!(resp.get_header("Content-Length").unwrap(), "3");
+        assert_eq!(resp.header_journal()[0].to_string(), "Set Content-Length: 1 -> 3");
+        assert!(resp.write_to(&mut Vec::new()).is_ok());
+
+        let resp = resp_presets::ok("a").with_added_header("Content-Length", "10").with_body("abc");
+        assert_eq!(resp.get_header("Content-Length").unwrap(), "10");
+    }
+}
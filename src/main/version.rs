@@ -30,7 +30,7 @@ impl FromStr for HttpVersion {
             .iter()
             .position(|&idx| idx.eq_ignore_ascii_case(s))
             .map(HttpVersion::try_from)
-            .ok_or(HttpParseError::from((Version, NAME_NOT_EXIST)))?
+            .ok_or(HttpParseError::version(NAME_NOT_EXIST))?
     }
 }
 
@@ -56,7 +56,7 @@ impl TryFrom<usize> for HttpVersion {
             1 => Ok(HttpVersion::OnePointOne),
             2 => Ok(HttpVersion::Two),
             3 => Ok(HttpVersion::Three),
-            _ => Err(HttpParseError::from((Version,INDEX_WAS_WRONG))),
+            _ => Err(HttpParseError::version(INDEX_WAS_WRONG)),
         }
     }
 }
@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+
+use crate::util::{get_header_ignore_case, parse_token_list, UPGRADE};
+
+const WEBSOCKET: &str = "websocket";
+const SEC_WEBSOCKET_KEY: &str = "Sec-WebSocket-Key";
+const SEC_WEBSOCKET_VERSION: &str = "Sec-WebSocket-Version";
+const SEC_WEBSOCKET_PROTOCOL: &str = "Sec-WebSocket-Protocol";
+const SEC_WEBSOCKET_EXTENSIONS: &str = "Sec-WebSocket-Extensions";
+
+/// Struct for the details of the opening handshake of a WebSocket
+/// ([RFC 6455](https://www.rfc-editor.org/rfc/rfc6455#section-4.2.1)) requested by a Request. <br>
+/// The key and the version are required, the protocols and extensions are in the order of the client's preference
+/// (a repeated header adds its elements at the end).
+/// Nothing is validated beyond that, so a server still has to check the version and the length of the key
+///
+/// Example:
+/// ```
+/// use whdp::Request;
+///
+/// let req = Request::try_from(
+///     "GET /chat HTTP/1.1\nUpgrade: websocket\nConnection: Upgrade\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\nSec-WebSocket-Version: 13\nSec-WebSocket-Protocol: chat, superchat\n\n",
+/// ).unwrap();
+/// let handshake = req.websocket_handshake().unwrap();
+/// assert_eq!(handshake.get_key(), "dGhlIHNhbXBsZSBub25jZQ==");
+/// assert_eq!(handshake.get_version(), 13);
+/// assert_eq!(handshake.get_protocols(), ["chat", "superchat"]);
+/// assert!(handshake.get_extensions().is_empty());
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct WebSocketHandshake {
+    key: String,
+    version: u8,
+    protocols: Vec<String>,
+    extensions: Vec<String>,
+}
+
+impl WebSocketHandshake {
+    /// Get the value of the Sec-WebSocket-Key header
+    pub fn get_key(&self) -> &str {
+        &self.key
+    }
+    /// Get the value of the Sec-WebSocket-Version header
+    pub fn get_version(&self) -> u8 {
+        self.version
+    }
+    /// Get the subprotocols of the Sec-WebSocket-Protocol header
+    pub fn get_protocols(&self) -> &[String] {
+        &self.protocols
+    }
+    /// Get the extensions (with their parameters) of the Sec-WebSocket-Extensions header
+    pub fn get_extensions(&self) -> &[String] {
+        &self.extensions
+    }
+}
+
+/// parses the handshake from the headers, if the Upgrade header names websocket
+/// and the key and the version are present
+pub(crate) fn parse_handshake(headers: &BTreeMap<String, String>) -> Option<WebSocketHandshake> {
+    let upgrades = get_header_ignore_case(headers, UPGRADE).map(|value| parse_token_list(value))?;
+    if !upgrades.iter().any(|upgrade| upgrade.eq_ignore_ascii_case(WEBSOCKET)) {
+        return None;
+    }
+    let key = get_header_ignore_case(headers, SEC_WEBSOCKET_KEY)
+        .map(|key| key.trim())
+        .filter(|key| !key.is_empty())?;
+    let version = get_header_ignore_case(headers, SEC_WEBSOCKET_VERSION)?.trim().parse().ok()?;
+    Some(WebSocketHandshake {
+        key: String::from(key),
+        version,
+        protocols: get_header_ignore_case(headers, SEC_WEBSOCKET_PROTOCOL).map_or_else(Vec::new, |value| parse_token_list(value)),
+        extensions: get_header_ignore_case(headers, SEC_WEBSOCKET_EXTENSIONS).map_or_else(Vec::new, |value| parse_token_list(value)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Request;
+
+    const HANDSHAKE: &str = "GET /chat HTTP/1.1\nHost: a\nUpgrade: WebSocket\nConnection: Upgrade\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\nSec-WebSocket-Version: 13\n";
+
+    #[test]
+    fn handshake() {
+        let req = Request::try_from(
+            format!("{}Sec-WebSocket-Protocol: chat\nSec-WebSocket-Protocol: superchat\nSec-WebSocket-Extensions: permessage-deflate; client_max_window_bits, x-webkit\n\n", HANDSHAKE)
+                .as_str(),
+        )
+        .unwrap();
+        let handshake = req.websocket_handshake().unwrap();
+        assert_eq!(handshake.get_key(), "dGhlIHNhbXBsZSBub25jZQ==");
+        assert_eq!(handshake.get_version(), 13);
+        assert_eq!(handshake.get_protocols(), ["chat", "superchat"]);
+        assert_eq!(handshake.get_extensions(), ["permessage-deflate; client_max_window_bits", "x-webkit"]);
+    }
+
+    #[test]
+    fn missing_fields() {
+        assert!(Request::try_from(format!("{}\n", HANDSHAKE).as_str()).unwrap().websocket_handshake().is_some());
+        for missing in ["Upgrade", "Sec-WebSocket-Key", "Sec-WebSocket-Version"] {
+            let mut req = Request::try_from(format!("{}\n", HANDSHAKE).as_str()).unwrap();
+            req.retain_headers(|key, _| key != missing);
+            assert_eq!(req.websocket_handshake(), None, "{}", missing);
+        }
+        let req = Request::try_from("GET / HTTP/1.1\nUpgrade: h2c\nSec-WebSocket-Key: a\nSec-WebSocket-Version: 13\n\n").unwrap();
+        assert_eq!(req.websocket_handshake(), None);
+        let req = Request::try_from("GET / HTTP/1.1\nUpgrade: websocket\nSec-WebSocket-Key: a\nSec-WebSocket-Version: x\n\n").unwrap();
+        assert_eq!(req.websocket_handshake(), None);
+    }
+}